@@ -0,0 +1,194 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Seek, SeekFrom, Write},
+};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use commandblock::nbt::{read_chunk, Compression, Endian, NbtReader, NbtValue, NbtWriter};
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkGroup, Criterion, Throughput,
+};
+
+/// The chunk the chunk benchmarks parse and write, stored the way region files store it.
+///
+/// The fixture follows the vanilla 1.19.3 layout, with 24 sections of terrain, ores, water and air, packed
+/// block states and biomes, light, heightmaps and a block entity. It was laid out from the format description
+/// by a script separate from this crate, as no world saved by the game is in the repository.
+const CHUNK_PATH: &str = "tests/data/java_chunk.dat";
+
+fn chunk() -> NbtValue {
+    let (chunk, compression) = read_chunk(File::open(CHUNK_PATH).unwrap()).unwrap();
+    assert_eq!(compression, Compression::Zlib);
+    chunk
+}
+
+fn encode(value: &NbtValue) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    NbtWriter::new(&mut bytes, Endian::Big)
+        .write_nbt_value(value)
+        .unwrap();
+    bytes
+}
+
+/// The element by element loop the reader used before bulk reads, kept as a baseline.
+fn read_long_array_per_element(mut bytes: &[u8]) -> Vec<i64> {
+    let length = bytes.read_i32::<BigEndian>().unwrap() as usize;
+    let mut array = Vec::with_capacity(length);
+    for _ in 0..length {
+        array.push(bytes.read_i64::<BigEndian>().unwrap());
+    }
+    array
+}
+
+/// Parses a value the way the reader did before bulk reads, with every array element read on its own, kept as
+/// a baseline for whole chunks.
+fn parse_per_element(bytes: &mut &[u8], tag: u8) -> NbtValue {
+    let string = |bytes: &mut &[u8]| {
+        let length = bytes.read_u16::<BigEndian>().unwrap() as usize;
+        let (string, rest) = bytes.split_at(length);
+        *bytes = rest;
+        String::from_utf8(string.to_vec()).unwrap()
+    };
+
+    match tag {
+        0x01 => NbtValue::Byte(bytes.read_i8().unwrap()),
+        0x02 => NbtValue::Short(bytes.read_i16::<BigEndian>().unwrap()),
+        0x03 => NbtValue::Int(bytes.read_i32::<BigEndian>().unwrap()),
+        0x04 => NbtValue::Long(bytes.read_i64::<BigEndian>().unwrap()),
+        0x05 => NbtValue::Float(bytes.read_f32::<BigEndian>().unwrap()),
+        0x06 => NbtValue::Double(bytes.read_f64::<BigEndian>().unwrap()),
+        0x07 => {
+            let length = bytes.read_i32::<BigEndian>().unwrap();
+            NbtValue::ByteArray((0..length).map(|_| bytes.read_i8().unwrap()).collect())
+        }
+        0x08 => NbtValue::String(string(bytes)),
+        0x09 => {
+            let element_type = bytes.read_u8().unwrap();
+            let length = bytes.read_i32::<BigEndian>().unwrap();
+            NbtValue::List(
+                (0..length)
+                    .map(|_| parse_per_element(bytes, element_type))
+                    .collect(),
+            )
+        }
+        0x0A => {
+            let mut map = HashMap::new();
+            loop {
+                let tag = bytes.read_u8().unwrap();
+                if tag == 0x00 {
+                    break;
+                }
+                let key = string(bytes);
+                map.insert(key, parse_per_element(bytes, tag));
+            }
+            NbtValue::Compound(map)
+        }
+        0x0B => {
+            let length = bytes.read_i32::<BigEndian>().unwrap();
+            NbtValue::IntArray(
+                (0..length)
+                    .map(|_| bytes.read_i32::<BigEndian>().unwrap())
+                    .collect(),
+            )
+        }
+        0x0C => {
+            let length = bytes.read_i32::<BigEndian>().unwrap();
+            NbtValue::LongArray(
+                (0..length)
+                    .map(|_| bytes.read_i64::<BigEndian>().unwrap())
+                    .collect(),
+            )
+        }
+        tag => panic!("unexpected tag {}", tag),
+    }
+}
+
+/// The element by element loop the writer used before buffered writes, kept as a baseline.
+fn write_long_array_per_element<W: Write>(array: &[i64], out: &mut W) {
+    out.write_i32::<BigEndian>(array.len() as i32).unwrap();
+    for item in array {
+        out.write_i64::<BigEndian>(*item).unwrap();
+    }
+}
+
+fn bench_long_arrays(group: &mut BenchmarkGroup<criterion::measurement::WallTime>) {
+    let array: Vec<i64> = (0..4096).map(|i| i * 0x0123_4567_89AB).collect();
+    let value = NbtValue::LongArray(array.clone());
+    let bytes = encode(&value);
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+
+    group.bench_function("read_long_array/per_element", |b| {
+        b.iter(|| read_long_array_per_element(black_box(&bytes)))
+    });
+    group.bench_function("read_long_array/bulk", |b| {
+        b.iter(|| {
+            NbtReader::new(black_box(&bytes[..]), Endian::Big)
+                .parse_nbt_value(0x0C)
+                .unwrap()
+        })
+    });
+
+    // Writes go straight to a file, as `write_to_file` does for uncompressed data, so every write is a
+    // system call.
+    let path = std::env::temp_dir().join("commandblock_bench_arrays.dat");
+    let mut file = File::create(&path).unwrap();
+    group.bench_function("write_long_array/per_element", |b| {
+        b.iter(|| {
+            file.seek(SeekFrom::Start(0)).unwrap();
+            write_long_array_per_element(black_box(&array), &mut file);
+        })
+    });
+    group.bench_function("write_long_array/buffered", |b| {
+        b.iter(|| {
+            file.seek(SeekFrom::Start(0)).unwrap();
+            NbtWriter::new(&mut file, Endian::Big)
+                .write_nbt_value(black_box(&value))
+                .unwrap();
+        })
+    });
+    std::fs::remove_file(path).ok();
+}
+
+fn bench_chunk(group: &mut BenchmarkGroup<criterion::measurement::WallTime>) {
+    let chunk = chunk();
+    let bytes = encode(&chunk);
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+
+    let stored = std::fs::read(CHUNK_PATH).unwrap();
+    group.bench_function("chunk/read_chunk", |b| {
+        b.iter(|| read_chunk(black_box(&stored[..])).unwrap())
+    });
+
+    assert_eq!(parse_per_element(&mut &bytes[..], 0x0A), chunk);
+    group.bench_function("chunk/parse_per_element", |b| {
+        b.iter(|| parse_per_element(&mut black_box(&bytes[..]), 0x0A))
+    });
+    group.bench_function("chunk/parse", |b| {
+        b.iter(|| {
+            NbtReader::new(black_box(&bytes[..]), Endian::Big)
+                .parse_nbt_value(0x0A)
+                .unwrap()
+        })
+    });
+
+    let mut out = Vec::with_capacity(bytes.len());
+    group.bench_function("chunk/write", |b| {
+        b.iter(|| {
+            out.clear();
+            NbtWriter::new(&mut out, Endian::Big)
+                .write_nbt_value(black_box(&chunk))
+                .unwrap();
+        })
+    });
+}
+
+fn arrays(c: &mut Criterion) {
+    let mut group = c.benchmark_group("arrays");
+    bench_long_arrays(&mut group);
+    bench_chunk(&mut group);
+    group.finish();
+}
+
+criterion_group!(benches, arrays);
+criterion_main!(benches);
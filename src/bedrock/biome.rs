@@ -0,0 +1,315 @@
+use std::io::Read;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use crate::nbt::NbtError;
+
+use super::subchunk::{
+    block_index, compact, invalid, read_indices, read_palette_size, write_indices, BLOCK_COUNT,
+};
+
+/// The number of columns in a chunk, 16 by 16.
+const COLUMN_COUNT: usize = 16 * 16;
+
+/// The bits per value Data3D uses to mark a subchunk whose biomes are the same as the one below it.
+const SAME_AS_BELOW: u8 = 0x7F;
+
+/// Returns the index of a column in a heightmap or Data2D biome array, which are stored in Z, then X order.
+fn column_index(x: usize, z: usize) -> usize {
+    assert!(x < 16 && z < 16, "column position out of range");
+    (z << 4) | x
+}
+
+fn read_heights(data: &mut &[u8]) -> Result<[i16; COLUMN_COUNT], NbtError> {
+    let mut heights = [0i16; COLUMN_COUNT];
+    data.read_i16_into::<LittleEndian>(&mut heights)?;
+    Ok(heights)
+}
+
+fn write_heights(bytes: &mut Vec<u8>, heights: &[i16; COLUMN_COUNT]) -> Result<(), NbtError> {
+    for &height in heights {
+        bytes.write_i16::<LittleEndian>(height)?;
+    }
+    Ok(())
+}
+
+/// `Data2D` is the heightmap and biomes of a chunk as stored under a `Data2D` key before 1.18, with a single
+/// biome id per column.
+///
+/// Heights are stored as the game wrote them, relative to the bottom of the world.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Data2D {
+    heights: [i16; COLUMN_COUNT],
+    biomes: [u8; COLUMN_COUNT],
+}
+
+impl Data2D {
+    /// Creates a `Data2D` with every height set to 0 and every column set to the given biome id.
+    pub fn new(biome: u8) -> Self {
+        Data2D {
+            heights: [0; COLUMN_COUNT],
+            biomes: [biome; COLUMN_COUNT],
+        }
+    }
+
+    /// Parses a `Data2D` record: 256 little endian heights followed by 256 biome ids.
+    ///
+    /// # Arguments
+    ///
+    /// * `data: &[u8]` - The value stored under the chunk's `Data2D` key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Data2D)` - The parsed heightmap and biomes.
+    /// * `Err(NbtError)` - `UnexpectedEof` if the data is too short.
+    pub fn parse(data: &[u8]) -> Result<Data2D, NbtError> {
+        let mut data = data;
+        let heights = read_heights(&mut data)?;
+        let mut biomes = [0u8; COLUMN_COUNT];
+        data.read_exact(&mut biomes)?;
+        Ok(Data2D { heights, biomes })
+    }
+
+    /// Encodes the heightmap and biomes as they are stored under the chunk's `Data2D` key.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, NbtError> {
+        let mut bytes = Vec::with_capacity(COLUMN_COUNT * 3);
+        write_heights(&mut bytes, &self.heights)?;
+        bytes.extend_from_slice(&self.biomes);
+        Ok(bytes)
+    }
+
+    /// Returns the height of the given column. Panics if a coordinate is not below 16.
+    pub fn height(&self, x: usize, z: usize) -> i16 {
+        self.heights[column_index(x, z)]
+    }
+
+    /// Sets the height of the given column. Panics if a coordinate is not below 16.
+    pub fn set_height(&mut self, x: usize, z: usize, height: i16) {
+        self.heights[column_index(x, z)] = height;
+    }
+
+    /// Returns the biome id of the given column. Panics if a coordinate is not below 16.
+    pub fn biome(&self, x: usize, z: usize) -> u8 {
+        self.biomes[column_index(x, z)]
+    }
+
+    /// Sets the biome id of the given column. Panics if a coordinate is not below 16.
+    pub fn set_biome(&mut self, x: usize, z: usize, biome: u8) {
+        self.biomes[column_index(x, z)] = biome;
+    }
+}
+
+/// `BiomeStorage` is the biomes of a 16 by 16 by 16 subchunk: a palette of biome ids and the palette index of
+/// every block, in the same layout as a `BlockStorage`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiomeStorage {
+    palette: Vec<i32>,
+    indices: Vec<u16>,
+}
+
+impl BiomeStorage {
+    /// Creates a biome storage with every block set to the given biome id.
+    pub fn new(biome: i32) -> Self {
+        BiomeStorage {
+            palette: vec![biome],
+            indices: vec![0; BLOCK_COUNT],
+        }
+    }
+
+    /// Reads a biome storage, returning `None` for the marker that repeats the storage below.
+    fn parse(data: &mut &[u8]) -> Result<Option<BiomeStorage>, NbtError> {
+        let bits = data.read_u8()? >> 1;
+        if bits == SAME_AS_BELOW {
+            return Ok(None);
+        }
+
+        let indices = read_indices(data, bits)?;
+        let palette_size = read_palette_size(data, bits, &indices)?;
+        let mut palette = vec![0i32; palette_size];
+        data.read_i32_into::<LittleEndian>(&mut palette)?;
+
+        Ok(Some(BiomeStorage { palette, indices }))
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) -> Result<(), NbtError> {
+        let (palette, indices) = compact(&self.palette, &self.indices);
+        write_indices(bytes, &indices, palette.len(), 0)?;
+        for &biome in palette {
+            bytes.write_i32::<LittleEndian>(biome)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the palette of biome ids.
+    pub fn palette(&self) -> &[i32] {
+        &self.palette
+    }
+
+    /// Returns the biome id at the given position within the subchunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a coordinate is not below 16.
+    pub fn get(&self, x: usize, y: usize, z: usize) -> i32 {
+        assert!(x < 16 && y < 16 && z < 16, "block position out of range");
+        self.palette[self.indices[block_index(x, y, z)] as usize]
+    }
+
+    /// Sets the biome id at the given position within the subchunk, adding it to the palette if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a coordinate is not below 16.
+    pub fn set(&mut self, x: usize, y: usize, z: usize, biome: i32) {
+        assert!(x < 16 && y < 16 && z < 16, "block position out of range");
+        let index = match self.palette.iter().position(|&entry| entry == biome) {
+            Some(index) => index,
+            None => {
+                self.palette.push(biome);
+                self.palette.len() - 1
+            }
+        };
+        self.indices[block_index(x, y, z)] = index as u16;
+    }
+}
+
+/// `Data3D` is the heightmap and biomes of a chunk as stored under a `Data3D` key since 1.18, with a biome
+/// storage for each subchunk from the bottom of the world up.
+///
+/// Heights are stored as the game wrote them, relative to the bottom of the world. Subchunks whose biomes
+/// are the same as the subchunk below are stored as a marker, and are expanded into their own storage when
+/// parsed.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::bedrock::Data3D;
+///
+/// // The overworld has 24 subchunks, from Y -64 to 320.
+/// let mut data = Data3D::new(24, 1);
+/// data.set_biome(0, 80, 0, 7).unwrap();
+///
+/// let data = Data3D::parse(&data.to_bytes().unwrap()).unwrap();
+/// assert_eq!(data.biome(0, 80, 0), Some(7));
+/// assert_eq!(data.biome(0, 79, 0), Some(1));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Data3D {
+    heights: [i16; COLUMN_COUNT],
+    biomes: Vec<BiomeStorage>,
+}
+
+impl Data3D {
+    /// Creates a `Data3D` with every height set to 0 and `sub_chunks` biome storages set to the given biome id.
+    pub fn new(sub_chunks: usize, biome: i32) -> Self {
+        Data3D {
+            heights: [0; COLUMN_COUNT],
+            biomes: vec![BiomeStorage::new(biome); sub_chunks],
+        }
+    }
+
+    /// Parses a `Data3D` record: 256 little endian heights followed by a biome storage for each subchunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `data: &[u8]` - The value stored under the chunk's `Data3D` key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Data3D)` - The parsed heightmap and biomes.
+    /// * `Err(NbtError)` - `InvalidBedrockData` if a biome storage is invalid, or `UnexpectedEof` if the data
+    ///   is truncated.
+    pub fn parse(data: &[u8]) -> Result<Data3D, NbtError> {
+        let mut data = data;
+        let heights = read_heights(&mut data)?;
+
+        let mut biomes: Vec<BiomeStorage> = Vec::new();
+        while !data.is_empty() {
+            let storage = match BiomeStorage::parse(&mut data)? {
+                Some(storage) => storage,
+                None => biomes
+                    .last()
+                    .cloned()
+                    .ok_or_else(|| invalid("the lowest biome storage repeats the one below it"))?,
+            };
+            biomes.push(storage);
+        }
+
+        Ok(Data3D { heights, biomes })
+    }
+
+    /// Encodes the heightmap and biomes as they are stored under the chunk's `Data3D` key, writing a marker for
+    /// each biome storage that is the same as the one below it.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, NbtError> {
+        let mut bytes = Vec::new();
+        write_heights(&mut bytes, &self.heights)?;
+
+        let mut below = None;
+        for storage in &self.biomes {
+            if below == Some(storage) {
+                bytes.push(SAME_AS_BELOW << 1 | 1);
+            } else {
+                storage.write(&mut bytes)?;
+            }
+            below = Some(storage);
+        }
+        Ok(bytes)
+    }
+
+    /// Returns the height of the given column. Panics if a coordinate is not below 16.
+    pub fn height(&self, x: usize, z: usize) -> i16 {
+        self.heights[column_index(x, z)]
+    }
+
+    /// Sets the height of the given column. Panics if a coordinate is not below 16.
+    pub fn set_height(&mut self, x: usize, z: usize, height: i16) {
+        self.heights[column_index(x, z)] = height;
+    }
+
+    /// Returns the biome storage of each subchunk, from the bottom of the world up.
+    pub fn biomes(&self) -> &[BiomeStorage] {
+        &self.biomes
+    }
+
+    /// Returns the biome storage of each subchunk for editing.
+    pub fn biomes_mut(&mut self) -> &mut Vec<BiomeStorage> {
+        &mut self.biomes
+    }
+
+    /// Returns the biome id at the given position.
+    ///
+    /// # Arguments
+    ///
+    /// * `x: usize` - The X position within the chunk, from 0 to 15.
+    /// * `y: usize` - The height above the bottom of the world, so 0 is Y -64 in the overworld.
+    /// * `z: usize` - The Z position within the chunk, from 0 to 15.
+    ///
+    /// # Returns
+    ///
+    /// The biome id, or `None` if the position is outside the chunk or above the stored subchunks.
+    pub fn biome(&self, x: usize, y: usize, z: usize) -> Option<i32> {
+        if x >= 16 || z >= 16 {
+            return None;
+        }
+        let storage = self.biomes.get(y / 16)?;
+        Some(storage.get(x, y % 16, z))
+    }
+
+    /// Sets the biome id at the given position, with `y` counted from the bottom of the world as in `biome`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the biome was set.
+    /// * `Err(NbtError)` - `InvalidBedrockData` if the position is outside the chunk or the stored subchunks.
+    pub fn set_biome(&mut self, x: usize, y: usize, z: usize, biome: i32) -> Result<(), NbtError> {
+        if x >= 16 || z >= 16 {
+            return Err(invalid("biome position out of range"));
+        }
+        let storage = self
+            .biomes
+            .get_mut(y / 16)
+            .ok_or_else(|| invalid(&format!("no biome storage for height {}", y)))?;
+        storage.set(x, y % 16, z, biome);
+        Ok(())
+    }
+}
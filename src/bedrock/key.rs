@@ -0,0 +1,358 @@
+use std::fmt::{Display, Formatter};
+
+/// Enum representing the dimensions of a Bedrock world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dimension {
+    /// The overworld, whose chunk keys have no dimension id.
+    Overworld,
+    /// The nether, dimension id 1.
+    Nether,
+    /// The end, dimension id 2.
+    End,
+    /// Any other dimension id.
+    Other(i32),
+}
+
+impl Dimension {
+    /// Returns the dimension for a dimension id.
+    pub fn from_id(id: i32) -> Dimension {
+        match id {
+            0 => Dimension::Overworld,
+            1 => Dimension::Nether,
+            2 => Dimension::End,
+            id => Dimension::Other(id),
+        }
+    }
+
+    /// Returns the dimension id.
+    pub fn id(self) -> i32 {
+        match self {
+            Dimension::Overworld => 0,
+            Dimension::Nether => 1,
+            Dimension::End => 2,
+            Dimension::Other(id) => id,
+        }
+    }
+}
+
+/// The position of a chunk in a Bedrock world, in chunk coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChunkPos {
+    /// The dimension the chunk is in.
+    pub dimension: Dimension,
+    /// The chunk X coordinate, the block X coordinate divided by 16.
+    pub x: i32,
+    /// The chunk Z coordinate, the block Z coordinate divided by 16.
+    pub z: i32,
+}
+
+impl ChunkPos {
+    /// Creates a new chunk position.
+    pub fn new(x: i32, z: i32, dimension: Dimension) -> Self {
+        ChunkPos { dimension, x, z }
+    }
+
+    /// Decodes a chunk position stored as X and Z, then the dimension id unless it is the overworld.
+    fn from_bytes(bytes: &[u8]) -> Option<ChunkPos> {
+        let int_at = |offset: usize| {
+            i32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+        match bytes.len() {
+            8 => Some(ChunkPos::new(int_at(0), int_at(4), Dimension::Overworld)),
+            12 => Some(ChunkPos::new(
+                int_at(0),
+                int_at(4),
+                Dimension::from_id(int_at(8)),
+            )),
+            _ => None,
+        }
+    }
+
+    fn write_to(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.x.to_le_bytes());
+        bytes.extend_from_slice(&self.z.to_le_bytes());
+        if self.dimension != Dimension::Overworld {
+            bytes.extend_from_slice(&self.dimension.id().to_le_bytes());
+        }
+    }
+}
+
+impl Display for ChunkPos {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {} in {:?}", self.x, self.z, self.dimension)
+    }
+}
+
+const LOCAL_PLAYER: &str = "~local_player";
+const PLAYER_SERVER_PREFIX: &str = "player_server_";
+const PLAYER_PREFIX: &str = "player_";
+const MAP_PREFIX: &str = "map_";
+const ACTOR_PREFIX: &[u8] = b"actorprefix";
+const DIGEST_PREFIX: &[u8] = b"digp";
+
+/// The tag byte of subchunk keys, the only chunk keys followed by another byte.
+const SUB_CHUNK_PREFIX: u8 = 47;
+
+/// `BedrockKey` is a decoded key of a Bedrock world's LevelDB database.
+///
+/// Chunk records are keyed by the chunk position followed by a tag byte saying what the record holds, and
+/// subchunk records add the subchunk's Y index. Other records use text keys, or a text prefix followed by an id.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::bedrock::{BedrockKey, ChunkPos, Dimension};
+///
+/// let key = BedrockKey::SubChunkPrefix(ChunkPos::new(1, -2, Dimension::Nether), 4);
+/// let bytes = key.to_bytes();
+/// assert_eq!(bytes.len(), 14);
+/// assert_eq!(BedrockKey::from_bytes(&bytes), key);
+///
+/// assert_eq!(BedrockKey::from_bytes(b"~local_player"), BedrockKey::LocalPlayer);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BedrockKey {
+    /// Biomes and heightmap of a chunk since 1.18 (tag 43).
+    Data3D(ChunkPos),
+    /// Chunk version (tag 44).
+    Version(ChunkPos),
+    /// Biomes and heightmap of a chunk before 1.18 (tag 45).
+    Data2D(ChunkPos),
+    /// Biomes and heightmap of a chunk before 1.0 (tag 46).
+    Data2DLegacy(ChunkPos),
+    /// Blocks of the subchunk at the given Y index (tag 47).
+    SubChunkPrefix(ChunkPos, i8),
+    /// Blocks of a whole chunk before 1.0 (tag 48).
+    LegacyTerrain(ChunkPos),
+    /// Block entities of a chunk as concatenated NBT compounds (tag 49).
+    BlockEntity(ChunkPos),
+    /// Entities of a chunk as concatenated NBT compounds, before actor digests were used (tag 50).
+    Entity(ChunkPos),
+    /// Scheduled block updates (tag 51).
+    PendingTicks(ChunkPos),
+    /// Extra block data before 1.2.13 (tag 52).
+    LegacyBlockExtraData(ChunkPos),
+    /// Biome states (tag 53).
+    BiomeState(ChunkPos),
+    /// Generation state of a chunk (tag 54).
+    FinalizedState(ChunkPos),
+    /// Data kept from converting a chunk from another edition (tag 55).
+    ConversionData(ChunkPos),
+    /// Education Edition border blocks (tag 56).
+    BorderBlocks(ChunkPos),
+    /// Bounding boxes of structure spawners (tag 57).
+    HardcodedSpawners(ChunkPos),
+    /// Random block updates (tag 58).
+    RandomTicks(ChunkPos),
+    /// Checksums of the chunk's records (tag 59).
+    Checksums(ChunkPos),
+    /// The seed a chunk was generated with (tag 60).
+    GenerationSeed(ChunkPos),
+    /// Whether a chunk was generated before 1.18 and needs blending (tag 61).
+    GeneratedPreCavesAndCliffsBlending(ChunkPos),
+    /// Heights used to blend old and new biomes (tag 62).
+    BlendingBiomeHeight(ChunkPos),
+    /// Hash of the chunk's metadata in the metadata dictionary (tag 63).
+    MetaDataHash(ChunkPos),
+    /// Data used to blend old and new chunks (tag 64).
+    BlendingData(ChunkPos),
+    /// Version of the chunk's actor digest (tag 65).
+    ActorDigestVersion(ChunkPos),
+    /// Chunk version before 1.16.100 (tag 118).
+    LegacyVersion(ChunkPos),
+    /// The ids of the entities in a chunk, stored as `digp` and the chunk position.
+    Digest(ChunkPos),
+    /// An entity, stored as `actorprefix` and its 8 byte id.
+    Actor([u8; 8]),
+    /// The local player, `~local_player`.
+    LocalPlayer,
+    /// A player by id, `player_server_` and the id.
+    PlayerServer(String),
+    /// A player by client id, `player_` and the id.
+    Player(String),
+    /// A map by id, `map_` and the id.
+    Map(i64),
+    /// Any other text key, such as `BiomeData`, `Overworld` or `scoreboard`.
+    Named(String),
+    /// A key that could not be decoded.
+    Unknown(Vec<u8>),
+}
+
+impl BedrockKey {
+    /// Decodes a key from the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes: &[u8]` - The key as stored in the database.
+    ///
+    /// # Returns
+    ///
+    /// The decoded key. Keys that do not match any known layout are returned as `Named` if they are text and
+    /// `Unknown` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> BedrockKey {
+        if let Some(id) = bytes.strip_prefix(ACTOR_PREFIX) {
+            if let Ok(id) = <[u8; 8]>::try_from(id) {
+                return BedrockKey::Actor(id);
+            }
+        }
+        if let Some(pos) = bytes
+            .strip_prefix(DIGEST_PREFIX)
+            .and_then(ChunkPos::from_bytes)
+        {
+            return BedrockKey::Digest(pos);
+        }
+
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            if text == LOCAL_PLAYER {
+                return BedrockKey::LocalPlayer;
+            }
+            if let Some(id) = text.strip_prefix(PLAYER_SERVER_PREFIX) {
+                return BedrockKey::PlayerServer(id.to_string());
+            }
+            if let Some(id) = text.strip_prefix(PLAYER_PREFIX) {
+                return BedrockKey::Player(id.to_string());
+            }
+            if let Some(id) = text.strip_prefix(MAP_PREFIX).and_then(|id| id.parse().ok()) {
+                return BedrockKey::Map(id);
+            }
+        }
+
+        if let Some(key) = BedrockKey::from_chunk_key(bytes) {
+            return key;
+        }
+
+        match String::from_utf8(bytes.to_vec()) {
+            Ok(text) => BedrockKey::Named(text),
+            Err(_) => BedrockKey::Unknown(bytes.to_vec()),
+        }
+    }
+
+    fn from_chunk_key(bytes: &[u8]) -> Option<BedrockKey> {
+        match bytes.len() {
+            10 | 14 => {
+                let (rest, index) = bytes.split_at(bytes.len() - 1);
+                let (pos, tag) = rest.split_at(rest.len() - 1);
+                match tag[0] {
+                    SUB_CHUNK_PREFIX => Some(BedrockKey::SubChunkPrefix(
+                        ChunkPos::from_bytes(pos)?,
+                        index[0] as i8,
+                    )),
+                    _ => None,
+                }
+            }
+            9 | 13 => {
+                let (pos, tag) = bytes.split_at(bytes.len() - 1);
+                let pos = ChunkPos::from_bytes(pos)?;
+                Some(match tag[0] {
+                    43 => BedrockKey::Data3D(pos),
+                    44 => BedrockKey::Version(pos),
+                    45 => BedrockKey::Data2D(pos),
+                    46 => BedrockKey::Data2DLegacy(pos),
+                    48 => BedrockKey::LegacyTerrain(pos),
+                    49 => BedrockKey::BlockEntity(pos),
+                    50 => BedrockKey::Entity(pos),
+                    51 => BedrockKey::PendingTicks(pos),
+                    52 => BedrockKey::LegacyBlockExtraData(pos),
+                    53 => BedrockKey::BiomeState(pos),
+                    54 => BedrockKey::FinalizedState(pos),
+                    55 => BedrockKey::ConversionData(pos),
+                    56 => BedrockKey::BorderBlocks(pos),
+                    57 => BedrockKey::HardcodedSpawners(pos),
+                    58 => BedrockKey::RandomTicks(pos),
+                    59 => BedrockKey::Checksums(pos),
+                    60 => BedrockKey::GenerationSeed(pos),
+                    61 => BedrockKey::GeneratedPreCavesAndCliffsBlending(pos),
+                    62 => BedrockKey::BlendingBiomeHeight(pos),
+                    63 => BedrockKey::MetaDataHash(pos),
+                    64 => BedrockKey::BlendingData(pos),
+                    65 => BedrockKey::ActorDigestVersion(pos),
+                    118 => BedrockKey::LegacyVersion(pos),
+                    _ => return None,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the position and tag byte of a chunk record key.
+    fn chunk_record(&self) -> Option<(ChunkPos, u8)> {
+        Some(match *self {
+            BedrockKey::Data3D(pos) => (pos, 43),
+            BedrockKey::Version(pos) => (pos, 44),
+            BedrockKey::Data2D(pos) => (pos, 45),
+            BedrockKey::Data2DLegacy(pos) => (pos, 46),
+            BedrockKey::SubChunkPrefix(pos, _) => (pos, SUB_CHUNK_PREFIX),
+            BedrockKey::LegacyTerrain(pos) => (pos, 48),
+            BedrockKey::BlockEntity(pos) => (pos, 49),
+            BedrockKey::Entity(pos) => (pos, 50),
+            BedrockKey::PendingTicks(pos) => (pos, 51),
+            BedrockKey::LegacyBlockExtraData(pos) => (pos, 52),
+            BedrockKey::BiomeState(pos) => (pos, 53),
+            BedrockKey::FinalizedState(pos) => (pos, 54),
+            BedrockKey::ConversionData(pos) => (pos, 55),
+            BedrockKey::BorderBlocks(pos) => (pos, 56),
+            BedrockKey::HardcodedSpawners(pos) => (pos, 57),
+            BedrockKey::RandomTicks(pos) => (pos, 58),
+            BedrockKey::Checksums(pos) => (pos, 59),
+            BedrockKey::GenerationSeed(pos) => (pos, 60),
+            BedrockKey::GeneratedPreCavesAndCliffsBlending(pos) => (pos, 61),
+            BedrockKey::BlendingBiomeHeight(pos) => (pos, 62),
+            BedrockKey::MetaDataHash(pos) => (pos, 63),
+            BedrockKey::BlendingData(pos) => (pos, 64),
+            BedrockKey::ActorDigestVersion(pos) => (pos, 65),
+            BedrockKey::LegacyVersion(pos) => (pos, 118),
+            _ => return None,
+        })
+    }
+
+    /// Returns the chunk a key belongs to, for chunk records and entity digests.
+    pub fn chunk_pos(&self) -> Option<ChunkPos> {
+        match *self {
+            BedrockKey::Digest(pos) => Some(pos),
+            _ => self.chunk_record().map(|(pos, _)| pos),
+        }
+    }
+
+    /// Encodes the key as it is stored in the database.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        if let Some((pos, tag)) = self.chunk_record() {
+            pos.write_to(&mut bytes);
+            bytes.push(tag);
+            if let BedrockKey::SubChunkPrefix(_, index) = *self {
+                bytes.push(index as u8);
+            }
+            return bytes;
+        }
+
+        match self {
+            BedrockKey::Digest(pos) => {
+                bytes.extend_from_slice(DIGEST_PREFIX);
+                pos.write_to(&mut bytes);
+            }
+            BedrockKey::Actor(id) => {
+                bytes.extend_from_slice(ACTOR_PREFIX);
+                bytes.extend_from_slice(id);
+            }
+            BedrockKey::LocalPlayer => bytes.extend_from_slice(LOCAL_PLAYER.as_bytes()),
+            BedrockKey::PlayerServer(id) => {
+                bytes.extend_from_slice(format!("{}{}", PLAYER_SERVER_PREFIX, id).as_bytes())
+            }
+            BedrockKey::Player(id) => {
+                bytes.extend_from_slice(format!("{}{}", PLAYER_PREFIX, id).as_bytes())
+            }
+            BedrockKey::Map(id) => {
+                bytes.extend_from_slice(format!("{}{}", MAP_PREFIX, id).as_bytes())
+            }
+            BedrockKey::Named(name) => bytes.extend_from_slice(name.as_bytes()),
+            BedrockKey::Unknown(key) => bytes.extend_from_slice(key),
+            _ => unreachable!("chunk records are encoded above"),
+        }
+        bytes
+    }
+}
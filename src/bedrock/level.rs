@@ -0,0 +1,361 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use crate::nbt::{
+    fields::{from_nbt_field, list_of, Fields},
+    read_from_file, write_to_file, Compression, Endian, NbtError, NbtValue,
+};
+
+/// The game rules Bedrock stores as top level fields of `level.dat`.
+const GAME_RULES: &[&str] = &[
+    "commandblockoutput",
+    "commandblocksenabled",
+    "dodaylightcycle",
+    "doentitydrops",
+    "dofiretick",
+    "doimmediaterespawn",
+    "doinsomnia",
+    "dolimitedcrafting",
+    "domobloot",
+    "domobspawning",
+    "dotiledrops",
+    "doweathercycle",
+    "drowningdamage",
+    "falldamage",
+    "firedamage",
+    "freezedamage",
+    "functioncommandlimit",
+    "keepinventory",
+    "maxcommandchainlength",
+    "mobgriefing",
+    "naturalregeneration",
+    "playerssleepingpercentage",
+    "projectilescanbreakblocks",
+    "pvp",
+    "randomtickspeed",
+    "recipesunlock",
+    "respawnblocksexplode",
+    "sendcommandfeedback",
+    "showbordereffect",
+    "showcoordinates",
+    "showdaysplayed",
+    "showdeathmessages",
+    "showrecipemessages",
+    "showtags",
+    "spawnradius",
+    "tntexplodes",
+    "tntexplosiondropdecay",
+];
+
+/// `GameRule` is the value of a Bedrock game rule, which is either a switch or a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameRule {
+    /// A rule stored as a byte, such as `keepinventory`.
+    Bool(bool),
+    /// A rule stored as an int, such as `randomtickspeed`.
+    Int(i32),
+}
+
+impl From<GameRule> for NbtValue {
+    fn from(rule: GameRule) -> Self {
+        match rule {
+            GameRule::Bool(value) => value.into(),
+            GameRule::Int(value) => value.into(),
+        }
+    }
+}
+
+/// `Abilities` is the `abilities` compound of a Bedrock `level.dat`, the permissions players get by default.
+///
+/// Abilities this struct does not know are kept in `other` and written back unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Abilities {
+    pub attack_mobs: bool,
+    pub attack_players: bool,
+    pub build: bool,
+    pub doors_and_switches: bool,
+    pub flying: bool,
+    pub fly_speed: f32,
+    pub instabuild: bool,
+    pub invulnerable: bool,
+    pub lightning: bool,
+    pub may_fly: bool,
+    pub mine: bool,
+    pub op: bool,
+    pub open_containers: bool,
+    pub teleport: bool,
+    pub walk_speed: f32,
+    /// The abilities not covered above.
+    pub other: HashMap<String, NbtValue>,
+}
+
+impl Abilities {
+    /// Reads the abilities from their compound.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Abilities)` - The abilities.
+    /// * `Err(NbtError)` - `PathNotFound` if an ability is missing, or `TypeMismatch` if one has the wrong type.
+    pub fn from_nbt(value: NbtValue) -> Result<Abilities, NbtError> {
+        let mut fields = Fields::new(value)?;
+        Ok(Abilities {
+            attack_mobs: fields.required("attackmobs")?,
+            attack_players: fields.required("attackplayers")?,
+            build: fields.required("build")?,
+            doors_and_switches: fields.required("doorsandswitches")?,
+            flying: fields.required("flying")?,
+            fly_speed: fields.required("flySpeed")?,
+            instabuild: fields.required("instabuild")?,
+            invulnerable: fields.required("invulnerable")?,
+            lightning: fields.required("lightning")?,
+            may_fly: fields.required("mayfly")?,
+            mine: fields.required("mine")?,
+            op: fields.required("op")?,
+            open_containers: fields.required("opencontainers")?,
+            teleport: fields.required("teleport")?,
+            walk_speed: fields.required("walkSpeed")?,
+            other: fields.into_rest(),
+        })
+    }
+
+    /// Converts the abilities back into their compound.
+    pub fn to_nbt(&self) -> NbtValue {
+        let mut map = self.other.clone();
+        map.insert("attackmobs".to_string(), self.attack_mobs.into());
+        map.insert("attackplayers".to_string(), self.attack_players.into());
+        map.insert("build".to_string(), self.build.into());
+        map.insert(
+            "doorsandswitches".to_string(),
+            self.doors_and_switches.into(),
+        );
+        map.insert("flying".to_string(), self.flying.into());
+        map.insert("flySpeed".to_string(), self.fly_speed.into());
+        map.insert("instabuild".to_string(), self.instabuild.into());
+        map.insert("invulnerable".to_string(), self.invulnerable.into());
+        map.insert("lightning".to_string(), self.lightning.into());
+        map.insert("mayfly".to_string(), self.may_fly.into());
+        map.insert("mine".to_string(), self.mine.into());
+        map.insert("op".to_string(), self.op.into());
+        map.insert("opencontainers".to_string(), self.open_containers.into());
+        map.insert("teleport".to_string(), self.teleport.into());
+        map.insert("walkSpeed".to_string(), self.walk_speed.into());
+        NbtValue::Compound(map)
+    }
+}
+
+from_nbt_field!(Abilities);
+
+/// `Experiments` is the `experiments` compound of a Bedrock `level.dat`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Experiments {
+    /// Whether an experiment was ever turned on, which the game never resets.
+    pub ever_used: bool,
+    /// Whether the world was last saved with an experiment turned on.
+    pub saved_with_toggled: bool,
+    /// Each experiment by its name, such as `gametest`, and whether it is turned on.
+    pub toggles: BTreeMap<String, bool>,
+}
+
+impl Experiments {
+    /// Reads the experiments from their compound.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Experiments)` - The experiments.
+    /// * `Err(NbtError)` - `TypeMismatch` if a field is not a byte.
+    pub fn from_nbt(value: NbtValue) -> Result<Experiments, NbtError> {
+        let mut fields = Fields::new(value)?;
+        let ever_used = fields.optional("experiments_ever_used")?.unwrap_or(false);
+        let saved_with_toggled = fields
+            .optional("saved_with_toggled_experiments")?
+            .unwrap_or(false);
+
+        let mut toggles = BTreeMap::new();
+        for name in fields.keys() {
+            let enabled = fields.required(&name)?;
+            toggles.insert(name, enabled);
+        }
+
+        Ok(Experiments {
+            ever_used,
+            saved_with_toggled,
+            toggles,
+        })
+    }
+
+    /// Converts the experiments back into their compound.
+    pub fn to_nbt(&self) -> NbtValue {
+        let mut map: HashMap<String, NbtValue> = self
+            .toggles
+            .iter()
+            .map(|(name, &enabled)| (name.clone(), enabled.into()))
+            .collect();
+        map.insert("experiments_ever_used".to_string(), self.ever_used.into());
+        map.insert(
+            "saved_with_toggled_experiments".to_string(),
+            self.saved_with_toggled.into(),
+        );
+        NbtValue::Compound(map)
+    }
+}
+
+from_nbt_field!(Experiments);
+
+/// `BedrockLevelData` is the `level.dat` of a Bedrock Edition world, with typed access to its common fields.
+///
+/// Fields this struct does not know are kept in `other` and written back unchanged, so settings can be edited
+/// without losing anything newer versions of the game store.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::bedrock::{BedrockLevelData, GameRule};
+/// use std::path::PathBuf;
+///
+/// let mut level = BedrockLevelData::read_from_file(PathBuf::from("./tests/data/bedrock_level.dat")).unwrap();
+/// assert_eq!(level.level_name, "My World");
+///
+/// level.level_name = "Renamed".to_string();
+/// level.game_rules.insert("keepinventory".to_string(), GameRule::Bool(true));
+/// level.write_to_file(PathBuf::from("./tests/data/test.dat")).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BedrockLevelData {
+    /// The name of the world shown in the world list.
+    pub level_name: String,
+    pub spawn_x: i32,
+    pub spawn_y: i32,
+    pub spawn_z: i32,
+    /// The default game mode: 0 survival, 1 creative, 2 adventure or 6 spectator.
+    pub game_type: i32,
+    /// The difficulty, from 0 peaceful to 3 hard.
+    pub difficulty: i32,
+    pub random_seed: i64,
+    /// The number of ticks the world has run for.
+    pub time: i64,
+    /// When the world was last played, in seconds since the Unix epoch.
+    pub last_played: i64,
+    /// The format version of the world, also written in the header of the file.
+    pub storage_version: i32,
+    /// The version of the game that last opened the world, such as `[1, 20, 12, 1, 0]`.
+    pub last_opened_with_version: Option<Vec<i32>>,
+    pub experiments: Option<Experiments>,
+    pub abilities: Option<Abilities>,
+    /// The game rules by their lowercase name.
+    pub game_rules: BTreeMap<String, GameRule>,
+    /// The fields not covered above.
+    pub other: HashMap<String, NbtValue>,
+}
+
+impl BedrockLevelData {
+    /// Reads the level data from the root compound of a `level.dat`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value: NbtValue` - The root compound, as returned by `read_from_file` with `Endian::Little`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BedrockLevelData)` - The level data.
+    /// * `Err(NbtError)` - `PathNotFound` if a field such as `LevelName` is missing, or `TypeMismatch` if a
+    ///   field has the wrong type.
+    pub fn from_nbt(value: NbtValue) -> Result<BedrockLevelData, NbtError> {
+        let mut fields = Fields::new(value)?;
+
+        let mut game_rules = BTreeMap::new();
+        for &name in GAME_RULES {
+            if let Some(value) = fields.take_if(name, 0x01) {
+                game_rules.insert(name.to_string(), GameRule::Bool(i8::from(&value) != 0));
+            } else if let Some(value) = fields.take_if(name, 0x03) {
+                game_rules.insert(name.to_string(), GameRule::Int(i32::from(&value)));
+            }
+        }
+
+        Ok(BedrockLevelData {
+            level_name: fields.required("LevelName")?,
+            spawn_x: fields.required("SpawnX")?,
+            spawn_y: fields.required("SpawnY")?,
+            spawn_z: fields.required("SpawnZ")?,
+            game_type: fields.required("GameType")?,
+            difficulty: fields.required("Difficulty")?,
+            random_seed: fields.required("RandomSeed")?,
+            time: fields.required("Time")?,
+            last_played: fields.required("LastPlayed")?,
+            storage_version: fields.required("StorageVersion")?,
+            last_opened_with_version: fields.optional("lastOpenedWithVersion")?,
+            experiments: fields.optional("experiments")?,
+            abilities: fields.optional("abilities")?,
+            game_rules,
+            other: fields.into_rest(),
+        })
+    }
+
+    /// Converts the level data back into the root compound of a `level.dat`.
+    pub fn to_nbt(&self) -> NbtValue {
+        let mut map = self.other.clone();
+        for (name, &rule) in &self.game_rules {
+            map.insert(name.clone(), rule.into());
+        }
+
+        map.insert("LevelName".to_string(), self.level_name.as_str().into());
+        map.insert("SpawnX".to_string(), self.spawn_x.into());
+        map.insert("SpawnY".to_string(), self.spawn_y.into());
+        map.insert("SpawnZ".to_string(), self.spawn_z.into());
+        map.insert("GameType".to_string(), self.game_type.into());
+        map.insert("Difficulty".to_string(), self.difficulty.into());
+        map.insert("RandomSeed".to_string(), self.random_seed.into());
+        map.insert("Time".to_string(), self.time.into());
+        map.insert("LastPlayed".to_string(), self.last_played.into());
+        map.insert("StorageVersion".to_string(), self.storage_version.into());
+        if let Some(version) = &self.last_opened_with_version {
+            map.insert(
+                "lastOpenedWithVersion".to_string(),
+                list_of(0x03, version.iter().copied()),
+            );
+        }
+        if let Some(experiments) = &self.experiments {
+            map.insert("experiments".to_string(), experiments.to_nbt());
+        }
+        if let Some(abilities) = &self.abilities {
+            map.insert("abilities".to_string(), abilities.to_nbt());
+        }
+        NbtValue::Compound(map)
+    }
+
+    /// Reads the level data from an uncompressed little endian `level.dat` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: PathBuf` - The path to the `level.dat` file of a Bedrock world.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BedrockLevelData)` - The level data.
+    /// * `Err(NbtError)` - If the file could not be read or a field is missing or has the wrong type.
+    pub fn read_from_file(path: PathBuf) -> Result<BedrockLevelData, NbtError> {
+        let (_, value) = read_from_file(path, Compression::Uncompressed, Endian::Little)?;
+        BedrockLevelData::from_nbt(value)
+    }
+
+    /// Writes the level data to a `level.dat` file, with the header Bedrock expects.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: PathBuf` - The path to write to. An existing file is overwritten.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the file was written.
+    /// * `Err(NbtError)` - If the file could not be written.
+    pub fn write_to_file(&self, path: PathBuf) -> Result<(), NbtError> {
+        write_to_file(
+            None,
+            self.to_nbt(),
+            path,
+            Compression::Uncompressed,
+            Endian::Little,
+        )
+    }
+}
@@ -0,0 +1,90 @@
+use crate::nbt::NbtError;
+
+use super::{corrupt, read_length_prefixed, read_u32, read_u64};
+
+/// Log files are written in blocks of this size, and a record header never spans two blocks.
+const BLOCK_SIZE: usize = 32 * 1024;
+/// A record header: a checksum, the length of the fragment and the fragment type.
+const HEADER_LENGTH: usize = 4 + 2 + 1;
+
+const FULL: u8 = 1;
+const FIRST: u8 = 2;
+const MIDDLE: u8 = 3;
+const LAST: u8 = 4;
+
+const VALUE: u8 = 1;
+const DELETION: u8 = 0;
+
+/// Reassembles the records of a LevelDB log file, the format of both the write ahead logs and the manifest.
+///
+/// A record cut short at the end of the file is what a crash while writing leaves behind, so it is dropped
+/// rather than reported, as LevelDB itself does when recovering.
+pub(crate) fn read_records(data: &[u8]) -> Result<Vec<Vec<u8>>, NbtError> {
+    let mut records = Vec::new();
+    let mut record: Option<Vec<u8>> = None;
+
+    for block in data.chunks(BLOCK_SIZE) {
+        let mut position = 0;
+        while block.len() - position >= HEADER_LENGTH {
+            let header = &block[position..position + HEADER_LENGTH];
+            let length = u16::from_le_bytes([header[4], header[5]]) as usize;
+            let kind = header[6];
+            position += HEADER_LENGTH;
+
+            // A zero type marks space preallocated but never written, so the rest of the block is empty.
+            if kind == 0 && length == 0 {
+                break;
+            }
+            if block.len() - position < length {
+                return Ok(records);
+            }
+            let fragment = &block[position..position + length];
+            position += length;
+
+            match kind {
+                FULL => {
+                    record = None;
+                    records.push(fragment.to_vec());
+                }
+                FIRST => record = Some(fragment.to_vec()),
+                // Fragments whose first part was lost are skipped.
+                MIDDLE | LAST => {
+                    if let Some(partial) = record.as_mut() {
+                        partial.extend_from_slice(fragment);
+                        if kind == LAST {
+                            records.extend(record.take());
+                        }
+                    }
+                }
+                kind => return Err(corrupt(&format!("unknown log record type {}", kind))),
+            }
+        }
+    }
+
+    Ok(records)
+}
+
+/// Calls `apply` with every key in a write batch, in order, with its value or `None` for a deletion.
+pub(crate) fn read_batch(
+    mut data: &[u8],
+    mut apply: impl FnMut(&[u8], Option<&[u8]>),
+) -> Result<(), NbtError> {
+    let _sequence = read_u64(&mut data)?;
+    let count = read_u32(&mut data)?;
+
+    for _ in 0..count {
+        let (&kind, rest) = data
+            .split_first()
+            .ok_or_else(|| corrupt("write batch is truncated"))?;
+        data = rest;
+
+        let key = read_length_prefixed(&mut data)?;
+        match kind {
+            VALUE => apply(key, Some(read_length_prefixed(&mut data)?)),
+            DELETION => apply(key, None),
+            kind => return Err(corrupt(&format!("unknown write batch entry {}", kind))),
+        }
+    }
+
+    Ok(())
+}
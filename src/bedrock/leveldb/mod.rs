@@ -0,0 +1,290 @@
+mod log;
+mod table;
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::PathBuf,
+};
+
+use crate::nbt::NbtError;
+
+use self::{
+    log::{read_batch, read_records},
+    table::{parse_internal_key, Table},
+};
+
+/// Version edit fields in the manifest.
+const EDIT_COMPARATOR: u64 = 1;
+const EDIT_LOG_NUMBER: u64 = 2;
+const EDIT_NEXT_FILE_NUMBER: u64 = 3;
+const EDIT_LAST_SEQUENCE: u64 = 4;
+const EDIT_COMPACT_POINTER: u64 = 5;
+const EDIT_DELETED_FILE: u64 = 6;
+const EDIT_NEW_FILE: u64 = 7;
+const EDIT_PREV_LOG_NUMBER: u64 = 9;
+
+pub(crate) fn corrupt(message: &str) -> NbtError {
+    NbtError::InvalidLevelDb(message.to_string())
+}
+
+pub(crate) fn read_varint(data: &mut &[u8]) -> Result<u64, NbtError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data
+            .split_first()
+            .ok_or_else(|| corrupt("varint is truncated"))?;
+        *data = rest;
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(corrupt("varint is too long"))
+}
+
+fn read_bytes<'a>(data: &mut &'a [u8], length: usize) -> Result<&'a [u8], NbtError> {
+    if data.len() < length {
+        return Err(corrupt("data is truncated"));
+    }
+    let (bytes, rest) = data.split_at(length);
+    *data = rest;
+    Ok(bytes)
+}
+
+pub(crate) fn read_length_prefixed<'a>(data: &mut &'a [u8]) -> Result<&'a [u8], NbtError> {
+    let length = read_varint(data)?;
+    read_bytes(data, length as usize)
+}
+
+pub(crate) fn read_u32(data: &mut &[u8]) -> Result<u32, NbtError> {
+    let bytes = read_bytes(data, 4)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+pub(crate) fn read_u64(data: &mut &[u8]) -> Result<u64, NbtError> {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(read_bytes(data, 8)?);
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// A table file listed in the manifest.
+struct TableFile {
+    number: u64,
+    smallest: Vec<u8>,
+    largest: Vec<u8>,
+    table: Table,
+}
+
+impl TableFile {
+    fn may_contain(&self, user_key: &[u8]) -> bool {
+        self.smallest.as_slice() <= user_key && user_key <= self.largest.as_slice()
+    }
+}
+
+/// `LevelDb` is a read-only view of a LevelDB database, as used by Bedrock Edition worlds for their `db` folder.
+///
+/// The database is read as it was when it was opened, including writes still in its logs that were never
+/// compacted into tables. Blocks compressed with zlib or raw deflate, which Bedrock's fork of LevelDB writes,
+/// are supported, Snappy compressed blocks are not. The database should not be open in the game while it is read.
+///
+/// # Examples
+///
+/// ```no_run
+/// use commandblock::bedrock::LevelDb;
+/// use std::path::PathBuf;
+///
+/// let db = LevelDb::open(PathBuf::from("./world/db")).unwrap();
+///
+/// for key in db.keys().unwrap() {
+///     let value = db.get(&key).unwrap();
+/// }
+/// ```
+pub struct LevelDb {
+    /// Writes found in the logs, newer than anything in the tables. `None` marks a deletion.
+    memtable: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    /// The table files of each level. Level 0 files may overlap and are sorted newest first.
+    levels: Vec<Vec<TableFile>>,
+}
+
+impl LevelDb {
+    /// Opens the LevelDB database in the given folder.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: PathBuf` - The path to the database folder, the `db` folder of a Bedrock world.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(LevelDb)` - The opened database.
+    /// * `Err(NbtError)` - `InvalidLevelDb` if the manifest or a table is corrupt, or an IO error.
+    pub fn open(path: PathBuf) -> Result<LevelDb, NbtError> {
+        let current = fs::read_to_string(path.join("CURRENT"))?;
+        let manifest = fs::read(path.join(current.trim_end()))?;
+
+        let mut log_number = 0;
+        let mut prev_log_number = 0;
+        let mut files: BTreeMap<(u32, u64), (Vec<u8>, Vec<u8>)> = BTreeMap::new();
+
+        for record in read_records(&manifest)? {
+            let mut data = &record[..];
+            while !data.is_empty() {
+                match read_varint(&mut data)? {
+                    EDIT_COMPARATOR => {
+                        let comparator = read_length_prefixed(&mut data)?;
+                        if comparator != b"leveldb.BytewiseComparator" {
+                            return Err(corrupt(&format!(
+                                "unsupported comparator {}",
+                                String::from_utf8_lossy(comparator)
+                            )));
+                        }
+                    }
+                    EDIT_LOG_NUMBER => log_number = read_varint(&mut data)?,
+                    EDIT_PREV_LOG_NUMBER => prev_log_number = read_varint(&mut data)?,
+                    EDIT_NEXT_FILE_NUMBER | EDIT_LAST_SEQUENCE => {
+                        read_varint(&mut data)?;
+                    }
+                    EDIT_COMPACT_POINTER => {
+                        read_varint(&mut data)?;
+                        read_length_prefixed(&mut data)?;
+                    }
+                    EDIT_DELETED_FILE => {
+                        let level = read_varint(&mut data)? as u32;
+                        let number = read_varint(&mut data)?;
+                        files.remove(&(level, number));
+                    }
+                    EDIT_NEW_FILE => {
+                        let level = read_varint(&mut data)? as u32;
+                        let number = read_varint(&mut data)?;
+                        let _size = read_varint(&mut data)?;
+                        let smallest = read_length_prefixed(&mut data)?;
+                        let largest = read_length_prefixed(&mut data)?;
+                        files.insert(
+                            (level, number),
+                            (
+                                parse_internal_key(smallest)?.0.to_vec(),
+                                parse_internal_key(largest)?.0.to_vec(),
+                            ),
+                        );
+                    }
+                    field => {
+                        return Err(corrupt(&format!("unknown manifest field {}", field)));
+                    }
+                }
+            }
+        }
+
+        let mut levels: Vec<Vec<TableFile>> = Vec::new();
+        for ((level, number), (smallest, largest)) in files {
+            let mut table_path = path.join(format!("{:06}.ldb", number));
+            if !table_path.exists() {
+                table_path = path.join(format!("{:06}.sst", number));
+            }
+
+            let level = level as usize;
+            if levels.len() <= level {
+                levels.resize_with(level + 1, Vec::new);
+            }
+            levels[level].push(TableFile {
+                number,
+                smallest,
+                largest,
+                table: Table::open(table_path)?,
+            });
+        }
+        if let Some(level_zero) = levels.first_mut() {
+            level_zero.sort_by_key(|file| Reverse(file.number));
+        }
+
+        let mut logs = BTreeSet::new();
+        for entry in fs::read_dir(&path)? {
+            let name = entry?.file_name();
+            let number = name
+                .to_str()
+                .and_then(|name| name.strip_suffix(".log"))
+                .and_then(|number| number.parse::<u64>().ok());
+            if let Some(number) = number {
+                if number >= log_number || number == prev_log_number {
+                    logs.insert(number);
+                }
+            }
+        }
+
+        let mut memtable = BTreeMap::new();
+        for number in logs {
+            let log = fs::read(path.join(format!("{:06}.log", number)))?;
+            for record in read_records(&log)? {
+                read_batch(&record, |key, value| {
+                    memtable.insert(key.to_vec(), value.map(<[u8]>::to_vec));
+                })?;
+            }
+        }
+
+        Ok(LevelDb { memtable, levels })
+    }
+
+    /// Returns the tables that may hold a key, newest first.
+    fn tables_for<'a>(&'a self, key: &'a [u8]) -> impl Iterator<Item = &'a Table> + 'a {
+        self.levels
+            .iter()
+            .flatten()
+            .filter(move |file| file.may_contain(key))
+            .map(|file| &file.table)
+    }
+
+    /// Returns the value stored for a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key: &[u8]` - The key to look up.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(Vec<u8>))` - The value stored for the key.
+    /// * `Ok(None)` - If the key is not in the database.
+    /// * `Err(NbtError)` - If a table could not be read.
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, NbtError> {
+        if let Some(value) = self.memtable.get(key) {
+            return Ok(value.clone());
+        }
+
+        for table in self.tables_for(key) {
+            if let Some(value) = table.get(key)? {
+                return Ok(value);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns every key in the database in sorted order.
+    ///
+    /// This reads every table in the database, so it can take a while for large worlds.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Vec<u8>>)` - The keys of the database.
+    /// * `Err(NbtError)` - If a table could not be read.
+    pub fn keys(&self) -> Result<Vec<Vec<u8>>, NbtError> {
+        // The newest entry for each key decides whether it exists, and sources are visited newest first.
+        let mut keys: BTreeMap<Vec<u8>, bool> = self
+            .memtable
+            .iter()
+            .map(|(key, value)| (key.clone(), value.is_some()))
+            .collect();
+
+        for file in self.levels.iter().flatten() {
+            file.table.for_each(|key, value| {
+                if !keys.contains_key(key) {
+                    keys.insert(key.to_vec(), value.is_some());
+                }
+            })?;
+        }
+
+        Ok(keys
+            .into_iter()
+            .filter_map(|(key, exists)| exists.then_some(key))
+            .collect())
+    }
+}
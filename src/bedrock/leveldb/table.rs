@@ -0,0 +1,220 @@
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::PathBuf,
+};
+
+use flate2::read::{DeflateDecoder, ZlibDecoder};
+
+use crate::nbt::NbtError;
+
+use super::{corrupt, read_varint};
+
+/// The footer at the end of every table: the metaindex and index block handles, padding and the magic number.
+const FOOTER_LENGTH: usize = 48;
+const MAGIC: u64 = 0xdb47_7524_8b80_fb57;
+/// Every block is followed by its compression type and a checksum.
+const BLOCK_TRAILER_LENGTH: usize = 1 + 4;
+
+const NO_COMPRESSION: u8 = 0;
+const SNAPPY_COMPRESSION: u8 = 1;
+/// Bedrock's fork of LevelDB adds zlib, and raw deflate which is what it writes.
+const ZLIB_COMPRESSION: u8 = 2;
+const ZLIB_RAW_COMPRESSION: u8 = 4;
+
+const TYPE_VALUE: u8 = 1;
+
+/// The location of a block within a table file.
+#[derive(Debug, Clone, Copy)]
+struct BlockHandle {
+    offset: u64,
+    size: u64,
+}
+
+impl BlockHandle {
+    fn decode(data: &mut &[u8]) -> Result<BlockHandle, NbtError> {
+        Ok(BlockHandle {
+            offset: read_varint(data)?,
+            size: read_varint(data)?,
+        })
+    }
+}
+
+/// Splits an internal key into its user key and whether it holds a value rather than a deletion.
+pub(crate) fn parse_internal_key(key: &[u8]) -> Result<(&[u8], bool), NbtError> {
+    if key.len() < 8 {
+        return Err(corrupt("internal key is too short"));
+    }
+    let (user_key, tag) = key.split_at(key.len() - 8);
+    // The tag packs the sequence number above the value type in the low byte.
+    Ok((user_key, tag[0] == TYPE_VALUE))
+}
+
+/// A key and value stored in a block.
+type BlockEntry = (Vec<u8>, Vec<u8>);
+
+/// Decodes the entries of a block, undoing the prefix compression of its keys.
+fn block_entries(block: &[u8]) -> Result<Vec<BlockEntry>, NbtError> {
+    if block.len() < 4 {
+        return Err(corrupt("block is too short"));
+    }
+    let restarts = u32::from_le_bytes([
+        block[block.len() - 4],
+        block[block.len() - 3],
+        block[block.len() - 2],
+        block[block.len() - 1],
+    ]) as usize;
+    let end = restarts
+        .checked_mul(4)
+        .and_then(|length| (block.len() - 4).checked_sub(length))
+        .ok_or_else(|| corrupt("block restart array is too long"))?;
+
+    let mut data = &block[..end];
+    let mut entries: Vec<BlockEntry> = Vec::new();
+    while !data.is_empty() {
+        let shared = read_varint(&mut data)? as usize;
+        let non_shared = read_varint(&mut data)? as usize;
+        let value_length = read_varint(&mut data)? as usize;
+        if data.len() < non_shared.saturating_add(value_length) {
+            return Err(corrupt("block entry is truncated"));
+        }
+
+        let mut key = match entries.last() {
+            Some((previous, _)) if shared <= previous.len() => previous[..shared].to_vec(),
+            None if shared == 0 => Vec::new(),
+            _ => return Err(corrupt("block entry shares more than the previous key")),
+        };
+        key.extend_from_slice(&data[..non_shared]);
+        let value = data[non_shared..non_shared + value_length].to_vec();
+        data = &data[non_shared + value_length..];
+
+        entries.push((key, value));
+    }
+
+    Ok(entries)
+}
+
+/// A sorted table file, with its index read so blocks can be found without reading the whole file.
+pub(crate) struct Table {
+    path: PathBuf,
+    /// Each data block with a key that is at least as large as every key in the block.
+    index: Vec<(Vec<u8>, BlockHandle)>,
+}
+
+impl Table {
+    pub(crate) fn open(path: PathBuf) -> Result<Table, NbtError> {
+        let mut file = File::open(&path)?;
+        let length = file.seek(SeekFrom::End(0))?;
+        if length < FOOTER_LENGTH as u64 {
+            return Err(corrupt("table is too short"));
+        }
+
+        let mut footer = [0u8; FOOTER_LENGTH];
+        file.seek(SeekFrom::Start(length - FOOTER_LENGTH as u64))?;
+        file.read_exact(&mut footer)?;
+
+        let mut magic = [0u8; 8];
+        magic.copy_from_slice(&footer[FOOTER_LENGTH - 8..]);
+        if u64::from_le_bytes(magic) != MAGIC {
+            return Err(corrupt("table has a bad magic number"));
+        }
+
+        let mut handles = &footer[..FOOTER_LENGTH - 8];
+        let _metaindex = BlockHandle::decode(&mut handles)?;
+        let index_handle = BlockHandle::decode(&mut handles)?;
+
+        let index = block_entries(&read_block(&mut file, index_handle)?)?
+            .into_iter()
+            .map(|(key, value)| Ok((key, BlockHandle::decode(&mut &value[..])?)))
+            .collect::<Result<_, NbtError>>()?;
+
+        Ok(Table { path, index })
+    }
+
+    /// Looks up the newest entry for a user key in the table.
+    ///
+    /// Returns `Some(None)` if the newest entry is a deletion.
+    pub(crate) fn get(&self, user_key: &[u8]) -> Result<Option<Option<Vec<u8>>>, NbtError> {
+        let mut file = File::open(&self.path)?;
+
+        for (last_key, handle) in &self.index {
+            let (last_user_key, _) = parse_internal_key(last_key)?;
+            if last_user_key < user_key {
+                continue;
+            }
+
+            // Entries for the same user key are sorted newest first, so the first match is the one to use.
+            for (key, value) in block_entries(&read_block(&mut file, *handle)?)? {
+                let (key, is_value) = parse_internal_key(&key)?;
+                if key == user_key {
+                    return Ok(Some(is_value.then_some(value)));
+                }
+                if key > user_key {
+                    return Ok(None);
+                }
+            }
+
+            if last_user_key > user_key {
+                break;
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Calls `visit` with every entry in the table in order, with its value or `None` for a deletion.
+    pub(crate) fn for_each(
+        &self,
+        mut visit: impl FnMut(&[u8], Option<&[u8]>),
+    ) -> Result<(), NbtError> {
+        let mut file = File::open(&self.path)?;
+
+        for (_, handle) in &self.index {
+            for (key, value) in block_entries(&read_block(&mut file, *handle)?)? {
+                let (key, is_value) = parse_internal_key(&key)?;
+                visit(key, is_value.then_some(&value[..]));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads a block and decompresses it. Block checksums are not verified.
+fn read_block(file: &mut File, handle: BlockHandle) -> Result<Vec<u8>, NbtError> {
+    // The handle comes from the file, so check the block lies within it before allocating its size.
+    let length = file.metadata()?.len();
+    let end = handle
+        .offset
+        .checked_add(handle.size)
+        .and_then(|end| end.checked_add(BLOCK_TRAILER_LENGTH as u64));
+    if end.is_none_or(|end| end > length) {
+        return Err(corrupt("block extends past the end of the table"));
+    }
+
+    let size = usize::try_from(handle.size).map_err(|_| corrupt("block is too large"))?;
+    let mut data = vec![0u8; size + BLOCK_TRAILER_LENGTH];
+    file.seek(SeekFrom::Start(handle.offset))?;
+    file.read_exact(&mut data)?;
+
+    let compression = data[size];
+    data.truncate(size);
+
+    match compression {
+        NO_COMPRESSION => Ok(data),
+        ZLIB_COMPRESSION => {
+            let mut block = Vec::new();
+            ZlibDecoder::new(&data[..]).read_to_end(&mut block)?;
+            Ok(block)
+        }
+        ZLIB_RAW_COMPRESSION => {
+            let mut block = Vec::new();
+            DeflateDecoder::new(&data[..]).read_to_end(&mut block)?;
+            Ok(block)
+        }
+        SNAPPY_COMPRESSION => Err(NbtError::UnsupportedCompression(
+            "Snappy compressed LevelDB blocks".to_string(),
+        )),
+        compression => Err(NbtError::InvalidCompression(compression)),
+    }
+}
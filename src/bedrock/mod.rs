@@ -0,0 +1,15 @@
+mod biome;
+mod key;
+mod level;
+mod leveldb;
+mod records;
+mod subchunk;
+mod world;
+
+pub use biome::*;
+pub use key::*;
+pub use level::*;
+pub use leveldb::LevelDb;
+pub use records::*;
+pub use subchunk::{BlockStorage, SubChunk};
+pub use world::*;
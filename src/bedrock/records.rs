@@ -0,0 +1,106 @@
+use std::borrow::Borrow;
+
+use crate::nbt::{Endian, NbtError, NbtReader, NbtValue, NbtWriter};
+
+use super::subchunk::invalid;
+
+/// `NbtRecords` splits a record made of concatenated little endian compounds, such as a `BlockEntity` or
+/// legacy `Entity` chunk record, into its compounds.
+///
+/// Each compound is read as a named tag without the header Bedrock files start with. Iteration stops after
+/// the last compound, or after the first error as the data after a corrupted compound cannot be located.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::bedrock::{write_records, NbtRecords};
+/// use commandblock::nbt::NbtValue;
+///
+/// let mut chest = NbtValue::new();
+/// chest.insert("id".to_string(), "Chest");
+/// let mut sign = NbtValue::new();
+/// sign.insert("id".to_string(), "Sign");
+///
+/// let data = write_records([&chest, &sign]).unwrap();
+///
+/// let values = NbtRecords::new(&data).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(values, vec![chest, sign]);
+/// ```
+pub struct NbtRecords<'a> {
+    reader: NbtReader<&'a [u8]>,
+    finished: bool,
+}
+
+impl<'a> NbtRecords<'a> {
+    /// Creates an iterator over the compounds in the given record.
+    pub fn new(data: &'a [u8]) -> Self {
+        NbtRecords {
+            reader: NbtReader::new(data, Endian::Little),
+            finished: false,
+        }
+    }
+}
+
+impl Iterator for NbtRecords<'_> {
+    type Item = Result<NbtValue, NbtError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let result = self.reader.next_root().transpose();
+        self.finished = !matches!(result, Some(Ok(_)));
+        result.map(|result| result.map(|(_, value)| value))
+    }
+}
+
+/// Writes compounds one after another, in the format of `BlockEntity` and legacy `Entity` chunk records.
+///
+/// # Arguments
+///
+/// * `values: I` - The compounds to write, either `NbtValue`s or `&NbtValue`s.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - The encoded record.
+/// * `Err(NbtError)` - If a value is not a compound or fails `NbtValue::validate`.
+pub fn write_records<V: Borrow<NbtValue>, I: IntoIterator<Item = V>>(
+    values: I,
+) -> Result<Vec<u8>, NbtError> {
+    let mut bytes = Vec::new();
+    for value in values {
+        let value = value.borrow();
+        if value.as_compound().is_none() {
+            return Err(invalid("records can only hold compounds"));
+        }
+        value.validate()?;
+        NbtWriter::new(&mut bytes, Endian::Little).write_root("", value)?;
+    }
+    Ok(bytes)
+}
+
+/// Parses a `Digest` record, the list of 8 byte ids of the entities in a chunk. Each entity is stored under
+/// the `BedrockKey::Actor` key with its id.
+///
+/// # Returns
+///
+/// * `Ok(Vec<[u8; 8]>)` - The entity ids.
+/// * `Err(NbtError)` - `InvalidBedrockData` if the record length is not a multiple of 8.
+pub fn parse_digest(data: &[u8]) -> Result<Vec<[u8; 8]>, NbtError> {
+    if !data.len().is_multiple_of(8) {
+        return Err(invalid(&format!(
+            "entity digest of {} bytes is not a list of ids",
+            data.len()
+        )));
+    }
+    Ok(data
+        .chunks_exact(8)
+        .map(|id| <[u8; 8]>::try_from(id).unwrap())
+        .collect())
+}
+
+/// Encodes a list of entity ids as a `Digest` record.
+pub fn write_digest(ids: &[[u8; 8]]) -> Vec<u8> {
+    ids.concat()
+}
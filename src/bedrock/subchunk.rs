@@ -0,0 +1,445 @@
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use crate::nbt::{Endian, NbtError, NbtReader, NbtValue, NbtWriter};
+
+/// The number of blocks in a subchunk, 16 by 16 by 16.
+pub(crate) const BLOCK_COUNT: usize = 16 * 16 * 16;
+
+/// The bits per value a paletted storage can use.
+const BITS_PER_BLOCK: [u8; 9] = [0, 1, 2, 3, 4, 5, 6, 8, 16];
+
+pub(crate) fn invalid(message: &str) -> NbtError {
+    NbtError::InvalidBedrockData(message.to_string())
+}
+
+/// Returns the index of a block in a block storage, which is stored in X, then Z, then Y order.
+pub(crate) fn block_index(x: usize, y: usize, z: usize) -> usize {
+    (x << 8) | (z << 4) | y
+}
+
+/// Reads the header-less packed indices of a paletted storage with the given bits per value.
+///
+/// Values are packed into little endian words from the lowest bits up, and never span two words, so some bits
+/// of each word can go unused.
+pub(crate) fn read_indices(data: &mut &[u8], bits: u8) -> Result<Vec<u16>, NbtError> {
+    if !BITS_PER_BLOCK.contains(&bits) {
+        return Err(invalid(&format!("storage has {} bits per value", bits)));
+    }
+
+    let mut indices = vec![0u16; BLOCK_COUNT];
+    if bits > 0 {
+        let values_per_word = 32 / bits as usize;
+        let mask = (1u32 << bits) - 1;
+
+        for chunk in indices.chunks_mut(values_per_word) {
+            let word = data.read_u32::<LittleEndian>()?;
+            for (offset, index) in chunk.iter_mut().enumerate() {
+                *index = ((word >> (offset * bits as usize)) & mask) as u16;
+            }
+        }
+    }
+    Ok(indices)
+}
+
+/// Reads the palette size that follows the indices of a paletted storage, checking every index is in range.
+///
+/// A storage with no bits per value holds a single palette entry and does not store the palette size.
+pub(crate) fn read_palette_size(
+    data: &mut &[u8],
+    bits: u8,
+    indices: &[u16],
+) -> Result<usize, NbtError> {
+    let palette_size = match bits {
+        0 => 1,
+        _ => data.read_i32::<LittleEndian>()?,
+    };
+    if palette_size < 1 || palette_size as usize > BLOCK_COUNT {
+        return Err(invalid(&format!(
+            "storage has a palette of {} entries",
+            palette_size
+        )));
+    }
+
+    if let Some(&index) = indices
+        .iter()
+        .find(|&&index| index as usize >= palette_size as usize)
+    {
+        return Err(invalid(&format!(
+            "storage uses palette index {} of {}",
+            index, palette_size
+        )));
+    }
+    Ok(palette_size as usize)
+}
+
+/// Reduces a palette to the entries the indices use, in the order they are first used, and maps the indices
+/// to the reduced palette.
+pub(crate) fn compact<'a, T>(palette: &'a [T], indices: &[u16]) -> (Vec<&'a T>, Vec<u32>) {
+    let mut remap = vec![None; palette.len()];
+    let mut used = Vec::new();
+    let indices = indices
+        .iter()
+        .map(|&index| {
+            *remap[index as usize].get_or_insert_with(|| {
+                used.push(&palette[index as usize]);
+                used.len() as u32 - 1
+            })
+        })
+        .collect();
+    (used, indices)
+}
+
+/// Writes the header, packed indices and palette size of a paletted storage, using the fewest bits per value,
+/// but at least `min_bits`, that fit the palette. The palette entries themselves are left to the caller.
+pub(crate) fn write_indices(
+    bytes: &mut Vec<u8>,
+    indices: &[u32],
+    palette_size: usize,
+    min_bits: u8,
+) -> Result<(), NbtError> {
+    let bits = BITS_PER_BLOCK
+        .into_iter()
+        .find(|&bits| bits >= min_bits && palette_size <= 1 << bits)
+        .ok_or_else(|| invalid("storage palette is too large"))?;
+    bytes.push(bits << 1);
+
+    if bits > 0 {
+        for values in indices.chunks(32 / bits as usize) {
+            let word = values
+                .iter()
+                .enumerate()
+                .fold(0u32, |word, (offset, &index)| {
+                    word | index << (offset * bits as usize)
+                });
+            bytes.write_u32::<LittleEndian>(word)?;
+        }
+        bytes.write_i32::<LittleEndian>(palette_size as i32)?;
+    }
+    Ok(())
+}
+
+/// `BlockStorage` is one layer of blocks in a subchunk: a palette of block states and the palette index of
+/// every block.
+///
+/// Palette entries are block state compounds as stored on disk, with a `name`, a `states` compound and a
+/// `version`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockStorage {
+    palette: Vec<NbtValue>,
+    indices: Vec<u16>,
+}
+
+impl BlockStorage {
+    /// Reads a block storage: a header byte with the bits per block, the packed indices as little endian words,
+    /// then the palette size and that many little endian NBT compounds.
+    fn parse(data: &mut &[u8]) -> Result<BlockStorage, NbtError> {
+        let header = data.read_u8()?;
+        if header & 1 != 0 {
+            return Err(invalid(
+                "block storage uses runtime ids, which are only sent over the network",
+            ));
+        }
+        let bits = header >> 1;
+        let indices = read_indices(data, bits)?;
+        let palette_size = read_palette_size(data, bits, &indices)?;
+
+        let mut palette = Vec::with_capacity(palette_size);
+        for _ in 0..palette_size {
+            let (_, value) = NbtReader::new(&mut *data, Endian::Little).parse_root()?;
+            palette.push(value);
+        }
+
+        Ok(BlockStorage { palette, indices })
+    }
+
+    /// Creates a block storage with every block set to the given block state.
+    ///
+    /// # Arguments
+    ///
+    /// * `block: NbtValue` - The block state compound, with a `name`, a `states` compound and a `version`.
+    pub fn new(block: NbtValue) -> Self {
+        BlockStorage {
+            palette: vec![block],
+            indices: vec![0; BLOCK_COUNT],
+        }
+    }
+
+    /// Sets the block state at the given position within the subchunk, adding it to the palette if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a coordinate is not below 16.
+    pub fn set(&mut self, x: usize, y: usize, z: usize, block: NbtValue) {
+        assert!(x < 16 && y < 16 && z < 16, "block position out of range");
+        let index = match self.palette.iter().position(|entry| *entry == block) {
+            Some(index) => index,
+            None => {
+                self.palette.push(block);
+                self.palette.len() - 1
+            }
+        };
+        self.indices[block_index(x, y, z)] = index as u16;
+    }
+
+    /// Returns the palette of block states.
+    pub fn palette(&self) -> &[NbtValue] {
+        &self.palette
+    }
+
+    /// Returns the palette index of the block at the given position within the subchunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a coordinate is not below 16.
+    pub fn palette_index(&self, x: usize, y: usize, z: usize) -> u16 {
+        assert!(x < 16 && y < 16 && z < 16, "block position out of range");
+        self.indices[block_index(x, y, z)]
+    }
+
+    /// Returns the block state at the given position within the subchunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a coordinate is not below 16.
+    pub fn get(&self, x: usize, y: usize, z: usize) -> &NbtValue {
+        &self.palette[self.palette_index(x, y, z) as usize]
+    }
+
+    /// Writes the block storage with the fewest bits per block its palette needs, leaving out palette entries
+    /// no block uses.
+    ///
+    /// Single-entry palettes are written with 1 bit per block rather than 0. Reading accepts 0 bits, but the
+    /// game's own saves have not been confirmed to use it for blocks, while 1 bit is always valid.
+    fn write(&self, bytes: &mut Vec<u8>) -> Result<(), NbtError> {
+        let (palette, indices) = compact(&self.palette, &self.indices);
+        write_indices(bytes, &indices, palette.len(), 1)?;
+
+        for block in palette {
+            if block.as_compound().is_none() {
+                return Err(invalid("block storage palette entries must be compounds"));
+            }
+            block.validate()?;
+            NbtWriter::new(&mut *bytes, Endian::Little).write_root("", block)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// `SubChunk` is a 16 by 16 by 16 section of a Bedrock chunk, stored under a `SubChunkPrefix` key.
+///
+/// A subchunk has one or more layers of blocks. The first layer holds the blocks themselves and the second, when
+/// present, holds the water in waterlogged blocks.
+///
+/// # Examples
+///
+/// ```no_run
+/// use commandblock::bedrock::{BedrockKey, BedrockWorld, ChunkPos, Dimension, SubChunk};
+/// use std::path::PathBuf;
+///
+/// let world = BedrockWorld::open(PathBuf::from("./world")).unwrap();
+/// let key = BedrockKey::SubChunkPrefix(ChunkPos::new(0, 0, Dimension::Overworld), 4);
+///
+/// if let Some(data) = world.get(&key).unwrap() {
+///     let sub_chunk = SubChunk::parse(&data).unwrap();
+///     let block = sub_chunk.get_block(0, 0, 0, 0).unwrap();
+///     println!("{}", block.get("name").unwrap());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubChunk {
+    version: u8,
+    y: Option<i8>,
+    layers: Vec<BlockStorage>,
+}
+
+impl SubChunk {
+    /// Parses a subchunk stored in version 1, 8 or 9 of the paletted format.
+    ///
+    /// # Arguments
+    ///
+    /// * `data: &[u8]` - The value stored under the subchunk's `SubChunkPrefix` key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SubChunk)` - The parsed subchunk.
+    /// * `Err(NbtError)` - `InvalidBedrockData` if the data is not a paletted subchunk, or the error reading a
+    ///   palette entry.
+    pub fn parse(data: &[u8]) -> Result<SubChunk, NbtError> {
+        let mut data = data;
+        let version = data.read_u8()?;
+        let (layer_count, y) = match version {
+            1 => (1, None),
+            8 => (data.read_u8()?, None),
+            9 => (data.read_u8()?, Some(data.read_i8()?)),
+            version => {
+                return Err(invalid(&format!(
+                    "subchunk version {} is not supported",
+                    version
+                )))
+            }
+        };
+
+        let layers = (0..layer_count)
+            .map(|_| BlockStorage::parse(&mut data))
+            .collect::<Result<_, _>>()?;
+
+        Ok(SubChunk { version, y, layers })
+    }
+
+    /// Creates a version 9 subchunk with a single layer of the given block state.
+    ///
+    /// # Arguments
+    ///
+    /// * `y: i8` - The Y index of the subchunk, the block Y coordinate divided by 16.
+    /// * `block: NbtValue` - The block state compound every block is set to, usually air.
+    pub fn new(y: i8, block: NbtValue) -> Self {
+        SubChunk {
+            version: 9,
+            y: Some(y),
+            layers: vec![BlockStorage::new(block)],
+        }
+    }
+
+    /// Encodes the subchunk as it is stored under its `SubChunkPrefix` key.
+    ///
+    /// Subchunks read from version 1 are written as version 8, which stores the same data. Each layer uses the
+    /// fewest bits per block that fit the block states it uses, and at least 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::bedrock::SubChunk;
+    /// use commandblock::nbt::NbtValue;
+    ///
+    /// let mut air = NbtValue::new();
+    /// air.insert("name".to_string(), "minecraft:air");
+    /// air.insert("states".to_string(), NbtValue::new());
+    ///
+    /// let mut stone = air.clone();
+    /// stone.insert("name".to_string(), "minecraft:stone");
+    ///
+    /// let mut sub_chunk = SubChunk::new(-4, air);
+    /// sub_chunk.set_block(0, 0, 0, 0, stone.clone()).unwrap();
+    ///
+    /// let sub_chunk = SubChunk::parse(&sub_chunk.to_bytes().unwrap()).unwrap();
+    /// assert_eq!(sub_chunk.get_block(0, 0, 0, 0), Some(&stone));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<u8>)` - The encoded subchunk.
+    /// * `Err(NbtError)` - If a palette entry is not a compound that can be written, or a version 9 subchunk has
+    ///   no Y index.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, NbtError> {
+        let mut bytes = Vec::new();
+        match (self.version, self.y) {
+            (9, Some(y)) => {
+                bytes.push(9);
+                bytes.push(self.layers.len() as u8);
+                bytes.push(y as u8);
+            }
+            (9, None) => return Err(invalid("version 9 subchunks need a Y index")),
+            _ => {
+                bytes.push(8);
+                bytes.push(self.layers.len() as u8);
+            }
+        }
+
+        for layer in &self.layers {
+            layer.write(&mut bytes)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Returns the version of the format the subchunk was stored in.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Sets the version the subchunk is written in, 8 or 9. Version 9 also stores the Y index, see `set_y`.
+    pub fn set_version(&mut self, version: u8) -> Result<(), NbtError> {
+        match version {
+            8 | 9 => {
+                self.version = version;
+                Ok(())
+            }
+            version => Err(invalid(&format!(
+                "subchunk version {} cannot be written",
+                version
+            ))),
+        }
+    }
+
+    /// Returns the Y index of the subchunk, which version 9 stores alongside the key.
+    pub fn y(&self) -> Option<i8> {
+        self.y
+    }
+
+    /// Sets the Y index of the subchunk.
+    pub fn set_y(&mut self, y: i8) {
+        self.y = Some(y);
+    }
+
+    /// Returns the block storage layers of the subchunk.
+    pub fn layers(&self) -> &[BlockStorage] {
+        &self.layers
+    }
+
+    /// Returns the block storage layers of the subchunk for editing, for example to add a waterlogging layer.
+    pub fn layers_mut(&mut self) -> &mut Vec<BlockStorage> {
+        &mut self.layers
+    }
+
+    /// Returns the block state at the given position within the subchunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `x: usize` - The X position within the subchunk, from 0 to 15.
+    /// * `y: usize` - The Y position within the subchunk, from 0 to 15.
+    /// * `z: usize` - The Z position within the subchunk, from 0 to 15.
+    /// * `layer: usize` - The block storage layer, 0 for blocks and 1 for the water in waterlogged blocks.
+    ///
+    /// # Returns
+    ///
+    /// The block state compound, or `None` if the subchunk has no such layer or the position is out of range.
+    pub fn get_block(&self, x: usize, y: usize, z: usize, layer: usize) -> Option<&NbtValue> {
+        if x >= 16 || y >= 16 || z >= 16 {
+            return None;
+        }
+        self.layers.get(layer).map(|storage| storage.get(x, y, z))
+    }
+
+    /// Sets the block state at the given position within the subchunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `x: usize` - The X position within the subchunk, from 0 to 15.
+    /// * `y: usize` - The Y position within the subchunk, from 0 to 15.
+    /// * `z: usize` - The Z position within the subchunk, from 0 to 15.
+    /// * `layer: usize` - The block storage layer, 0 for blocks and 1 for the water in waterlogged blocks.
+    /// * `block: NbtValue` - The block state compound.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the block was set.
+    /// * `Err(NbtError)` - `InvalidBedrockData` if the subchunk has no such layer or the position is out of range.
+    pub fn set_block(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        layer: usize,
+        block: NbtValue,
+    ) -> Result<(), NbtError> {
+        if x >= 16 || y >= 16 || z >= 16 {
+            return Err(invalid("block position out of range"));
+        }
+        let storage = self
+            .layers
+            .get_mut(layer)
+            .ok_or_else(|| invalid(&format!("subchunk has no layer {}", layer)))?;
+        storage.set(x, y, z, block);
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use crate::{
+    nbt::{Endian, NbtError, NbtReader, NbtValue},
+    player::PlayerData,
+};
+
+use super::{
+    biome::{Data2D, Data3D},
+    key::{BedrockKey, ChunkPos},
+    leveldb::LevelDb,
+    records::{parse_digest, NbtRecords},
+    subchunk::SubChunk,
+};
+
+/// `BedrockWorld` reads the chunks and other records of a Bedrock Edition world folder.
+///
+/// # Examples
+///
+/// ```no_run
+/// use commandblock::bedrock::{BedrockKey, BedrockWorld};
+/// use std::path::PathBuf;
+///
+/// let world = BedrockWorld::open(PathBuf::from("./world")).unwrap();
+///
+/// for pos in world.chunks().unwrap() {
+///     let version = world.get(&BedrockKey::Version(pos)).unwrap();
+/// }
+/// ```
+pub struct BedrockWorld {
+    db: LevelDb,
+}
+
+impl BedrockWorld {
+    /// Opens the world in the given folder, the one holding `level.dat` and the `db` folder.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: PathBuf` - The path to the world folder.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BedrockWorld)` - The opened world.
+    /// * `Err(NbtError)` - If the world's database could not be opened.
+    pub fn open(path: PathBuf) -> Result<BedrockWorld, NbtError> {
+        Ok(BedrockWorld {
+            db: LevelDb::open(path.join("db"))?,
+        })
+    }
+
+    /// Returns the world's database, for reading keys directly.
+    pub fn db(&self) -> &LevelDb {
+        &self.db
+    }
+
+    /// Returns the value stored for a key, or `None` if the world has no such record.
+    pub fn get(&self, key: &BedrockKey) -> Result<Option<Vec<u8>>, NbtError> {
+        self.db.get(&key.to_bytes())
+    }
+
+    /// Returns the subchunk at the given Y index of a chunk, or `None` if it is not stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos: ChunkPos` - The position of the chunk.
+    /// * `y: i8` - The Y index of the subchunk, the block Y coordinate divided by 16.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(SubChunk))` - The parsed subchunk.
+    /// * `Ok(None)` - If the world has no such subchunk, which means it is all air.
+    /// * `Err(NbtError)` - If the subchunk could not be read or parsed.
+    pub fn sub_chunk(&self, pos: ChunkPos, y: i8) -> Result<Option<SubChunk>, NbtError> {
+        self.get(&BedrockKey::SubChunkPrefix(pos, y))?
+            .map(|data| SubChunk::parse(&data))
+            .transpose()
+    }
+
+    /// Returns the heightmap and biomes of a chunk saved since 1.18, or `None` if it has no `Data3D` record.
+    pub fn data_3d(&self, pos: ChunkPos) -> Result<Option<Data3D>, NbtError> {
+        self.get(&BedrockKey::Data3D(pos))?
+            .map(|data| Data3D::parse(&data))
+            .transpose()
+    }
+
+    /// Returns the heightmap and biomes of a chunk saved before 1.18, or `None` if it has no `Data2D` record.
+    pub fn data_2d(&self, pos: ChunkPos) -> Result<Option<Data2D>, NbtError> {
+        self.get(&BedrockKey::Data2D(pos))?
+            .map(|data| Data2D::parse(&data))
+            .transpose()
+    }
+
+    /// Returns the block entities of a chunk, or an empty list if it has none.
+    pub fn block_entities(&self, pos: ChunkPos) -> Result<Vec<NbtValue>, NbtError> {
+        match self.get(&BedrockKey::BlockEntity(pos))? {
+            Some(data) => NbtRecords::new(&data).collect(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the entities of a chunk, or an empty list if it has none.
+    ///
+    /// Entities are read from the chunk's legacy `Entity` record, then from the `Actor` records its `Digest`
+    /// lists. Ids in the digest without an actor record are skipped.
+    pub fn entities(&self, pos: ChunkPos) -> Result<Vec<NbtValue>, NbtError> {
+        let mut entities = match self.get(&BedrockKey::Entity(pos))? {
+            Some(data) => NbtRecords::new(&data).collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+
+        if let Some(digest) = self.get(&BedrockKey::Digest(pos))? {
+            for id in parse_digest(&digest)? {
+                if let Some(data) = self.get(&BedrockKey::Actor(id))? {
+                    let (_, entity) = NbtReader::new(&data[..], Endian::Little).parse_root()?;
+                    entities.push(entity);
+                }
+            }
+        }
+
+        Ok(entities)
+    }
+
+    /// Returns the player stored under a `LocalPlayer`, `PlayerServer` or `Player` key, or `None` if the world
+    /// has no such record.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use commandblock::bedrock::{BedrockKey, BedrockWorld};
+    /// use std::path::PathBuf;
+    ///
+    /// let world = BedrockWorld::open(PathBuf::from("./world")).unwrap();
+    /// let player = world.player(&BedrockKey::LocalPlayer).unwrap().unwrap();
+    /// println!("Level {}", player.xp_level);
+    /// ```
+    pub fn player(&self, key: &BedrockKey) -> Result<Option<PlayerData>, NbtError> {
+        self.get(key)?
+            .map(|data| PlayerData::from_bedrock_record(&data))
+            .transpose()
+    }
+
+    /// Returns every key in the world's database, decoded, in the database's order.
+    pub fn keys(&self) -> Result<Vec<BedrockKey>, NbtError> {
+        Ok(self
+            .db
+            .keys()?
+            .iter()
+            .map(|key| BedrockKey::from_bytes(key))
+            .collect())
+    }
+
+    /// Returns the position of every chunk that has records in the world, sorted by dimension, then X, then Z.
+    pub fn chunks(&self) -> Result<Vec<ChunkPos>, NbtError> {
+        let chunks: BTreeSet<ChunkPos> = self
+            .keys()?
+            .iter()
+            .filter_map(BedrockKey::chunk_pos)
+            .collect();
+        Ok(chunks.into_iter().collect())
+    }
+}
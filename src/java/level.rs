@@ -0,0 +1,325 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use crate::nbt::{
+    fields::{from_nbt_field, list_of, Fields},
+    read_from_file, write_to_file, Compression, Endian, NbtError, NbtValue,
+};
+
+/// `JavaVersion` is the `Version` compound of a Java `level.dat`, the game version that last saved the world.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JavaVersion {
+    /// The data version of the game, the same number as `DataVersion`.
+    pub id: i32,
+    /// The name of the game version, such as `1.19.3`.
+    pub name: String,
+    /// Whether the game version is a snapshot.
+    pub snapshot: bool,
+    /// The fields not covered above, such as `Series`.
+    pub other: HashMap<String, NbtValue>,
+}
+
+impl JavaVersion {
+    /// Reads the version from its compound.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(JavaVersion)` - The version.
+    /// * `Err(NbtError)` - `PathNotFound` if a field is missing, or `TypeMismatch` if one has the wrong type.
+    pub fn from_nbt(value: NbtValue) -> Result<JavaVersion, NbtError> {
+        let mut fields = Fields::new(value)?;
+        Ok(JavaVersion {
+            id: fields.required("Id")?,
+            name: fields.required("Name")?,
+            snapshot: fields.required("Snapshot")?,
+            other: fields.into_rest(),
+        })
+    }
+
+    /// Converts the version back into its compound.
+    pub fn to_nbt(&self) -> NbtValue {
+        let mut map = self.other.clone();
+        map.insert("Id".to_string(), self.id.into());
+        map.insert("Name".to_string(), self.name.as_str().into());
+        map.insert("Snapshot".to_string(), self.snapshot.into());
+        NbtValue::Compound(map)
+    }
+}
+
+from_nbt_field!(JavaVersion);
+
+/// `WorldGenSettings` is the `WorldGenSettings` compound of a Java `level.dat`, added in 1.16.
+///
+/// The generator of each dimension is left as NBT, as its shape depends on the generator type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldGenSettings {
+    pub seed: i64,
+    pub generate_features: bool,
+    pub bonus_chest: bool,
+    /// Each dimension by its id, such as `minecraft:overworld`.
+    pub dimensions: HashMap<String, NbtValue>,
+    /// The fields not covered above.
+    pub other: HashMap<String, NbtValue>,
+}
+
+impl WorldGenSettings {
+    /// Reads the settings from their compound.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(WorldGenSettings)` - The settings.
+    /// * `Err(NbtError)` - `PathNotFound` if a field is missing, or `TypeMismatch` if one has the wrong type.
+    pub fn from_nbt(value: NbtValue) -> Result<WorldGenSettings, NbtError> {
+        let mut fields = Fields::new(value)?;
+        Ok(WorldGenSettings {
+            seed: fields.required("seed")?,
+            generate_features: fields.required("generate_features")?,
+            bonus_chest: fields.required("bonus_chest")?,
+            dimensions: fields.required("dimensions")?,
+            other: fields.into_rest(),
+        })
+    }
+
+    /// Converts the settings back into their compound.
+    pub fn to_nbt(&self) -> NbtValue {
+        let mut map = self.other.clone();
+        map.insert("seed".to_string(), self.seed.into());
+        map.insert(
+            "generate_features".to_string(),
+            self.generate_features.into(),
+        );
+        map.insert("bonus_chest".to_string(), self.bonus_chest.into());
+        map.insert("dimensions".to_string(), self.dimensions.clone().into());
+        NbtValue::Compound(map)
+    }
+}
+
+from_nbt_field!(WorldGenSettings);
+
+/// `DataPacks` is the `DataPacks` compound of a Java `level.dat`, the data packs of the world by id.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DataPacks {
+    /// The enabled data packs in load order, such as `vanilla` or `file/pack.zip`.
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+    /// The fields not covered above.
+    pub other: HashMap<String, NbtValue>,
+}
+
+impl DataPacks {
+    /// Reads the data packs from their compound.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(DataPacks)` - The data packs.
+    /// * `Err(NbtError)` - `TypeMismatch` if a list does not hold strings.
+    pub fn from_nbt(value: NbtValue) -> Result<DataPacks, NbtError> {
+        let mut fields = Fields::new(value)?;
+        Ok(DataPacks {
+            enabled: fields.optional("Enabled")?.unwrap_or_default(),
+            disabled: fields.optional("Disabled")?.unwrap_or_default(),
+            other: fields.into_rest(),
+        })
+    }
+
+    /// Converts the data packs back into their compound.
+    pub fn to_nbt(&self) -> NbtValue {
+        // The game writes empty lists as lists of End tags.
+        let mut map = self.other.clone();
+        map.insert("Enabled".to_string(), list_of(0x00, &self.enabled));
+        map.insert("Disabled".to_string(), list_of(0x00, &self.disabled));
+        NbtValue::Compound(map)
+    }
+}
+
+from_nbt_field!(DataPacks);
+
+/// `JavaLevelData` is the `Data` compound of a Java Edition `level.dat`, with typed access to its common fields.
+///
+/// Fields this struct does not know, such as the `Player` of a singleplayer world, are kept in `other` and
+/// written back unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::java::JavaLevelData;
+/// use std::path::PathBuf;
+///
+/// let mut level = JavaLevelData::read_from_file(PathBuf::from("./tests/data/java_level.dat")).unwrap();
+/// assert_eq!(level.version.as_ref().unwrap().name, "1.19.3");
+///
+/// level
+///     .game_rules
+///     .get_or_insert_with(Default::default)
+///     .insert("keepInventory".to_string(), "true".to_string());
+/// level.write_to_file(PathBuf::from("./tests/data/test.dat")).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct JavaLevelData {
+    /// The data version of the game that last saved the world, missing before 1.9.
+    pub data_version: Option<i32>,
+    pub version: Option<JavaVersion>,
+    /// The name of the world shown in the world list.
+    pub level_name: String,
+    pub spawn_x: i32,
+    pub spawn_y: i32,
+    pub spawn_z: i32,
+    pub spawn_angle: Option<f32>,
+    /// The number of ticks the world has run for.
+    pub time: i64,
+    /// The time of day in ticks, which keeps counting past 24000.
+    pub day_time: i64,
+    /// When the world was last played, in milliseconds since the Unix epoch.
+    pub last_played: i64,
+    pub raining: bool,
+    /// The number of ticks until `raining` toggles.
+    pub rain_time: i32,
+    pub thundering: bool,
+    /// The number of ticks until `thundering` toggles.
+    pub thunder_time: i32,
+    /// The number of ticks of clear weather left after `/weather clear`.
+    pub clear_weather_time: Option<i32>,
+    /// The default game mode: 0 survival, 1 creative, 2 adventure or 3 spectator.
+    pub game_type: i32,
+    /// The difficulty, from 0 peaceful to 3 hard.
+    pub difficulty: Option<i8>,
+    pub hardcore: bool,
+    pub allow_commands: Option<bool>,
+    /// The game rules by name, if the file has them. Java stores every game rule as a string, such as `"true"`
+    /// or `"3"`.
+    pub game_rules: Option<BTreeMap<String, String>>,
+    pub world_gen_settings: Option<WorldGenSettings>,
+    pub data_packs: Option<DataPacks>,
+    /// The fields not covered above.
+    pub other: HashMap<String, NbtValue>,
+}
+
+impl JavaLevelData {
+    /// Reads the level data from the `Data` compound of a `level.dat`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value: NbtValue` - The `Data` compound inside the root compound.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(JavaLevelData)` - The level data.
+    /// * `Err(NbtError)` - `PathNotFound` if a field such as `LevelName` is missing, or `TypeMismatch` if a
+    ///   field has the wrong type.
+    pub fn from_nbt(value: NbtValue) -> Result<JavaLevelData, NbtError> {
+        let mut fields = Fields::new(value)?;
+        Ok(JavaLevelData {
+            data_version: fields.optional("DataVersion")?,
+            version: fields.optional("Version")?,
+            level_name: fields.required("LevelName")?,
+            spawn_x: fields.required("SpawnX")?,
+            spawn_y: fields.required("SpawnY")?,
+            spawn_z: fields.required("SpawnZ")?,
+            spawn_angle: fields.optional("SpawnAngle")?,
+            time: fields.required("Time")?,
+            day_time: fields.required("DayTime")?,
+            last_played: fields.required("LastPlayed")?,
+            raining: fields.required("raining")?,
+            rain_time: fields.required("rainTime")?,
+            thundering: fields.required("thundering")?,
+            thunder_time: fields.required("thunderTime")?,
+            clear_weather_time: fields.optional("clearWeatherTime")?,
+            game_type: fields.required("GameType")?,
+            difficulty: fields.optional("Difficulty")?,
+            hardcore: fields.required("hardcore")?,
+            allow_commands: fields.optional("allowCommands")?,
+            game_rules: fields.optional("GameRules")?,
+            world_gen_settings: fields.optional("WorldGenSettings")?,
+            data_packs: fields.optional("DataPacks")?,
+            other: fields.into_rest(),
+        })
+    }
+
+    /// Converts the level data back into the `Data` compound of a `level.dat`.
+    pub fn to_nbt(&self) -> NbtValue {
+        let mut map = self.other.clone();
+        if let Some(data_version) = self.data_version {
+            map.insert("DataVersion".to_string(), data_version.into());
+        }
+        if let Some(version) = &self.version {
+            map.insert("Version".to_string(), version.to_nbt());
+        }
+        map.insert("LevelName".to_string(), self.level_name.as_str().into());
+        map.insert("SpawnX".to_string(), self.spawn_x.into());
+        map.insert("SpawnY".to_string(), self.spawn_y.into());
+        map.insert("SpawnZ".to_string(), self.spawn_z.into());
+        if let Some(spawn_angle) = self.spawn_angle {
+            map.insert("SpawnAngle".to_string(), spawn_angle.into());
+        }
+        map.insert("Time".to_string(), self.time.into());
+        map.insert("DayTime".to_string(), self.day_time.into());
+        map.insert("LastPlayed".to_string(), self.last_played.into());
+        map.insert("raining".to_string(), self.raining.into());
+        map.insert("rainTime".to_string(), self.rain_time.into());
+        map.insert("thundering".to_string(), self.thundering.into());
+        map.insert("thunderTime".to_string(), self.thunder_time.into());
+        if let Some(clear_weather_time) = self.clear_weather_time {
+            map.insert("clearWeatherTime".to_string(), clear_weather_time.into());
+        }
+        map.insert("GameType".to_string(), self.game_type.into());
+        if let Some(difficulty) = self.difficulty {
+            map.insert("Difficulty".to_string(), difficulty.into());
+        }
+        map.insert("hardcore".to_string(), self.hardcore.into());
+        if let Some(allow_commands) = self.allow_commands {
+            map.insert("allowCommands".to_string(), allow_commands.into());
+        }
+
+        if let Some(game_rules) = &self.game_rules {
+            let game_rules: HashMap<String, NbtValue> = game_rules
+                .iter()
+                .map(|(name, value)| (name.clone(), value.into()))
+                .collect();
+            map.insert("GameRules".to_string(), game_rules.into());
+        }
+
+        if let Some(settings) = &self.world_gen_settings {
+            map.insert("WorldGenSettings".to_string(), settings.to_nbt());
+        }
+        if let Some(data_packs) = &self.data_packs {
+            map.insert("DataPacks".to_string(), data_packs.to_nbt());
+        }
+        NbtValue::Compound(map)
+    }
+
+    /// Reads the level data from a gzip compressed `level.dat` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: PathBuf` - The path to the `level.dat` file of a Java world.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(JavaLevelData)` - The level data.
+    /// * `Err(NbtError)` - If the file could not be read, has no `Data` compound, or a field is missing or has
+    ///   the wrong type.
+    pub fn read_from_file(path: PathBuf) -> Result<JavaLevelData, NbtError> {
+        let (_, value) = read_from_file(path, Compression::Gzip, Endian::Big)?;
+        Fields::new(value)?.required("Data")
+    }
+
+    /// Writes the level data to a gzip compressed `level.dat` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: PathBuf` - The path to write to. An existing file is overwritten.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the file was written.
+    /// * `Err(NbtError)` - If the file could not be written.
+    pub fn write_to_file(&self, path: PathBuf) -> Result<(), NbtError> {
+        let mut root = NbtValue::new();
+        root.insert("Data".to_string(), self.to_nbt());
+        write_to_file(Some(""), root, path, Compression::Gzip, Endian::Big)
+    }
+}
+
+from_nbt_field!(JavaLevelData);
@@ -0,0 +1,3 @@
+mod level;
+
+pub use level::*;
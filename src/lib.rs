@@ -25,3 +25,52 @@
 /// write_to_file(Some(&name), value, path, Compression::Uncompressed, Endian::Little).unwrap();
 /// ```
 pub mod nbt;
+
+/// This module provides functionality for reading Bedrock Edition worlds, which store their chunks and other
+/// records in a LevelDB database.
+///
+/// # Example
+///
+/// ```no_run
+/// use commandblock::bedrock::{BedrockKey, BedrockWorld};
+/// use std::path::PathBuf;
+///
+/// let world = BedrockWorld::open(PathBuf::from("./world")).unwrap();
+///
+/// for key in world.keys().unwrap() {
+///     if let BedrockKey::SubChunkPrefix(pos, y) = key {
+///         println!("Subchunk {} at {}", y, pos);
+///     }
+/// }
+/// ```
+pub mod bedrock;
+
+/// This module provides typed models of Java Edition world files, which keep the fields they do not cover so the
+/// files can be edited and written back safely.
+///
+/// # Example
+///
+/// ```
+/// use commandblock::java::JavaLevelData;
+/// use std::path::PathBuf;
+///
+/// let level = JavaLevelData::read_from_file(PathBuf::from("./tests/data/java_level.dat")).unwrap();
+/// println!("{} was last played in {}", level.level_name, level.version.unwrap().name);
+/// ```
+pub mod java;
+
+/// This module provides typed models of players and their items for both editions, which keep the fields they do
+/// not cover so inventories can be edited and written back safely.
+///
+/// # Example
+///
+/// ```no_run
+/// use commandblock::player::PlayerData;
+/// use std::path::PathBuf;
+///
+/// let player = PlayerData::read_from_file(PathBuf::from("./world/playerdata/uuid.dat")).unwrap();
+/// for item in &player.inventory {
+///     println!("{} x{} in slot {:?}", item.id, item.count, item.slot);
+/// }
+/// ```
+pub mod player;
@@ -0,0 +1,225 @@
+use std::{
+    borrow::Borrow,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use async_compression::{
+    tokio::{
+        bufread::{GzipDecoder, ZlibDecoder},
+        write::{GzipEncoder, ZlibEncoder},
+    },
+    Level,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::nbt::{
+    compression::{self, DEFAULT_COMPRESSION_LEVEL},
+    reader::{read_from_reader, NbtReader},
+    types::{Compression, Endian, NbtError, NbtValue},
+    writer::NbtWriter,
+};
+
+/// Reads NBT data from the given async reader, decompresses it if necessary, and returns the parsed NBT value.
+///
+/// The data is read and decompressed without blocking, then parsed in memory, so the whole decompressed data is
+/// buffered while it is parsed.
+///
+/// # Arguments
+///
+/// * `reader: R` - The async reader from which NBT data is read.
+/// * `compression: Compression` - The compression method used in the NBT data.
+/// * `endian_style: Endian` - The endian style of the NBT data (Big or Little).
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::nbt::{read_from_async_reader, Compression, Endian};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let file = tokio::fs::File::open("./tests/data/java_level.dat").await.unwrap();
+///
+/// let (name, value) = read_from_async_reader(file, Compression::Gzip, Endian::Big).await.unwrap();
+/// # });
+/// ```
+///
+/// # Returns
+///
+/// * `Ok((String, NbtValue))` - Returns a tuple containing the root tag name and the parsed NBT value on success.
+/// * `Err(NbtError)` - Returns an NbtError on failure.
+pub async fn read_from_async_reader<R: AsyncRead + Unpin>(
+    reader: R,
+    compression: Compression,
+    endian_style: Endian,
+) -> Result<(String, NbtValue), NbtError> {
+    let mut data = Vec::new();
+    match compression {
+        Compression::Uncompressed => {
+            let mut reader = reader;
+            reader.read_to_end(&mut data).await?;
+        }
+        Compression::Gzip => {
+            GzipDecoder::new(BufReader::new(reader))
+                .read_to_end(&mut data)
+                .await?;
+        }
+        Compression::Zlib => {
+            ZlibDecoder::new(BufReader::new(reader))
+                .read_to_end(&mut data)
+                .await?;
+        }
+        Compression::Lz4 => {
+            // async-compression has no decoder for the LZ4 block stream format, so the compressed data is read
+            // first and decompressed in memory.
+            let mut reader = reader;
+            reader.read_to_end(&mut data).await?;
+            return read_from_reader(&data[..], compression, endian_style);
+        }
+    }
+
+    NbtReader::new(&data[..], endian_style).parse_data()
+}
+
+/// Writes NBT data to the given async writer using the given compression and endian style.
+///
+/// The data is encoded in memory, then compressed and written without blocking. The writer is flushed but not
+/// shut down.
+///
+/// # Arguments
+///
+/// * `data_name: Option<&str>` - Optional name for the root tag of the NBT data.
+/// * `value: V` - The NBT value to write, either a `NbtValue` or a `&NbtValue`.
+/// * `writer: &mut W` - The async writer to write to.
+/// * `compression: Compression` - The compression method to use.
+/// * `endian: Endian` - The byte order to use.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::nbt::{write_to_async_writer, Compression, Endian, NbtValue};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut value = NbtValue::new();
+/// value.insert("test".to_string(), "test string");
+///
+/// let mut writer = Vec::new();
+///
+/// write_to_async_writer(None, &value, &mut writer, Compression::Gzip, Endian::Big).await.unwrap();
+/// # });
+/// ```
+///
+/// # Returns
+///
+/// * `Ok(())` - If the NBT value is successfully written to the writer.
+/// * `Err(NbtError)` - If there is an error while writing the NBT value to the writer.
+pub async fn write_to_async_writer<V: Borrow<NbtValue>, W: AsyncWrite + Unpin>(
+    data_name: Option<&str>,
+    value: V,
+    writer: &mut W,
+    compression: Compression,
+    endian: Endian,
+) -> Result<(), NbtError> {
+    write_to_async_writer_with_level(
+        data_name,
+        value,
+        writer,
+        compression,
+        endian,
+        DEFAULT_COMPRESSION_LEVEL,
+    )
+    .await
+}
+
+/// Writes NBT data to the given async writer like `write_to_async_writer`, using the given compression level.
+///
+/// # Arguments
+///
+/// * `data_name: Option<&str>` - Optional name for the root tag of the NBT data.
+/// * `value: V` - The NBT value to write, either a `NbtValue` or a `&NbtValue`.
+/// * `writer: &mut W` - The async writer to write to.
+/// * `compression: Compression` - The compression method to use.
+/// * `endian: Endian` - The byte order to use.
+/// * `level: u32` - The compression level, from 0 (fastest) to 9 (smallest). Only gzip and zlib use it.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the NBT value is successfully written to the writer.
+/// * `Err(NbtError)` - If there is an error while writing the NBT value to the writer.
+pub async fn write_to_async_writer_with_level<V: Borrow<NbtValue>, W: AsyncWrite + Unpin>(
+    data_name: Option<&str>,
+    value: V,
+    writer: &mut W,
+    compression: Compression,
+    endian: Endian,
+    level: u32,
+) -> Result<(), NbtError> {
+    let value = value.borrow();
+    value.validate()?;
+
+    let quality = Level::Precise(level.min(9) as i32);
+
+    match compression {
+        Compression::Uncompressed => {
+            writer.write_all(&encode(data_name, value, endian)?).await?;
+        }
+        Compression::Gzip => {
+            let mut encoder = GzipEncoder::with_quality(NoShutdown(&mut *writer), quality);
+            encoder
+                .write_all(&encode(data_name, value, endian)?)
+                .await?;
+            encoder.shutdown().await?;
+        }
+        Compression::Zlib => {
+            let mut encoder = ZlibEncoder::with_quality(NoShutdown(&mut *writer), quality);
+            encoder
+                .write_all(&encode(data_name, value, endian)?)
+                .await?;
+            encoder.shutdown().await?;
+        }
+        Compression::Lz4 => {
+            let mut data = Vec::new();
+            compression::encode(&mut data, compression, level, |data| {
+                NbtWriter::new(data, endian).write_data(data_name, value)
+            })?;
+            writer.write_all(&data).await?;
+        }
+    }
+
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Encodes the NBT data uncompressed into memory.
+fn encode<V: Borrow<NbtValue>>(
+    data_name: Option<&str>,
+    value: V,
+    endian: Endian,
+) -> Result<Vec<u8>, NbtError> {
+    let mut data = Vec::new();
+    NbtWriter::new(&mut data, endian).write_data(data_name, value)?;
+    Ok(data)
+}
+
+/// Forwards writes to the inner writer, but only flushes it when shut down.
+///
+/// Shutting down an encoder is how its compressed stream is finished, and that must not close the caller's writer.
+struct NoShutdown<'a, W: AsyncWrite + Unpin>(&'a mut W);
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for NoShutdown<'_, W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut *self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0).poll_flush(cx)
+    }
+}
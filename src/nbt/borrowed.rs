@@ -0,0 +1,426 @@
+use std::{borrow::Cow, collections::HashMap, marker::PhantomData};
+
+use crate::nbt::{
+    list::NbtList,
+    path::NbtPathNode,
+    types::{Endian, NbtError, NbtValue},
+};
+
+/// `NbtArrayRef` is an int or long array borrowed from the input bytes. Elements are decoded on access.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NbtArrayRef<'a, T> {
+    bytes: &'a [u8],
+    endian: Endian,
+    element: PhantomData<T>,
+}
+
+macro_rules! impl_array_ref {
+    ($type:ty, $size:expr) => {
+        impl<'a> NbtArrayRef<'a, $type> {
+            /// Returns the number of elements in the array.
+            pub fn len(&self) -> usize {
+                self.bytes.len() / $size
+            }
+
+            /// Returns `true` if the array contains no elements.
+            pub fn is_empty(&self) -> bool {
+                self.bytes.is_empty()
+            }
+
+            /// Returns the element at the given index, decoding it from the input bytes.
+            pub fn get(&self, index: usize) -> Option<$type> {
+                let start = index.checked_mul($size)?;
+                let chunk = self.bytes.get(start..start + $size)?;
+                Some(decode::<$type, $size>(chunk, self.endian))
+            }
+
+            /// Returns an iterator over the decoded elements.
+            pub fn iter(&self) -> impl Iterator<Item = $type> + 'a {
+                let endian = self.endian;
+                self.bytes
+                    .chunks_exact($size)
+                    .map(move |chunk| decode::<$type, $size>(chunk, endian))
+            }
+
+            /// Decodes the whole array into an owned vector.
+            pub fn to_vec(&self) -> Vec<$type> {
+                self.iter().collect()
+            }
+        }
+    };
+}
+
+trait FromBytes<const N: usize> {
+    fn from_be(bytes: [u8; N]) -> Self;
+    fn from_le(bytes: [u8; N]) -> Self;
+}
+
+impl FromBytes<4> for i32 {
+    fn from_be(bytes: [u8; 4]) -> Self {
+        i32::from_be_bytes(bytes)
+    }
+    fn from_le(bytes: [u8; 4]) -> Self {
+        i32::from_le_bytes(bytes)
+    }
+}
+
+impl FromBytes<8> for i64 {
+    fn from_be(bytes: [u8; 8]) -> Self {
+        i64::from_be_bytes(bytes)
+    }
+    fn from_le(bytes: [u8; 8]) -> Self {
+        i64::from_le_bytes(bytes)
+    }
+}
+
+fn decode<T: FromBytes<N>, const N: usize>(chunk: &[u8], endian: Endian) -> T {
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(chunk);
+    match endian {
+        Endian::Big => T::from_be(bytes),
+        Endian::Little => T::from_le(bytes),
+    }
+}
+
+impl_array_ref!(i32, 4);
+impl_array_ref!(i64, 8);
+
+/// Enum representing an NBT value borrowed from a byte slice, as parsed by `NbtRef::parse_data`.
+///
+/// Strings, keys and arrays refer to the input bytes in place instead of being copied, which suits
+/// memory-mapped region files. Lists and compounds still hold their children in small vectors.
+/// Use `NbtRef::to_nbt_value` to turn the value into an owned `NbtValue` when needed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtRef<'a> {
+    /// Represents the end of a compound NBT tag.
+    End,
+    /// Represents a byte (8 bits).
+    Byte(i8),
+    /// Represents a short integer (16 bits).
+    Short(i16),
+    /// Represents an integer (32 bits).
+    Int(i32),
+    /// Represents a long integer (64 bits).
+    Long(i64),
+    /// Represents a floating point number (32 bits).
+    Float(f32),
+    /// Represents a double precision floating point number (64 bits).
+    Double(f64),
+    /// Represents an array of bytes, borrowed as unsigned bytes.
+    ByteArray(&'a [u8]),
+    /// Represents a string. Only strings that are not valid UTF-8 are copied.
+    String(Cow<'a, str>),
+    /// Represents a list of NBT values, with the element tag type first so empty lists keep it.
+    List(u8, Vec<NbtRef<'a>>),
+    /// Represents a compound NBT tag, with entries in the order they were read.
+    Compound(Vec<(Cow<'a, str>, NbtRef<'a>)>),
+    /// Represents an array of integers (32 bits each).
+    IntArray(NbtArrayRef<'a, i32>),
+    /// Represents an array of long integers (64 bits each).
+    LongArray(NbtArrayRef<'a, i64>),
+}
+
+/// `SliceReader` reads NBT data from a byte slice, handing out sub-slices instead of copies.
+struct SliceReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    endian: Endian,
+}
+
+impl<'a> SliceReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], NbtError> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(NbtError::UnexpectedEof)?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], NbtError> {
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+
+    fn parse_byte(&mut self) -> Result<i8, NbtError> {
+        Ok(self.take(1)?[0] as i8)
+    }
+
+    fn parse_short(&mut self) -> Result<i16, NbtError> {
+        let bytes = self.take_array()?;
+        Ok(match self.endian {
+            Endian::Big => i16::from_be_bytes(bytes),
+            Endian::Little => i16::from_le_bytes(bytes),
+        })
+    }
+
+    fn parse_int(&mut self) -> Result<i32, NbtError> {
+        let bytes = self.take_array()?;
+        Ok(match self.endian {
+            Endian::Big => i32::from_be_bytes(bytes),
+            Endian::Little => i32::from_le_bytes(bytes),
+        })
+    }
+
+    fn parse_long(&mut self) -> Result<i64, NbtError> {
+        let bytes = self.take_array()?;
+        Ok(match self.endian {
+            Endian::Big => i64::from_be_bytes(bytes),
+            Endian::Little => i64::from_le_bytes(bytes),
+        })
+    }
+
+    fn parse_string(&mut self) -> Result<Cow<'a, str>, NbtError> {
+        let length = self.parse_short()? as u16 as usize;
+        Ok(String::from_utf8_lossy(self.take(length)?))
+    }
+
+    fn parse_array(
+        &mut self,
+        size: usize,
+        error: fn(usize) -> NbtError,
+    ) -> Result<&'a [u8], NbtError> {
+        let length = self.parse_int()?;
+        if length < 0 {
+            return Err(error(length as usize));
+        }
+        let byte_length = (length as usize)
+            .checked_mul(size)
+            .ok_or(error(length as usize))?;
+        self.take(byte_length)
+    }
+
+    fn parse_root(&mut self) -> Result<(Cow<'a, str>, NbtRef<'a>), NbtError> {
+        if self.endian == Endian::Little {
+            self.take(8)?;
+        }
+
+        let header = self.parse_byte()? as u8;
+        match NbtValue::from_binary(header) {
+            Some(NbtValue::End) => Ok((Cow::Borrowed(""), NbtRef::End)),
+            Some(_) => {
+                let name = self.parse_string()?;
+                Ok((name, self.parse_value(header)?))
+            }
+            None => Err(NbtError::InvalidTagType(header)),
+        }
+    }
+
+    fn parse_value(&mut self, tag_type: u8) -> Result<NbtRef<'a>, NbtError> {
+        let value = match tag_type {
+            0x00 => NbtRef::End,
+            0x01 => NbtRef::Byte(self.parse_byte()?),
+            0x02 => NbtRef::Short(self.parse_short()?),
+            0x03 => NbtRef::Int(self.parse_int()?),
+            0x04 => NbtRef::Long(self.parse_long()?),
+            0x05 => NbtRef::Float(f32::from_bits(self.parse_int()? as u32)),
+            0x06 => NbtRef::Double(f64::from_bits(self.parse_long()? as u64)),
+            0x07 => NbtRef::ByteArray(self.parse_array(1, NbtError::InvalidByteArrayLength)?),
+            0x08 => NbtRef::String(self.parse_string()?),
+            0x09 => {
+                let list_tag_type = self.parse_byte()? as u8;
+                let length = self.parse_int()?.max(0) as usize;
+
+                if length > 0
+                    && matches!(
+                        NbtValue::from_binary(list_tag_type),
+                        None | Some(NbtValue::End)
+                    )
+                {
+                    return Err(NbtError::InvalidTagType(list_tag_type));
+                }
+
+                // Every element takes at least one byte, which bounds the allocation for corrupted lengths.
+                let remaining = self.data.len() - self.position;
+                let mut vec = Vec::with_capacity(length.min(remaining));
+                for index in 0..length {
+                    let value = self.parse_value(list_tag_type).map_err(|error| {
+                        error.within(NbtPathNode::Index(index as i32), Some(self.position as u64))
+                    })?;
+                    vec.push(value);
+                }
+                NbtRef::List(list_tag_type, vec)
+            }
+            0x0A => {
+                let mut entries = Vec::new();
+                loop {
+                    let tag = self.parse_byte()? as u8;
+                    if tag == 0x00 {
+                        break;
+                    }
+                    if NbtValue::from_binary(tag).is_none() {
+                        return Err(NbtError::InvalidTagType(tag));
+                    }
+                    let key = self.parse_string()?;
+                    let value = self.parse_value(tag).map_err(|error| {
+                        error.within(
+                            NbtPathNode::Key(key.to_string()),
+                            Some(self.position as u64),
+                        )
+                    })?;
+                    entries.push((key, value));
+                }
+                NbtRef::Compound(entries)
+            }
+            0x0B => NbtRef::IntArray(NbtArrayRef {
+                bytes: self.parse_array(4, NbtError::InvalidIntArrayLength)?,
+                endian: self.endian,
+                element: PhantomData,
+            }),
+            0x0C => NbtRef::LongArray(NbtArrayRef {
+                bytes: self.parse_array(8, NbtError::InvalidLongArrayLength)?,
+                endian: self.endian,
+                element: PhantomData,
+            }),
+            _ => return Err(NbtError::InvalidTagType(tag_type)),
+        };
+        Ok(value)
+    }
+}
+
+impl<'a> NbtRef<'a> {
+    /// Parses NBT data borrowed from a byte slice and returns the root tag name and the borrowed value.
+    ///
+    /// Little endian data is expected to start with the Bedrock 8-byte header, like `NbtReader::parse_data`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data: &'a [u8]` - The uncompressed NBT data.
+    /// * `endian: Endian` - The endian style (Big or Little) used to interpret the data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{NbtRef, Endian};
+    ///
+    /// let data = std::fs::read("./tests/data/bedrock_level.dat").unwrap();
+    /// let (_, level) = NbtRef::parse_data(&data, Endian::Little).unwrap();
+    ///
+    /// if let Some(NbtRef::String(name)) = level.get("LevelName") {
+    ///     println!("{}", name);
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((Cow<str>, NbtRef))` - Returns a tuple containing the root tag name and the borrowed value on success.
+    /// * `Err(NbtError)` - Returns an NbtError on failure.
+    pub fn parse_data(
+        data: &'a [u8],
+        endian: Endian,
+    ) -> Result<(Cow<'a, str>, NbtRef<'a>), NbtError> {
+        let mut reader = SliceReader {
+            data,
+            position: 0,
+            endian,
+        };
+        reader
+            .parse_root()
+            .map_err(|error| error.at(Some(reader.position as u64)))
+    }
+
+    /// Parses a single NBT value of the given tag type borrowed from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `data: &'a [u8]` - The bytes of the value.
+    /// * `tag_type: u8` - The tag type of the value.
+    /// * `endian: Endian` - The endian style (Big or Little) used to interpret the data.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((NbtRef, usize))` - Returns the borrowed value and the number of bytes it took up on success.
+    /// * `Err(NbtError)` - Returns an NbtError on failure.
+    pub fn parse_value(
+        data: &'a [u8],
+        tag_type: u8,
+        endian: Endian,
+    ) -> Result<(NbtRef<'a>, usize), NbtError> {
+        let mut reader = SliceReader {
+            data,
+            position: 0,
+            endian,
+        };
+        let value = reader
+            .parse_value(tag_type)
+            .map_err(|error| error.at(Some(reader.position as u64)))?;
+        Ok((value, reader.position))
+    }
+
+    /// Returns the value corresponding to the key if this is an `NbtRef::Compound`.
+    pub fn get(&self, key: &str) -> Option<&NbtRef<'a>> {
+        match self {
+            NbtRef::Compound(entries) => entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Returns the tag type of the value, matching `NbtValue::to_binary`.
+    pub fn to_binary(&self) -> u8 {
+        match self {
+            NbtRef::End => 0x0,
+            NbtRef::Byte(_) => 0x1,
+            NbtRef::Short(_) => 0x2,
+            NbtRef::Int(_) => 0x3,
+            NbtRef::Long(_) => 0x4,
+            NbtRef::Float(_) => 0x5,
+            NbtRef::Double(_) => 0x6,
+            NbtRef::ByteArray(_) => 0x7,
+            NbtRef::String(_) => 0x8,
+            NbtRef::List(..) => 0x9,
+            NbtRef::Compound(_) => 0xA,
+            NbtRef::IntArray(_) => 0xB,
+            NbtRef::LongArray(_) => 0xC,
+        }
+    }
+
+    /// Copies the borrowed value into an owned `NbtValue`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{NbtRef, NbtValue, Endian};
+    ///
+    /// let data = [0x00, 0x02, 0x41, 0x42];
+    /// let (value, _) = NbtRef::parse_value(&data, 0x08, Endian::Big).unwrap();
+    ///
+    /// assert_eq!(value.to_nbt_value(), NbtValue::String("AB".to_string()));
+    /// ```
+    pub fn to_nbt_value(&self) -> NbtValue {
+        match self {
+            NbtRef::End => NbtValue::End,
+            NbtRef::Byte(v) => NbtValue::Byte(*v),
+            NbtRef::Short(v) => NbtValue::Short(*v),
+            NbtRef::Int(v) => NbtValue::Int(*v),
+            NbtRef::Long(v) => NbtValue::Long(*v),
+            NbtRef::Float(v) => NbtValue::Float(*v),
+            NbtRef::Double(v) => NbtValue::Double(*v),
+            NbtRef::ByteArray(v) => NbtValue::ByteArray(v.iter().map(|b| *b as i8).collect()),
+            NbtRef::String(v) => NbtValue::String(v.to_string()),
+            NbtRef::List(tag_type, v) => {
+                let mut list = NbtList::with_capacity(*tag_type, v.len());
+                list.extend(v.iter().map(NbtRef::to_nbt_value));
+                NbtValue::List(list)
+            }
+            NbtRef::Compound(v) => NbtValue::Compound(
+                v.iter()
+                    .map(|(key, value)| (key.to_string(), value.to_nbt_value()))
+                    .collect::<HashMap<String, NbtValue>>(),
+            ),
+            NbtRef::IntArray(v) => NbtValue::IntArray(v.to_vec()),
+            NbtRef::LongArray(v) => NbtValue::LongArray(v.to_vec()),
+        }
+    }
+}
+
+impl From<&NbtRef<'_>> for NbtValue {
+    fn from(value: &NbtRef<'_>) -> Self {
+        value.to_nbt_value()
+    }
+}
@@ -0,0 +1,72 @@
+use std::io::{Read, Write};
+
+use flate2::{
+    read::{GzDecoder, ZlibDecoder},
+    write::{GzEncoder, ZlibEncoder},
+};
+
+use crate::nbt::types::{Compression, NbtError};
+
+/// The compression level used when none is given, the same default as gzip and zlib.
+pub const DEFAULT_COMPRESSION_LEVEL: u32 = 6;
+
+#[cfg(not(feature = "lz4"))]
+fn lz4_unsupported() -> NbtError {
+    NbtError::UnsupportedCompression("LZ4 requires the `lz4` feature".to_string())
+}
+
+/// Wraps the reader in a decoder for the given compression.
+pub(crate) fn decoder<'a, R: Read + 'a>(
+    reader: R,
+    compression: Compression,
+) -> Result<Box<dyn Read + 'a>, NbtError> {
+    match compression {
+        Compression::Uncompressed => Ok(Box::new(reader)),
+        Compression::Gzip => Ok(Box::new(GzDecoder::new(reader))),
+        Compression::Zlib => Ok(Box::new(ZlibDecoder::new(reader))),
+        #[cfg(feature = "lz4")]
+        Compression::Lz4 => Ok(Box::new(crate::nbt::lz4::Lz4BlockDecoder::new(reader))),
+        #[cfg(not(feature = "lz4"))]
+        Compression::Lz4 => Err(lz4_unsupported()),
+    }
+}
+
+/// Runs `write` against an encoder for the given compression, then finishes the compressed stream.
+///
+/// The level ranges from 0 (no compression) to 9 (best compression) and only applies to gzip and zlib.
+pub(crate) fn encode<W: Write>(
+    writer: W,
+    compression: Compression,
+    level: u32,
+    write: impl FnOnce(&mut dyn Write) -> Result<(), NbtError>,
+) -> Result<(), NbtError> {
+    let level = flate2::Compression::new(level.min(9));
+
+    match compression {
+        Compression::Uncompressed => {
+            let mut writer = writer;
+            write(&mut writer)?;
+            writer.flush()?;
+        }
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(writer, level);
+            write(&mut encoder)?;
+            encoder.finish()?;
+        }
+        Compression::Zlib => {
+            let mut encoder = ZlibEncoder::new(writer, level);
+            write(&mut encoder)?;
+            encoder.finish()?;
+        }
+        #[cfg(feature = "lz4")]
+        Compression::Lz4 => {
+            let mut encoder = crate::nbt::lz4::Lz4BlockEncoder::new(writer);
+            write(&mut encoder)?;
+            encoder.finish()?;
+        }
+        #[cfg(not(feature = "lz4"))]
+        Compression::Lz4 => return Err(lz4_unsupported()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,250 @@
+use std::fmt::{Display, Formatter};
+
+use crate::nbt::{
+    path::{NbtPath, NbtPathNode},
+    types::{NbtError, NbtValue},
+};
+
+/// Enum representing a single difference between two NBT trees, as produced by `NbtValue::diff`.
+///
+/// Paths are relative to the root value that was diffed, so they can be replayed with `NbtValue::apply_patch`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtChange {
+    /// A value that only exists in the new tree.
+    Added { path: NbtPath, value: NbtValue },
+    /// A value that only exists in the old tree.
+    Removed { path: NbtPath, value: NbtValue },
+    /// A value of the same tag type whose contents changed.
+    Changed {
+        path: NbtPath,
+        old: NbtValue,
+        new: NbtValue,
+    },
+    /// A value whose tag type changed, e.g. from `Int` to `Long`.
+    TypeChanged {
+        path: NbtPath,
+        old: NbtValue,
+        new: NbtValue,
+    },
+}
+
+impl NbtChange {
+    /// Returns the path of the value this change applies to.
+    pub fn path(&self) -> &NbtPath {
+        match self {
+            NbtChange::Added { path, .. }
+            | NbtChange::Removed { path, .. }
+            | NbtChange::Changed { path, .. }
+            | NbtChange::TypeChanged { path, .. } => path,
+        }
+    }
+}
+
+impl Display for NbtChange {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            NbtChange::Added { path, value } => write!(f, "+ {}: {}", path, value.to_snbt()),
+            NbtChange::Removed { path, value } => write!(f, "- {}: {}", path, value.to_snbt()),
+            NbtChange::Changed { path, old, new } => {
+                write!(f, "~ {}: {} -> {}", path, old.to_snbt(), new.to_snbt())
+            }
+            NbtChange::TypeChanged { path, old, new } => write!(
+                f,
+                "~ {}: {} ({}) -> {} ({})",
+                path,
+                old.to_snbt(),
+                old.type_name(),
+                new.to_snbt(),
+                new.type_name()
+            ),
+        }
+    }
+}
+
+fn diff_values(path: &NbtPath, old: &NbtValue, new: &NbtValue, changes: &mut Vec<NbtChange>) {
+    match (old, new) {
+        (NbtValue::Compound(old_map), NbtValue::Compound(new_map)) => {
+            let mut keys: Vec<&String> = old_map.keys().chain(new_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let child = path.join(NbtPathNode::Key(key.clone()));
+                match (old_map.get(key), new_map.get(key)) {
+                    (Some(old), Some(new)) => diff_values(&child, old, new, changes),
+                    (Some(old), None) => changes.push(NbtChange::Removed {
+                        path: child,
+                        value: old.clone(),
+                    }),
+                    (None, Some(new)) => changes.push(NbtChange::Added {
+                        path: child,
+                        value: new.clone(),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (NbtValue::List(old_vec), NbtValue::List(new_vec))
+            if old_vec.element_type() == new_vec.element_type()
+                || (old_vec.is_empty()
+                    && old_vec.element_type() == 0x00
+                    && !new_vec.is_empty()) =>
+        {
+            let common = old_vec.len().min(new_vec.len());
+            for index in 0..common {
+                let child = path.join(NbtPathNode::Index(index as i32));
+                diff_values(&child, &old_vec[index], &new_vec[index], changes);
+            }
+            for (index, value) in new_vec.iter().enumerate().skip(common) {
+                changes.push(NbtChange::Added {
+                    path: path.join(NbtPathNode::Index(index as i32)),
+                    value: value.clone(),
+                });
+            }
+            // Removals are listed from the end so that replaying them keeps earlier indices valid.
+            for (index, value) in old_vec.iter().enumerate().skip(common).rev() {
+                changes.push(NbtChange::Removed {
+                    path: path.join(NbtPathNode::Index(index as i32)),
+                    value: value.clone(),
+                });
+            }
+        }
+        _ if old.to_binary() != new.to_binary() => changes.push(NbtChange::TypeChanged {
+            path: path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ if old != new => changes.push(NbtChange::Changed {
+            path: path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn path_not_found(path: &NbtPath) -> NbtError {
+    NbtError::PathNotFound(path.to_string())
+}
+
+impl NbtValue {
+    /// Compares this value against a newer version and returns the list of changes between them.
+    ///
+    /// Compounds are compared key by key and lists index by index. Lists whose element types differ and
+    /// arrays are reported as a single change.
+    ///
+    /// # Arguments
+    ///
+    /// * `new: &NbtValue` - The newer version of the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{parse_snbt, NbtChange};
+    ///
+    /// let old = parse_snbt("{Time:10,LevelName:\"World\"}").unwrap();
+    /// let new = parse_snbt("{Time:10L,LevelName:\"World\",Raining:1b}").unwrap();
+    ///
+    /// let changes = old.diff(&new);
+    /// assert_eq!(changes.len(), 2);
+    /// assert!(matches!(changes[1], NbtChange::TypeChanged { .. }));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<NbtChange>` - The changes, ordered by path. Empty if both values are equal.
+    pub fn diff(&self, new: &NbtValue) -> Vec<NbtChange> {
+        let mut changes = Vec::new();
+        diff_values(&NbtPath::new(Vec::new()), self, new, &mut changes);
+        changes
+    }
+
+    /// Applies a list of changes, as produced by `NbtValue::diff`, to this value.
+    ///
+    /// # Arguments
+    ///
+    /// * `patch: &[NbtChange]` - The changes to apply, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::parse_snbt;
+    ///
+    /// let mut old = parse_snbt("{Inventory:[{Slot:0b}],XpLevel:3}").unwrap();
+    /// let new = parse_snbt("{Inventory:[{Slot:0b},{Slot:1b}]}").unwrap();
+    ///
+    /// let patch = old.diff(&new);
+    /// old.apply_patch(&patch).unwrap();
+    /// assert_eq!(old, new);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every change was applied.
+    /// * `Err(NbtError::PathNotFound)` - If a change refers to a value that does not exist.
+    pub fn apply_patch(&mut self, patch: &[NbtChange]) -> Result<(), NbtError> {
+        for change in patch {
+            let path = change.path();
+            let (last, parents) = match path.nodes().split_last() {
+                Some(split) => split,
+                None => {
+                    match change {
+                        NbtChange::Changed { new, .. } | NbtChange::TypeChanged { new, .. } => {
+                            *self = new.clone();
+                        }
+                        _ => return Err(path_not_found(path)),
+                    }
+                    continue;
+                }
+            };
+
+            let parent = self
+                .get_path_mut(&NbtPath::new(parents.to_vec()))
+                .ok_or_else(|| path_not_found(path))?;
+
+            match (change, last, parent) {
+                (
+                    NbtChange::Added { value, .. },
+                    NbtPathNode::Key(key),
+                    NbtValue::Compound(map),
+                ) => {
+                    map.insert(key.clone(), value.clone());
+                }
+                (
+                    NbtChange::Added { value, .. },
+                    NbtPathNode::Index(index),
+                    NbtValue::List(vec),
+                ) if *index >= 0 && *index as usize <= vec.len() => {
+                    let element_type = vec.element_type();
+                    if (!vec.is_empty() || element_type != 0x00)
+                        && element_type != value.to_binary()
+                    {
+                        return Err(NbtError::InvalidListType(value.to_binary()));
+                    }
+                    vec.insert(*index as usize, value.clone());
+                }
+                (NbtChange::Removed { .. }, NbtPathNode::Key(key), NbtValue::Compound(map)) => {
+                    map.remove(key).ok_or_else(|| path_not_found(path))?;
+                }
+                (NbtChange::Removed { .. }, NbtPathNode::Index(index), NbtValue::List(vec))
+                    if *index >= 0 && (*index as usize) < vec.len() =>
+                {
+                    vec.remove(*index as usize);
+                }
+                (
+                    NbtChange::Changed { new, .. } | NbtChange::TypeChanged { new, .. },
+                    _,
+                    parent,
+                ) => {
+                    let target = parent
+                        .get_path_mut(&NbtPath::new(vec![last.clone()]))
+                        .ok_or_else(|| path_not_found(path))?;
+                    *target = new.clone();
+                }
+                _ => return Err(path_not_found(path)),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::nbt::{
+    list::NbtList,
+    path::NbtPathNode,
+    types::{NbtError, NbtValue},
+};
+
+/// Converts the value of a compound field into a Rust type for the typed file models.
+pub(crate) trait FromField: Sized {
+    /// Converts the value, returning `TypeMismatch` if it has the wrong tag type.
+    fn from_field(value: NbtValue) -> Result<Self, NbtError>;
+}
+
+fn mismatch(expected: u8, value: &NbtValue) -> NbtError {
+    NbtError::TypeMismatch(expected, value.to_binary())
+}
+
+macro_rules! from_field {
+    ($type:ty, $variant:ident, $tag:expr) => {
+        impl FromField for $type {
+            fn from_field(value: NbtValue) -> Result<Self, NbtError> {
+                match value {
+                    NbtValue::$variant(value) => Ok(value),
+                    value => Err(mismatch($tag, &value)),
+                }
+            }
+        }
+    };
+}
+
+from_field!(i8, Byte, 0x01);
+from_field!(i16, Short, 0x02);
+from_field!(i32, Int, 0x03);
+from_field!(i64, Long, 0x04);
+from_field!(f32, Float, 0x05);
+from_field!(f64, Double, 0x06);
+from_field!(String, String, 0x08);
+from_field!(NbtList, List, 0x09);
+from_field!(HashMap<String, NbtValue>, Compound, 0x0A);
+
+impl FromField for bool {
+    fn from_field(value: NbtValue) -> Result<Self, NbtError> {
+        i8::from_field(value).map(|value| value != 0)
+    }
+}
+
+impl FromField for NbtValue {
+    fn from_field(value: NbtValue) -> Result<Self, NbtError> {
+        Ok(value)
+    }
+}
+
+/// Lists are converted element by element. Arrays are not lists and have their own tag types.
+impl<T: FromField> FromField for Vec<T> {
+    fn from_field(value: NbtValue) -> Result<Self, NbtError> {
+        NbtList::from_field(value)?
+            .into_vec()
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                T::from_field(item)
+                    .map_err(|error| error.within(NbtPathNode::Index(index as i32), None))
+            })
+            .collect()
+    }
+}
+
+/// Compounds whose fields all have the same type are converted field by field.
+impl<T: FromField> FromField for BTreeMap<String, T> {
+    fn from_field(value: NbtValue) -> Result<Self, NbtError> {
+        HashMap::from_field(value)?
+            .into_iter()
+            .map(|(key, item)| match T::from_field(item) {
+                Ok(item) => Ok((key, item)),
+                Err(error) => Err(error.within(NbtPathNode::Key(key), None)),
+            })
+            .collect()
+    }
+}
+
+/// Implements `FromField` for a typed model with a `from_nbt` constructor.
+macro_rules! from_nbt_field {
+    ($type:ty) => {
+        impl $crate::nbt::fields::FromField for $type {
+            fn from_field(value: NbtValue) -> Result<Self, NbtError> {
+                <$type>::from_nbt(value)
+            }
+        }
+    };
+}
+
+pub(crate) use from_nbt_field;
+
+/// Creates a list value holding `values`, which keeps `element_type` if it is empty.
+pub(crate) fn list_of<T: Into<NbtValue>, I: IntoIterator<Item = T>>(
+    element_type: u8,
+    values: I,
+) -> NbtValue {
+    let mut list: NbtList = values.into_iter().map(Into::into).collect();
+    list.set_element_type(element_type);
+    NbtValue::List(list)
+}
+
+/// `Fields` takes the fields of a compound one by one, leaving the fields nobody asked for so they can be
+/// written back unchanged.
+pub(crate) struct Fields {
+    map: HashMap<String, NbtValue>,
+}
+
+impl Fields {
+    /// Starts taking the fields of a compound, returning `TypeMismatch` for any other value.
+    pub(crate) fn new(value: NbtValue) -> Result<Fields, NbtError> {
+        Ok(Fields {
+            map: HashMap::from_field(value)?,
+        })
+    }
+
+    /// Removes and converts a field, returning `PathNotFound` if it is missing.
+    pub(crate) fn required<T: FromField>(&mut self, key: &str) -> Result<T, NbtError> {
+        self.optional(key)?
+            .ok_or_else(|| NbtError::PathNotFound(key.to_string()))
+    }
+
+    /// Removes and converts a field, returning `None` if it is missing.
+    pub(crate) fn optional<T: FromField>(&mut self, key: &str) -> Result<Option<T>, NbtError> {
+        self.map
+            .remove(key)
+            .map(|value| {
+                T::from_field(value)
+                    .map_err(|error| error.within(NbtPathNode::Key(key.to_string()), None))
+            })
+            .transpose()
+    }
+
+    /// Returns whether a field has not been taken yet.
+    pub(crate) fn contains(&self, key: &str) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Removes a field if it has the expected tag type, leaving it in place otherwise.
+    pub(crate) fn take_if(&mut self, key: &str, tag: u8) -> Option<NbtValue> {
+        match self.map.get(key) {
+            Some(value) if value.to_binary() == tag => self.map.remove(key),
+            _ => None,
+        }
+    }
+
+    /// Returns the names of the fields that were not taken yet.
+    pub(crate) fn keys(&self) -> Vec<String> {
+        self.map.keys().cloned().collect()
+    }
+
+    /// Returns the fields that were not taken.
+    pub(crate) fn into_rest(self) -> HashMap<String, NbtValue> {
+        self.map
+    }
+}
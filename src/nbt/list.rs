@@ -0,0 +1,140 @@
+use std::{
+    fmt::{Debug, Formatter},
+    ops::{Deref, DerefMut},
+};
+
+use crate::nbt::types::NbtValue;
+
+/// `NbtList` holds the elements of an `NbtValue::List` together with their tag type.
+///
+/// The element tag type of a non-empty list is the type of its elements. An empty list keeps the element tag type
+/// it was read or created with, so an empty list of compounds is written back as one. `NbtList` dereferences to
+/// `Vec<NbtValue>` for everything else.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::nbt::{NbtList, NbtValue};
+///
+/// let mut list = NbtList::new(0x0A);
+/// assert_eq!(list.element_type(), 0x0A);
+///
+/// list.push(NbtValue::Int(1));
+/// assert_eq!(list.element_type(), 0x03);
+/// assert_eq!(list.len(), 1);
+/// ```
+#[derive(Clone, Default)]
+pub struct NbtList {
+    element_type: u8,
+    values: Vec<NbtValue>,
+}
+
+impl NbtList {
+    /// Creates a new empty list with the given element tag type.
+    pub fn new(element_type: u8) -> Self {
+        NbtList {
+            element_type,
+            values: Vec::new(),
+        }
+    }
+
+    /// Creates a new empty list with the given element tag type and room for `capacity` elements.
+    pub fn with_capacity(element_type: u8, capacity: usize) -> Self {
+        NbtList {
+            element_type,
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the tag type of the list's elements, which for an empty list is the type it was created with.
+    pub fn element_type(&self) -> u8 {
+        self.values
+            .first()
+            .map_or(self.element_type, NbtValue::to_binary)
+    }
+
+    /// Sets the tag type used while the list is empty.
+    pub fn set_element_type(&mut self, element_type: u8) {
+        self.element_type = element_type;
+    }
+
+    /// Returns the elements of the list.
+    pub fn into_vec(self) -> Vec<NbtValue> {
+        self.values
+    }
+}
+
+impl Deref for NbtList {
+    type Target = Vec<NbtValue>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl DerefMut for NbtList {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.values
+    }
+}
+
+impl PartialEq for NbtList {
+    fn eq(&self, other: &Self) -> bool {
+        self.element_type() == other.element_type() && self.values == other.values
+    }
+}
+
+impl Debug for NbtList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.values)
+    }
+}
+
+impl From<Vec<NbtValue>> for NbtList {
+    /// Creates a list from its elements. An empty vector becomes a list of `End` tags.
+    fn from(values: Vec<NbtValue>) -> Self {
+        NbtList {
+            element_type: values.first().map_or(0x00, NbtValue::to_binary),
+            values,
+        }
+    }
+}
+
+impl From<NbtList> for Vec<NbtValue> {
+    fn from(list: NbtList) -> Self {
+        list.values
+    }
+}
+
+impl FromIterator<NbtValue> for NbtList {
+    fn from_iter<I: IntoIterator<Item = NbtValue>>(iter: I) -> Self {
+        NbtList::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl IntoIterator for NbtList {
+    type Item = NbtValue;
+    type IntoIter = std::vec::IntoIter<NbtValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a NbtList {
+    type Item = &'a NbtValue;
+    type IntoIter = std::slice::Iter<'a, NbtValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut NbtList {
+    type Item = &'a mut NbtValue;
+    type IntoIter = std::slice::IterMut<'a, NbtValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter_mut()
+    }
+}
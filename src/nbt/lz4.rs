@@ -0,0 +1,208 @@
+use std::io::{self, Read, Write};
+
+use xxhash_rust::xxh32::xxh32;
+
+/// Every block starts with these bytes.
+const MAGIC: &[u8; 8] = b"LZ4Block";
+/// Length of a block header: the magic, a token byte, then the compressed length, the decompressed length and
+/// the checksum as little endian ints.
+const HEADER_LENGTH: usize = MAGIC.len() + 1 + 4 + 4 + 4;
+
+const METHOD_RAW: u8 = 0x10;
+const METHOD_LZ4: u8 = 0x20;
+
+/// Minecraft writes blocks of at most 64 KiB. The low nibble of the token stores log2 of the block size minus 10.
+const BLOCK_SIZE: usize = 1 << 16;
+const BLOCK_SIZE_TOKEN: u8 = 16 - 10;
+
+const CHECKSUM_SEED: u32 = 0x9747_B28C;
+
+/// The checksum of a block, a xxHash32 of the decompressed data truncated to 28 bits.
+fn checksum(data: &[u8]) -> u32 {
+    xxh32(data, CHECKSUM_SEED) & 0x0FFF_FFFF
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("LZ4 block stream: {}", message),
+    )
+}
+
+/// Decompresses an LZ4 block stream, the format written by lz4-java's `LZ4BlockOutputStream` that Java Edition
+/// uses for region chunks.
+pub(crate) struct Lz4BlockDecoder<R: Read> {
+    reader: R,
+    block: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<R: Read> Lz4BlockDecoder<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Lz4BlockDecoder {
+            reader,
+            block: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// Reads the next block into the buffer, returning false once the end of the stream is reached.
+    fn next_block(&mut self) -> io::Result<bool> {
+        let mut header = [0u8; HEADER_LENGTH];
+        let mut filled = 0;
+        while filled < HEADER_LENGTH {
+            match self.reader.read(&mut header[filled..])? {
+                // A stream that stops cleanly between blocks is treated as finished, even without an end block.
+                0 if filled == 0 => return Ok(false),
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                read => filled += read,
+            }
+        }
+
+        if &header[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data("bad magic"));
+        }
+        let token = header[MAGIC.len()];
+        let int_at = |offset: usize| {
+            u32::from_le_bytes([
+                header[offset],
+                header[offset + 1],
+                header[offset + 2],
+                header[offset + 3],
+            ])
+        };
+        let compressed_length = int_at(MAGIC.len() + 1) as usize;
+        let decompressed_length = int_at(MAGIC.len() + 5) as usize;
+        let check = int_at(MAGIC.len() + 9);
+
+        if decompressed_length > 1 << (10 + (token & 0x0F)) || compressed_length > BLOCK_SIZE * 2 {
+            return Err(invalid_data("block too large"));
+        }
+
+        if compressed_length == 0 && decompressed_length == 0 {
+            return Ok(false);
+        }
+
+        let mut compressed = vec![0u8; compressed_length];
+        self.reader.read_exact(&mut compressed)?;
+
+        self.block = match token & 0xF0 {
+            METHOD_RAW if compressed_length == decompressed_length => compressed,
+            METHOD_LZ4 => lz4_flex::block::decompress(&compressed, decompressed_length)
+                .map_err(|_| invalid_data("corrupt block"))?,
+            _ => return Err(invalid_data("bad block header")),
+        };
+        self.position = 0;
+
+        if self.block.len() != decompressed_length {
+            return Err(invalid_data("bad block length"));
+        }
+        if checksum(&self.block) != check {
+            return Err(invalid_data("checksum mismatch"));
+        }
+
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for Lz4BlockDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.block.len() {
+            if self.finished || !self.next_block()? {
+                self.finished = true;
+                return Ok(0);
+            }
+        }
+
+        let read = buf.len().min(self.block.len() - self.position);
+        buf[..read].copy_from_slice(&self.block[self.position..self.position + read]);
+        self.position += read;
+        Ok(read)
+    }
+}
+
+fn write_header<W: Write>(
+    writer: &mut W,
+    method: u8,
+    compressed_length: usize,
+    decompressed_length: usize,
+    check: u32,
+) -> io::Result<()> {
+    let mut header = [0u8; HEADER_LENGTH];
+    header[..MAGIC.len()].copy_from_slice(MAGIC);
+    header[MAGIC.len()] = method | BLOCK_SIZE_TOKEN;
+    header[MAGIC.len() + 1..MAGIC.len() + 5]
+        .copy_from_slice(&(compressed_length as u32).to_le_bytes());
+    header[MAGIC.len() + 5..MAGIC.len() + 9]
+        .copy_from_slice(&(decompressed_length as u32).to_le_bytes());
+    header[MAGIC.len() + 9..].copy_from_slice(&check.to_le_bytes());
+    writer.write_all(&header)
+}
+
+/// Compresses data into an LZ4 block stream readable by lz4-java's `LZ4BlockInputStream`.
+///
+/// `finish` must be called to write the final block and the end of stream marker.
+pub(crate) struct Lz4BlockEncoder<W: Write> {
+    writer: W,
+    block: Vec<u8>,
+}
+
+impl<W: Write> Lz4BlockEncoder<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Lz4BlockEncoder {
+            writer,
+            block: Vec::with_capacity(BLOCK_SIZE),
+        }
+    }
+
+    fn write_block(&mut self) -> io::Result<()> {
+        let compressed = lz4_flex::block::compress(&self.block);
+        let (method, data) = if compressed.len() < self.block.len() {
+            (METHOD_LZ4, &compressed[..])
+        } else {
+            (METHOD_RAW, &self.block[..])
+        };
+
+        let check = checksum(&self.block);
+        write_header(
+            &mut self.writer,
+            method,
+            data.len(),
+            self.block.len(),
+            check,
+        )?;
+        self.writer.write_all(data)?;
+        self.block.clear();
+        Ok(())
+    }
+
+    /// Writes any buffered data and the end of stream marker, returning the inner writer.
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        if !self.block.is_empty() {
+            self.write_block()?;
+        }
+        write_header(&mut self.writer, METHOD_RAW, 0, 0, 0)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Write for Lz4BlockEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = buf.len().min(BLOCK_SIZE - self.block.len());
+        self.block.extend_from_slice(&buf[..written]);
+        if self.block.len() == BLOCK_SIZE {
+            self.write_block()?;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.block.is_empty() {
+            self.write_block()?;
+        }
+        self.writer.flush()
+    }
+}
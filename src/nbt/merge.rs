@@ -0,0 +1,181 @@
+use crate::nbt::types::{NbtError, NbtValue};
+
+/// Enum representing how two lists are combined by `NbtValue::merge`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListMergeStrategy {
+    /// The incoming list replaces the existing one, like `/data merge`.
+    Replace,
+    /// The incoming elements are appended to the existing list.
+    Append,
+    /// Compound elements sharing the same value for the given key are merged, all others are appended.
+    /// For example `MergeByKey("Slot".to_string())` merges inventory items slot by slot.
+    MergeByKey(String),
+}
+
+/// Enum representing what `NbtValue::merge` does when the same key holds values of different tag types.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeConflictStrategy {
+    /// The merge fails with `NbtError::TypeMismatch` and the value is left unchanged.
+    Error,
+    /// The incoming value replaces the existing one.
+    Overwrite,
+}
+
+/// `MergeStrategy` configures how `NbtValue::merge` overlays one value onto another.
+///
+/// The default strategy behaves like Minecraft's `/data merge`: lists are replaced and type conflicts overwrite.
+///
+/// # Fields
+///
+/// * `lists: ListMergeStrategy` - How lists are combined.
+/// * `conflicts: TypeConflictStrategy` - What happens when tag types differ.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeStrategy {
+    pub lists: ListMergeStrategy,
+    pub conflicts: TypeConflictStrategy,
+}
+
+impl MergeStrategy {
+    /// Creates a new `MergeStrategy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `lists: ListMergeStrategy` - How lists are combined.
+    /// * `conflicts: TypeConflictStrategy` - What happens when tag types differ.
+    pub fn new(lists: ListMergeStrategy, conflicts: TypeConflictStrategy) -> Self {
+        MergeStrategy { lists, conflicts }
+    }
+}
+
+impl Default for MergeStrategy {
+    fn default() -> Self {
+        MergeStrategy::new(ListMergeStrategy::Replace, TypeConflictStrategy::Overwrite)
+    }
+}
+
+fn list_types_match(target: &[NbtValue], other: &[NbtValue]) -> bool {
+    match (target.first(), other.first()) {
+        (Some(target), Some(other)) => target.to_binary() == other.to_binary(),
+        _ => true,
+    }
+}
+
+fn find_by_key(vec: &[NbtValue], key: &str, item: &NbtValue) -> Option<usize> {
+    let wanted = item.try_get(key)?;
+    vec.iter()
+        .position(|existing| existing.try_get(key) == Some(wanted))
+}
+
+/// Walks both values the same way `merge_values` does and reports the first type conflict.
+fn check_conflicts(
+    target: &NbtValue,
+    other: &NbtValue,
+    strategy: &MergeStrategy,
+) -> Result<(), NbtError> {
+    match (target, other) {
+        (NbtValue::Compound(target_map), NbtValue::Compound(other_map)) => {
+            for (key, value) in other_map {
+                if let Some(existing) = target_map.get(key) {
+                    check_conflicts(existing, value, strategy)?;
+                }
+            }
+            Ok(())
+        }
+        (NbtValue::List(target_vec), NbtValue::List(other_vec)) => {
+            if strategy.lists == ListMergeStrategy::Replace {
+                return Ok(());
+            }
+            if !list_types_match(target_vec, other_vec) {
+                return Err(NbtError::InvalidListType(other_vec[0].to_binary()));
+            }
+            if let ListMergeStrategy::MergeByKey(key) = &strategy.lists {
+                for item in other_vec {
+                    if let Some(index) = find_by_key(target_vec, key, item) {
+                        check_conflicts(&target_vec[index], item, strategy)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        _ if target.to_binary() != other.to_binary() => Err(NbtError::TypeMismatch(
+            target.to_binary(),
+            other.to_binary(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn merge_values(target: &mut NbtValue, other: NbtValue, strategy: &MergeStrategy) {
+    match (target, other) {
+        (NbtValue::Compound(target_map), NbtValue::Compound(other_map)) => {
+            for (key, value) in other_map {
+                match target_map.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value, strategy),
+                    None => {
+                        target_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (NbtValue::List(target_vec), NbtValue::List(other_vec))
+            if list_types_match(target_vec, &other_vec) =>
+        {
+            match &strategy.lists {
+                ListMergeStrategy::Replace => *target_vec = other_vec,
+                ListMergeStrategy::Append => target_vec.extend(other_vec),
+                ListMergeStrategy::MergeByKey(key) => {
+                    for item in other_vec {
+                        match find_by_key(target_vec, key, &item) {
+                            Some(index) => merge_values(&mut target_vec[index], item, strategy),
+                            None => target_vec.push(item),
+                        }
+                    }
+                }
+            }
+        }
+        (target, other) => *target = other,
+    }
+}
+
+impl NbtValue {
+    /// Deep merges another value into this one, like Minecraft's `/data merge`.
+    ///
+    /// Compounds are merged key by key, lists are combined according to `strategy.lists`, and any other value
+    /// is replaced. When the tag types of a key differ, `strategy.conflicts` decides whether the merge fails
+    /// or the incoming value wins. On error, the value is left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `other: NbtValue` - The value to merge into this one.
+    /// * `strategy: &MergeStrategy` - How lists and type conflicts are handled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{parse_snbt, ListMergeStrategy, MergeStrategy, TypeConflictStrategy};
+    ///
+    /// let mut inventory = parse_snbt("{Items:[{Slot:0b,Count:1b}]}").unwrap();
+    /// let update = parse_snbt("{Items:[{Slot:0b,Count:5b},{Slot:1b,Count:1b}]}").unwrap();
+    ///
+    /// let strategy = MergeStrategy::new(
+    ///     ListMergeStrategy::MergeByKey("Slot".to_string()),
+    ///     TypeConflictStrategy::Error,
+    /// );
+    /// inventory.merge(update, &strategy).unwrap();
+    ///
+    /// assert_eq!(inventory, parse_snbt("{Items:[{Slot:0b,Count:5b},{Slot:1b,Count:1b}]}").unwrap());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the values were merged.
+    /// * `Err(NbtError::TypeMismatch)` - If tag types conflict and the strategy is `TypeConflictStrategy::Error`.
+    /// * `Err(NbtError::InvalidListType)` - If list element types conflict and the strategy is `TypeConflictStrategy::Error`.
+    pub fn merge(&mut self, other: NbtValue, strategy: &MergeStrategy) -> Result<(), NbtError> {
+        if strategy.conflicts == TypeConflictStrategy::Error {
+            check_conflicts(self, &other, strategy)?;
+        }
+        merge_values(self, other, strategy);
+        Ok(())
+    }
+}
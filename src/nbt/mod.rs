@@ -1,7 +1,31 @@
+#[cfg(feature = "async")]
+mod async_io;
+mod borrowed;
+mod compression;
+mod diff;
+pub(crate) mod fields;
+mod list;
+#[cfg(feature = "lz4")]
+mod lz4;
+mod merge;
+mod path;
 mod reader;
+mod snbt;
 mod types;
+mod validate;
+mod visitor;
 mod writer;
 
+#[cfg(feature = "async")]
+pub use async_io::*;
+pub use borrowed::*;
+pub use compression::DEFAULT_COMPRESSION_LEVEL;
+pub use diff::*;
+pub use list::NbtList;
+pub use merge::*;
+pub use path::*;
 pub use reader::*;
+pub use snbt::parse_snbt;
 pub use types::*;
+pub use visitor::{NbtVisitor, Visit};
 pub use writer::*;
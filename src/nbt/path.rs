@@ -0,0 +1,702 @@
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use crate::nbt::{
+    list::NbtList,
+    snbt::{quote_string, write_snbt, SnbtParser},
+    types::{NbtError, NbtValue},
+};
+
+/// Enum representing a single step of an `NbtPath`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtPathNode {
+    /// Matches the root compound if it contains the given compound, e.g. `{Invulnerable:1b}`.
+    MatchRoot(NbtValue),
+    /// Selects the child of a compound with the given key, e.g. `Inventory`.
+    Key(String),
+    /// Selects the child of a compound with the given key if it contains the given compound, e.g. `tag{Damage:0}`.
+    MatchKey(String, NbtValue),
+    /// Selects the element of a list or array at the given index, e.g. `[0]`. Negative indices count from the end.
+    Index(i32),
+    /// Selects every element of a list or array, i.e. `[]`.
+    AllElements,
+    /// Selects every compound element of a list that contains the given compound, e.g. `[{Slot:0b}]`.
+    MatchElement(NbtValue),
+}
+
+/// `NbtPath` is a parsed Minecraft `/data`-style NBT path, such as `Data.Player.Inventory[0].tag.display.Name`
+/// or `Inventory[{id:"minecraft:stone"}].Count`.
+///
+/// Filters are written in SNBT and match when the target contains every entry of the filter.
+///
+/// Indices and `[]` also select the elements of byte, int and long arrays, such as `UUID[0]`. Array elements
+/// are not stored as `NbtValue`s, so they are returned by value and cannot be borrowed mutably.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::nbt::{parse_snbt, NbtPath, NbtValue};
+///
+/// let value = parse_snbt(r#"{Inventory:[{id:"minecraft:stone",Count:3b}]}"#).unwrap();
+/// let path: NbtPath = r#"Inventory[{id:"minecraft:stone"}].Count"#.parse().unwrap();
+///
+/// assert_eq!(value.get_path(&path).as_deref(), Some(&NbtValue::Byte(3)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NbtPath {
+    nodes: Vec<NbtPathNode>,
+}
+
+impl NbtPath {
+    /// Creates a new `NbtPath` from its nodes.
+    pub fn new(nodes: Vec<NbtPathNode>) -> Self {
+        NbtPath { nodes }
+    }
+
+    /// Inserts a node at the start of the path.
+    pub(crate) fn prepend(&mut self, node: NbtPathNode) {
+        self.nodes.insert(0, node);
+    }
+
+    /// Parses an NBT path.
+    ///
+    /// # Arguments
+    ///
+    /// * `input: &str` - The path, e.g. `Data.Player.Inventory[0]`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(NbtPath)` - On success, returns the parsed path.
+    /// * `Err(NbtError::InvalidPath)` - If the path is malformed.
+    pub fn parse(input: &str) -> Result<NbtPath, NbtError> {
+        let mut parser = SnbtParser::new(input);
+        let mut nodes = Vec::new();
+
+        while parser.peek().is_some() {
+            let first = nodes.is_empty();
+            nodes.push(parse_node(&mut parser, input, first)?);
+
+            match parser.peek() {
+                None | Some('[') | Some('{') => {}
+                Some('.') => {
+                    parser.seek(parser.position() + 1);
+                    if parser.peek().is_none() {
+                        return Err(path_error(input, parser.position(), "Expected key"));
+                    }
+                }
+                Some(_) => return Err(path_error(input, parser.position(), "Expected '.'")),
+            }
+        }
+
+        if nodes.is_empty() {
+            return Err(NbtError::InvalidPath("Empty path".to_string()));
+        }
+
+        Ok(NbtPath { nodes })
+    }
+
+    /// Returns the nodes that make up this path.
+    pub fn nodes(&self) -> &[NbtPathNode] {
+        &self.nodes
+    }
+
+    /// Returns a new path with the given node appended.
+    pub fn join(&self, node: NbtPathNode) -> NbtPath {
+        let mut nodes = self.nodes.clone();
+        nodes.push(node);
+        NbtPath { nodes }
+    }
+}
+
+fn path_error(input: &str, position: usize, message: &str) -> NbtError {
+    NbtError::InvalidPath(format!(
+        "{} at position {} in '{}'",
+        message, position, input
+    ))
+}
+
+fn parse_node(parser: &mut SnbtParser, input: &str, first: bool) -> Result<NbtPathNode, NbtError> {
+    let start = parser.position();
+    match parser.peek() {
+        Some('{') => {
+            if !first {
+                return Err(path_error(input, start, "Unexpected compound filter"));
+            }
+            Ok(NbtPathNode::MatchRoot(parser.parse_compound()?))
+        }
+        Some('[') => {
+            parser.seek(start + 1);
+            let node = match parser.peek() {
+                Some(']') => NbtPathNode::AllElements,
+                Some('{') => NbtPathNode::MatchElement(parser.parse_compound()?),
+                _ => {
+                    let rest = &input[parser.position()..];
+                    let length = rest.find(']').unwrap_or(rest.len());
+                    let index = rest[..length]
+                        .trim()
+                        .parse::<i32>()
+                        .map_err(|_| path_error(input, parser.position(), "Invalid index"))?;
+                    parser.seek(parser.position() + length);
+                    NbtPathNode::Index(index)
+                }
+            };
+            if parser.peek() != Some(']') {
+                return Err(path_error(input, parser.position(), "Expected ']'"));
+            }
+            parser.seek(parser.position() + 1);
+            Ok(node)
+        }
+        Some('"') | Some('\'') => {
+            let key = parser.parse_quoted_string()?;
+            parse_key_filter(parser, key)
+        }
+        _ => {
+            let rest = &input[start..];
+            let length = rest
+                .find([' ', '"', '\'', '[', ']', '.', '{', '}'])
+                .unwrap_or(rest.len());
+            if length == 0 {
+                return Err(path_error(input, start, "Expected key"));
+            }
+            parser.seek(start + length);
+            parse_key_filter(parser, rest[..length].to_string())
+        }
+    }
+}
+
+fn parse_key_filter(parser: &mut SnbtParser, key: String) -> Result<NbtPathNode, NbtError> {
+    if parser.peek() == Some('{') {
+        Ok(NbtPathNode::MatchKey(key, parser.parse_compound()?))
+    } else {
+        Ok(NbtPathNode::Key(key))
+    }
+}
+
+impl FromStr for NbtPath {
+    type Err = NbtError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NbtPath::parse(s)
+    }
+}
+
+impl Display for NbtPath {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let mut out = String::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            match node {
+                NbtPathNode::Key(key) | NbtPathNode::MatchKey(key, _) => {
+                    if index > 0 {
+                        out.push('.');
+                    }
+                    if !key.is_empty() && !key.contains([' ', '"', '\'', '[', ']', '.', '{', '}']) {
+                        out.push_str(key);
+                    } else {
+                        out.push_str(&quote_string(key));
+                    }
+                    if let NbtPathNode::MatchKey(_, filter) = node {
+                        write_snbt(filter, &mut out);
+                    }
+                }
+                NbtPathNode::MatchRoot(filter) => write_snbt(filter, &mut out),
+                NbtPathNode::Index(i) => out.push_str(&format!("[{}]", i)),
+                NbtPathNode::AllElements => out.push_str("[]"),
+                NbtPathNode::MatchElement(filter) => {
+                    out.push('[');
+                    write_snbt(filter, &mut out);
+                    out.push(']');
+                }
+            }
+        }
+        write!(f, "{}", out)
+    }
+}
+
+/// Returns `true` if `target` contains everything in `filter`.
+///
+/// Compounds match when every key of the filter matches, lists match when every filter element matches some
+/// target element (an empty filter list only matches an empty list), and everything else must be equal.
+pub(crate) fn matches_filter(filter: &NbtValue, target: &NbtValue) -> bool {
+    match (filter, target) {
+        (NbtValue::Compound(filter), NbtValue::Compound(target)) => filter
+            .iter()
+            .all(|(key, value)| target.get(key).is_some_and(|t| matches_filter(value, t))),
+        (NbtValue::List(filter), NbtValue::List(target)) => {
+            if filter.is_empty() {
+                target.is_empty()
+            } else {
+                filter
+                    .iter()
+                    .all(|f| target.iter().any(|t| matches_filter(f, t)))
+            }
+        }
+        _ => filter == target,
+    }
+}
+
+fn resolve_index(index: i32, len: usize) -> Option<usize> {
+    let resolved = if index < 0 {
+        len as i64 + index as i64
+    } else {
+        index as i64
+    };
+    if resolved >= 0 && (resolved as usize) < len {
+        Some(resolved as usize)
+    } else {
+        None
+    }
+}
+
+/// Returns an element of a byte, int or long array as a `Byte`, `Int` or `Long`.
+fn array_element(array: &NbtValue, index: usize) -> Option<NbtValue> {
+    match array {
+        NbtValue::ByteArray(vec) => vec.get(index).map(|&value| NbtValue::Byte(value)),
+        NbtValue::IntArray(vec) => vec.get(index).map(|&value| NbtValue::Int(value)),
+        NbtValue::LongArray(vec) => vec.get(index).map(|&value| NbtValue::Long(value)),
+        _ => None,
+    }
+}
+
+/// Returns the indices of the array elements a node selects, in order, or nothing if `array` is not an array.
+fn array_indices(array: &NbtValue, node: &NbtPathNode) -> Vec<usize> {
+    let len = match array {
+        NbtValue::ByteArray(vec) => vec.len(),
+        NbtValue::IntArray(vec) => vec.len(),
+        NbtValue::LongArray(vec) => vec.len(),
+        _ => return Vec::new(),
+    };
+    match node {
+        NbtPathNode::Index(index) => resolve_index(*index, len).into_iter().collect(),
+        NbtPathNode::AllElements => (0..len).collect(),
+        NbtPathNode::MatchElement(filter) => (0..len)
+            .filter(|&i| {
+                array_element(array, i).is_some_and(|element| matches_filter(filter, &element))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Sets the array elements a node selects, returning how many were set.
+fn set_array_elements(
+    array: &mut NbtValue,
+    node: &NbtPathNode,
+    value: &NbtValue,
+) -> Result<usize, NbtError> {
+    let indices = array_indices(array, node);
+    let element_type = match indices.first().and_then(|&i| array_element(array, i)) {
+        Some(element) => element.to_binary(),
+        None => return Ok(0),
+    };
+    match (array, value) {
+        (NbtValue::ByteArray(vec), NbtValue::Byte(value)) => {
+            indices.iter().for_each(|&i| vec[i] = *value)
+        }
+        (NbtValue::IntArray(vec), NbtValue::Int(value)) => {
+            indices.iter().for_each(|&i| vec[i] = *value)
+        }
+        (NbtValue::LongArray(vec), NbtValue::Long(value)) => {
+            indices.iter().for_each(|&i| vec[i] = *value)
+        }
+        _ => return Err(NbtError::TypeMismatch(element_type, value.to_binary())),
+    }
+    Ok(indices.len())
+}
+
+/// Removes the array elements a node selects, returning them.
+fn remove_array_elements(array: &mut NbtValue, node: &NbtPathNode) -> Vec<NbtValue> {
+    let indices = array_indices(array, node);
+    let removed = indices
+        .iter()
+        .filter_map(|&i| array_element(array, i))
+        .collect();
+    match array {
+        NbtValue::ByteArray(vec) => remove_indices(vec, &indices),
+        NbtValue::IntArray(vec) => remove_indices(vec, &indices),
+        NbtValue::LongArray(vec) => remove_indices(vec, &indices),
+        _ => {}
+    }
+    removed
+}
+
+/// Removes the elements at the given sorted indices.
+fn remove_indices<T>(vec: &mut Vec<T>, indices: &[usize]) {
+    let mut index = 0;
+    vec.retain(|_| {
+        let keep = indices.binary_search(&index).is_err();
+        index += 1;
+        keep
+    });
+}
+
+fn resolve<'a>(value: &'a NbtValue, nodes: &[NbtPathNode], out: &mut Vec<Cow<'a, NbtValue>>) {
+    let (node, rest) = match nodes.split_first() {
+        Some(split) => split,
+        None => {
+            out.push(Cow::Borrowed(value));
+            return;
+        }
+    };
+
+    match (node, value) {
+        (NbtPathNode::MatchRoot(filter), _) if matches_filter(filter, value) => {
+            resolve(value, rest, out);
+        }
+        (NbtPathNode::Key(key), NbtValue::Compound(map)) => {
+            if let Some(child) = map.get(key) {
+                resolve(child, rest, out);
+            }
+        }
+        (NbtPathNode::MatchKey(key, filter), NbtValue::Compound(map)) => {
+            if let Some(child) = map.get(key).filter(|child| matches_filter(filter, child)) {
+                resolve(child, rest, out);
+            }
+        }
+        (NbtPathNode::Index(index), NbtValue::List(vec)) => {
+            if let Some(child) = resolve_index(*index, vec.len()).map(|i| &vec[i]) {
+                resolve(child, rest, out);
+            }
+        }
+        (NbtPathNode::AllElements, NbtValue::List(vec)) => {
+            for child in vec {
+                resolve(child, rest, out);
+            }
+        }
+        (NbtPathNode::MatchElement(filter), NbtValue::List(vec)) => {
+            for child in vec.iter().filter(|child| matches_filter(filter, child)) {
+                resolve(child, rest, out);
+            }
+        }
+        // Array elements are numbers, so a path can only end at them.
+        (_, array) if rest.is_empty() => out.extend(
+            array_indices(array, node)
+                .into_iter()
+                .filter_map(|i| array_element(array, i))
+                .map(Cow::Owned),
+        ),
+        _ => {}
+    }
+}
+
+fn resolve_mut<'a>(
+    value: &'a mut NbtValue,
+    nodes: &[NbtPathNode],
+    create: Option<&NbtPathNode>,
+    out: &mut Vec<&'a mut NbtValue>,
+) {
+    let (node, rest) = match nodes.split_first() {
+        Some(split) => split,
+        None => {
+            out.push(value);
+            return;
+        }
+    };
+
+    match node {
+        NbtPathNode::MatchRoot(filter) => {
+            if matches_filter(filter, value) {
+                resolve_mut(value, rest, create, out);
+            }
+        }
+        NbtPathNode::Key(key) => {
+            if let NbtValue::Compound(map) = value {
+                if let Some(last) = create.filter(|_| !map.contains_key(key)) {
+                    map.insert(key.clone(), preferred_parent(rest.first().unwrap_or(last)));
+                }
+                if let Some(child) = map.get_mut(key) {
+                    resolve_mut(child, rest, create, out);
+                }
+            }
+        }
+        NbtPathNode::MatchKey(key, filter) => {
+            if let NbtValue::Compound(map) = value {
+                if create.is_some() && !map.contains_key(key) {
+                    map.insert(key.clone(), filter.clone());
+                }
+                if let Some(child) = map.get_mut(key) {
+                    if matches_filter(filter, child) {
+                        resolve_mut(child, rest, create, out);
+                    }
+                }
+            }
+        }
+        NbtPathNode::Index(index) => {
+            if let NbtValue::List(vec) = value {
+                if let Some(i) = resolve_index(*index, vec.len()) {
+                    resolve_mut(&mut vec[i], rest, create, out);
+                }
+            }
+        }
+        NbtPathNode::AllElements => {
+            if let NbtValue::List(vec) = value {
+                for child in vec.iter_mut() {
+                    resolve_mut(child, rest, create, out);
+                }
+            }
+        }
+        NbtPathNode::MatchElement(filter) => {
+            if let NbtValue::List(vec) = value {
+                if create.is_some() && !vec.iter().any(|child| matches_filter(filter, child)) {
+                    // Only lists of compounds, or empty lists without an element type, can take the filter.
+                    if !matches!(vec.element_type(), 0x00 | 0x0A) {
+                        return;
+                    }
+                    vec.push(filter.clone());
+                }
+                for child in vec.iter_mut() {
+                    if matches_filter(filter, child) {
+                        resolve_mut(child, rest, create, out);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn preferred_parent(next: &NbtPathNode) -> NbtValue {
+    match next {
+        NbtPathNode::Index(_) | NbtPathNode::AllElements | NbtPathNode::MatchElement(_) => {
+            NbtValue::List(NbtList::default())
+        }
+        _ => NbtValue::new(),
+    }
+}
+
+fn check_list_type(vec: &[NbtValue], value: &NbtValue) -> Result<(), NbtError> {
+    match vec.first() {
+        Some(first) if vec.len() > 1 && first.to_binary() != value.to_binary() => {
+            Err(NbtError::InvalidListType(value.to_binary()))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl NbtValue {
+    /// Returns the first value matched by the path. Values inside compounds and lists are borrowed, and
+    /// array elements are returned by value.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: &NbtPath` - The path to look up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{parse_snbt, NbtValue};
+    ///
+    /// let value = parse_snbt("{Data:{Player:{XpLevel:30}}}").unwrap();
+    /// let level = value.get_path(&"Data.Player.XpLevel".parse().unwrap());
+    /// assert_eq!(level.as_deref(), Some(&NbtValue::Int(30)));
+    ///
+    /// let uuid = parse_snbt("{UUID:[I;1,2,3,4]}").unwrap();
+    /// let last = uuid.get_path(&"UUID[-1]".parse().unwrap());
+    /// assert_eq!(last.as_deref(), Some(&NbtValue::Int(4)));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Cow<NbtValue>)` - If the path matched at least one value.
+    /// * `None` - Otherwise.
+    pub fn get_path(&self, path: &NbtPath) -> Option<Cow<'_, NbtValue>> {
+        self.query_all(path).into_iter().next()
+    }
+
+    /// Returns a mutable reference to the first value matched by the path. Array elements cannot be borrowed, so
+    /// paths ending at one match nothing; use `set_path` to change them.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: &NbtPath` - The path to look up.
+    pub fn get_path_mut(&mut self, path: &NbtPath) -> Option<&mut NbtValue> {
+        let mut out = Vec::new();
+        resolve_mut(self, &path.nodes, None, &mut out);
+        out.into_iter().next()
+    }
+
+    /// Returns every value matched by the path, e.g. all items selected by `Inventory[]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: &NbtPath` - The path to look up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::parse_snbt;
+    ///
+    /// let value = parse_snbt("{Inventory:[{Slot:0b},{Slot:1b}]}").unwrap();
+    /// let slots = value.query_all(&"Inventory[].Slot".parse().unwrap());
+    ///
+    /// assert_eq!(slots.len(), 2);
+    /// ```
+    pub fn query_all(&self, path: &NbtPath) -> Vec<Cow<'_, NbtValue>> {
+        let mut out = Vec::new();
+        resolve(self, &path.nodes, &mut out);
+        out
+    }
+
+    /// Sets every value matched by the path, creating missing compounds along the way.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: &NbtPath` - The path to set.
+    /// * `value: T` - A value that can be converted into `NbtValue`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{NbtValue, NbtPath};
+    ///
+    /// let mut value = NbtValue::new();
+    /// let path: NbtPath = "tag.display.Name".parse().unwrap();
+    /// value.set_path(&path, "\"Stick\"").unwrap();
+    ///
+    /// assert_eq!(value.get_path(&path).as_deref(), Some(&NbtValue::from("\"Stick\"")));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of values that were set.
+    /// * `Err(NbtError)` - `InvalidListType` if the value does not fit the list it is placed in, or `TypeMismatch`
+    ///   if it does not have the element type of the array it is placed in.
+    pub fn set_path<T: Into<NbtValue>>(
+        &mut self,
+        path: &NbtPath,
+        value: T,
+    ) -> Result<usize, NbtError> {
+        let value = value.into();
+        let (last, parents) = match path.nodes.split_last() {
+            Some(split) => split,
+            None => return Ok(0),
+        };
+
+        if let NbtPathNode::MatchRoot(filter) = last {
+            let mut targets = Vec::new();
+            resolve_mut(self, parents, Some(last), &mut targets);
+            let mut count = 0;
+            for target in targets {
+                if matches_filter(filter, target) {
+                    *target = value.clone();
+                    count += 1;
+                }
+            }
+            return Ok(count);
+        }
+
+        let mut targets = Vec::new();
+        resolve_mut(self, parents, Some(last), &mut targets);
+
+        let mut count = 0;
+        for target in targets {
+            match (last, target) {
+                (NbtPathNode::Key(key), NbtValue::Compound(map)) => {
+                    map.insert(key.clone(), value.clone());
+                    count += 1;
+                }
+                (NbtPathNode::MatchKey(key, filter), NbtValue::Compound(map)) => {
+                    let matched = map
+                        .get(key)
+                        .is_none_or(|child| matches_filter(filter, child));
+                    if matched {
+                        map.insert(key.clone(), value.clone());
+                        count += 1;
+                    }
+                }
+                (NbtPathNode::Index(index), NbtValue::List(vec)) => {
+                    if let Some(i) = resolve_index(*index, vec.len()) {
+                        check_list_type(vec, &value)?;
+                        vec[i] = value.clone();
+                        count += 1;
+                    }
+                }
+                (NbtPathNode::AllElements, NbtValue::List(vec)) => {
+                    for child in vec.iter_mut() {
+                        *child = value.clone();
+                        count += 1;
+                    }
+                }
+                (NbtPathNode::MatchElement(filter), NbtValue::List(vec)) => {
+                    for child in vec.iter_mut() {
+                        if matches_filter(filter, child) {
+                            if child.to_binary() != value.to_binary() {
+                                return Err(NbtError::InvalidListType(value.to_binary()));
+                            }
+                            *child = value.clone();
+                            count += 1;
+                        }
+                    }
+                }
+                (_, array) => count += set_array_elements(array, last, &value)?,
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Removes every value matched by the path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: &NbtPath` - The path to remove.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::parse_snbt;
+    ///
+    /// let mut value = parse_snbt(r#"{Inventory:[{id:"minecraft:stone"},{id:"minecraft:dirt"}]}"#).unwrap();
+    /// let removed = value.remove_path(&r#"Inventory[{id:"minecraft:dirt"}]"#.parse().unwrap());
+    ///
+    /// assert_eq!(removed.len(), 1);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<NbtValue>` - The values that were removed.
+    pub fn remove_path(&mut self, path: &NbtPath) -> Vec<NbtValue> {
+        let mut removed = Vec::new();
+        let (last, parents) = match path.nodes.split_last() {
+            Some(split) => split,
+            None => return removed,
+        };
+
+        let mut targets = Vec::new();
+        resolve_mut(self, parents, None, &mut targets);
+
+        for target in targets {
+            match (last, target) {
+                (NbtPathNode::Key(key), NbtValue::Compound(map)) => {
+                    removed.extend(map.remove(key));
+                }
+                (NbtPathNode::MatchKey(key, filter), NbtValue::Compound(map))
+                    if map
+                        .get(key)
+                        .is_some_and(|child| matches_filter(filter, child)) =>
+                {
+                    removed.extend(map.remove(key));
+                }
+                (NbtPathNode::Index(index), NbtValue::List(vec)) => {
+                    if let Some(i) = resolve_index(*index, vec.len()) {
+                        removed.push(vec.remove(i));
+                    }
+                }
+                (NbtPathNode::AllElements, NbtValue::List(vec)) => {
+                    removed.append(vec);
+                }
+                (NbtPathNode::MatchElement(filter), NbtValue::List(vec)) => {
+                    let (matched, kept): (Vec<_>, Vec<_>) = vec
+                        .drain(..)
+                        .partition(|child| matches_filter(filter, child));
+                    vec.extend(kept);
+                    removed.extend(matched);
+                }
+                (_, array) => removed.extend(remove_array_elements(array, last)),
+            }
+        }
+
+        removed
+    }
+}
@@ -1,8 +1,13 @@
-use std::{collections::HashMap, fs::File, io::Read, path::PathBuf};
+use std::{fs::File, io::Read, path::PathBuf};
 
 use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
 
-use crate::nbt::types::{Compression, Endian, NbtError, NbtValue};
+use crate::nbt::{
+    compression::decoder,
+    path::NbtPathNode,
+    types::{Compression, Endian, NbtError, NbtValue},
+    visitor::{NbtVisitor, TreeBuilder, Visit},
+};
 
 use flate2::read::{GzDecoder, ZlibDecoder};
 
@@ -12,9 +17,50 @@ use flate2::read::{GzDecoder, ZlibDecoder};
 ///
 /// * `reader: R` - The reader from which NBT data is read. This reader must implement the `Read` trait.
 /// * `endian: Endian` - The endian style (Big or Little) used to interpret the read NBT data.
+///
+/// Errors returned while reading are wrapped in `NbtError::Context`, carrying the byte offset reading had reached
+/// and the path of the value being read.
 pub struct NbtReader<R: Read> {
-    reader: R,
+    reader: PositionReader<R>,
     endian: Endian,
+    /// The keys of the compound entries being visited, one after another, so visitors can borrow them and
+    /// errors can name them without allocating a `String` per entry.
+    keys: String,
+    key_bytes: Vec<u8>,
+}
+
+/// `NbtRoots` is an iterator over named tags stored back to back, returned by `NbtReader::roots`.
+pub struct NbtRoots<'a, R: Read> {
+    reader: &'a mut NbtReader<R>,
+    finished: bool,
+}
+
+impl<R: Read> Iterator for NbtRoots<'_, R> {
+    type Item = Result<(String, NbtValue), NbtError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let result = self.reader.next_root().transpose();
+        self.finished = !matches!(result, Some(Ok(_)));
+        result
+    }
+}
+
+/// Counts the bytes read through it, so errors can report where they happened.
+struct PositionReader<R: Read> {
+    inner: R,
+    position: u64,
+}
+
+impl<R: Read> Read for PositionReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.position += read as u64;
+        Ok(read)
+    }
 }
 
 impl<R: Read> NbtReader<R> {
@@ -29,7 +75,20 @@ impl<R: Read> NbtReader<R> {
     ///
     /// * `Self` - A new instance of `NbtReader`.
     pub fn new(reader: R, endian: Endian) -> Self {
-        NbtReader { reader, endian }
+        NbtReader {
+            reader: PositionReader {
+                inner: reader,
+                position: 0,
+            },
+            endian,
+            keys: String::new(),
+            key_bytes: Vec::new(),
+        }
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn position(&self) -> u64 {
+        self.reader.position
     }
 
     /// Parses an NBT value from the reader according to the given tag type.
@@ -43,104 +102,9 @@ impl<R: Read> NbtReader<R> {
     /// * `Ok(NbtValue)` - On success, returns the parsed NBT value.
     /// * `Err(NbtError)` - On failure, returns an NbtError.
     pub fn parse_nbt_value(&mut self, tag_type: u8) -> Result<NbtValue, NbtError> {
-        match tag_type {
-            0x00 => Ok(NbtValue::End),
-            0x01 => {
-                let value = self.parse_byte()?;
-                Ok(NbtValue::Byte(value))
-            }
-            0x02 => {
-                let value = self.parse_short()?;
-                Ok(NbtValue::Short(value))
-            }
-            0x03 => {
-                let value = self.parse_int()?;
-                Ok(NbtValue::Int(value))
-            }
-            0x04 => {
-                let value = self.parse_long()?;
-                Ok(NbtValue::Long(value))
-            }
-            0x05 => {
-                let value = self.parse_float()?;
-                Ok(NbtValue::Float(value))
-            }
-            0x06 => {
-                let value = self.parse_double()?;
-                Ok(NbtValue::Double(value))
-            }
-            0x07 => {
-                let value = self.parse_byte_array()?;
-                Ok(NbtValue::ByteArray(value))
-            }
-            0x08 => {
-                let value = self.parse_string()?;
-                Ok(NbtValue::String(value))
-            }
-            0x09 => {
-                let initial_byte = self.parse_byte()? as u8;
-                let list_tag_type = NbtValue::from_binary(initial_byte);
-                let array_length = self.parse_int()?;
-
-                if array_length == 0 {
-                    return Ok(NbtValue::List(Vec::new()));
-                }
-
-                if list_tag_type.is_none() {
-                    return Err(NbtError::InvalidTagType(initial_byte));
-                }
-
-                let mut vec = Vec::with_capacity(array_length as usize);
-
-                for _ in 0..array_length {
-                    if let Some(tag_type) = list_tag_type.as_ref() {
-                        let value = self.parse_nbt_value(tag_type.to_binary())?;
-                        vec.push(value);
-                    } else {
-                        return Err(NbtError::InvalidTagType(initial_byte));
-                    }
-                }
-
-                Ok(NbtValue::List(vec))
-            }
-            0x0A => {
-                let mut map = HashMap::new();
-
-                loop {
-                    match self.parse_nbt_tag() {
-                        Ok((key, value)) => {
-                            if let NbtValue::End = value {
-                                break;
-                            }
-                            map.insert(key, value);
-                        }
-                        Err(NbtError::InvalidCompoundType(0)) => break,
-                        Err(e) => return Err(e),
-                    }
-                }
-
-                Ok(NbtValue::Compound(map))
-            }
-            0x0B => {
-                let array_length = self.parse_int()? as usize;
-                let mut array = Vec::with_capacity(array_length);
-                for _ in 0..array_length {
-                    let value = self.parse_int()?;
-                    array.push(value);
-                }
-                Ok(NbtValue::IntArray(array))
-            }
-            0x0C => {
-                let array_length = self.parse_int()? as usize;
-                let mut array = Vec::with_capacity(array_length);
-                for _ in 0..array_length {
-                    let value = self.parse_long()?;
-                    array.push(value);
-                }
-                Ok(NbtValue::LongArray(array))
-            }
-            _ => Err(NbtError::InvalidTagType(tag_type)),
-        }
+        let mut builder = TreeBuilder::new();
+        self.visit_nbt_value(tag_type, &mut builder)?;
+        Ok(builder.finish().1)
     }
 
     /// Parses the NBT data from the reader and returns the root tag name and the parsed NBT value.
@@ -162,6 +126,157 @@ impl<R: Read> NbtReader<R> {
     /// * `Ok((String, NbtValue))` - Returns a tuple containing the root tag name and the parsed NBT value on success.
     /// * `Err(NbtError)` - Returns an NbtError on failure.
     pub fn parse_data(&mut self) -> Result<(String, NbtValue), NbtError> {
+        let mut builder = TreeBuilder::new();
+        self.visit_data(&mut builder)?;
+        Ok(builder.finish())
+    }
+
+    /// Parses the NBT data from the reader like `parse_data`, but recovers what it can from corrupted data.
+    ///
+    /// When reading fails, for example on truncated data or an invalid tag type, the values read up to that
+    /// point are kept: open lists and compounds are closed, and only the value being read is dropped. Reading
+    /// stops at the first problem, as the data after a corrupted value cannot be located reliably.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{NbtReader, Endian};
+    ///
+    /// let data = std::fs::read("./tests/data/bedrock_level.dat").unwrap();
+    ///
+    /// // Cut the file off half way through.
+    /// let mut reader = NbtReader::new(&data[..data.len() / 2], Endian::Little);
+    /// let (name, value, problem) = reader.parse_data_lenient();
+    ///
+    /// assert!(value.get("LevelName").is_some());
+    /// assert!(problem.is_some());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `(String, NbtValue, Option<NbtError>)` - The root tag name, the recovered NBT value, and the problem that
+    ///   stopped reading, with the byte offset and path where it happened. No problem means the data was read in
+    ///   full.
+    pub fn parse_data_lenient(&mut self) -> (String, NbtValue, Option<NbtError>) {
+        let mut builder = TreeBuilder::new();
+        let problem = self.visit_data(&mut builder).err();
+        let (name, value) = builder.finish_partial();
+        (name, value, problem)
+    }
+
+    /// Reads the NBT data from the reader, reporting the root tag and every value to the visitor instead of
+    /// building an `NbtValue` tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `visitor: &mut V` - The visitor that receives the values as they are read.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the data was read, or the visitor stopped reading.
+    /// * `Err(NbtError)` - On failure, returns an NbtError.
+    pub fn visit_data<V: NbtVisitor>(&mut self, visitor: &mut V) -> Result<(), NbtError> {
+        self.read_header()
+            .and_then(|_| self.visit_root(visitor))
+            .map_err(|error| error.at(Some(self.reader.position)))
+    }
+
+    /// Parses a single named tag from the reader, without the header Bedrock files start with.
+    ///
+    /// This is how Bedrock stores NBT inside its world database, for example block states in subchunk palettes
+    /// and the concatenated compounds of block entity records. For big endian data it reads the same as
+    /// `parse_data`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{NbtReader, NbtValue, NbtWriter, Endian};
+    ///
+    /// let mut value = NbtValue::new();
+    /// value.insert("name".to_string(), "minecraft:stone");
+    ///
+    /// let mut data = Vec::new();
+    /// NbtWriter::new(&mut data, Endian::Little).write_root("", &value).unwrap();
+    ///
+    /// let (name, read) = NbtReader::new(&data[..], Endian::Little).parse_root().unwrap();
+    /// assert_eq!(read, value);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((String, NbtValue))` - Returns a tuple containing the tag name and the parsed NBT value on success.
+    /// * `Err(NbtError)` - Returns an NbtError on failure.
+    pub fn parse_root(&mut self) -> Result<(String, NbtValue), NbtError> {
+        let mut builder = TreeBuilder::new();
+        self.visit_root(&mut builder)
+            .map_err(|error| error.at(Some(self.reader.position)))?;
+        Ok(builder.finish())
+    }
+
+    /// Parses the next of several named tags stored back to back, without the header Bedrock files start with.
+    ///
+    /// Unlike `parse_root`, reaching the end of the data before a tag starts is not an error: it returns
+    /// `Ok(None)`. Data that ends part way through a tag returns `NbtError::UnexpectedEof`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some((String, NbtValue)))` - The name and value of the next tag.
+    /// * `Ok(None)` - If the data ended cleanly before another tag.
+    /// * `Err(NbtError)` - If the tag could not be read, including when the data ends part way through it.
+    pub fn next_root(&mut self) -> Result<Option<(String, NbtValue)>, NbtError> {
+        let mut header = [0u8; 1];
+        loop {
+            match self.reader.read(&mut header) {
+                Ok(0) => return Ok(None),
+                Ok(_) => break,
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(NbtError::from(error).at(Some(self.reader.position))),
+            }
+        }
+
+        let mut builder = TreeBuilder::new();
+        self.visit_named(header[0], &mut builder)
+            .map_err(|error| error.at(Some(self.reader.position)))?;
+        Ok(Some(builder.finish()))
+    }
+
+    /// Returns an iterator over named tags stored back to back, as found in Bedrock block entity records, some
+    /// mod formats and network buffers.
+    ///
+    /// Each tag is read like `next_root`. The iterator ends when the data ends cleanly between tags, and after
+    /// the first error, so truncated data yields an `NbtError::UnexpectedEof` as its last item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{NbtReader, NbtValue, NbtWriter, Endian};
+    ///
+    /// let mut value = NbtValue::new();
+    /// value.insert("id".to_string(), "Chest");
+    ///
+    /// let mut data = Vec::new();
+    /// let mut writer = NbtWriter::new(&mut data, Endian::Little);
+    /// writer.write_root("", &value).unwrap();
+    /// writer.write_root("", &value).unwrap();
+    ///
+    /// let mut reader = NbtReader::new(&data[..], Endian::Little);
+    /// assert_eq!(reader.roots().count(), 2);
+    ///
+    /// // Cutting the data short makes the last item an error.
+    /// let mut reader = NbtReader::new(&data[..data.len() - 1], Endian::Little);
+    /// let roots: Vec<_> = reader.roots().collect();
+    /// assert!(roots[0].is_ok());
+    /// assert!(roots[1].is_err());
+    /// ```
+    pub fn roots(&mut self) -> NbtRoots<'_, R> {
+        NbtRoots {
+            reader: self,
+            finished: false,
+        }
+    }
+
+    /// Reads the storage version and length that Bedrock files start with.
+    fn read_header(&mut self) -> Result<(), NbtError> {
         match self.endian {
             Endian::Big => {}
             Endian::Little => {
@@ -169,39 +284,232 @@ impl<R: Read> NbtReader<R> {
                 let _file_length = self.reader.read_i32::<LittleEndian>()?;
             }
         };
+        Ok(())
+    }
 
+    fn visit_root<V: NbtVisitor>(&mut self, visitor: &mut V) -> Result<(), NbtError> {
         let header = self.reader.read_u8()?;
+        self.visit_named(header, visitor)
+    }
 
-        if let Some(tag) = NbtValue::from_binary(header) {
-            if let NbtValue::End = tag {
-                return Ok((String::new(), NbtValue::End));
+    /// Reads the name and value of a root tag whose tag type has already been read.
+    fn visit_named<V: NbtVisitor>(&mut self, header: u8, visitor: &mut V) -> Result<(), NbtError> {
+        match NbtValue::from_binary(header) {
+            Some(NbtValue::End) => {
+                visitor.visit_root(String::new(), header)?;
+            }
+            Some(_) => {
+                let name = self.parse_string()?;
+                match visitor.visit_root(name, header)? {
+                    Visit::Continue => {
+                        self.visit_value(header, visitor)?;
+                    }
+                    Visit::Skip => self.skip_value(header)?,
+                    Visit::Stop => {}
+                }
             }
+            None => return Err(NbtError::InvalidTagType(header)),
+        }
 
-            let name = self.parse_string()?;
-            let value = self.parse_nbt_value(tag.to_binary())?;
+        Ok(())
+    }
 
-            Ok((name, value))
-        } else {
-            Err(NbtError::InvalidTagType(header))
-        }
+    /// Reads an NBT value of the given tag type, reporting it to the visitor instead of building an `NbtValue`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_type: u8` - The tag type of the NBT value to be read.
+    /// * `visitor: &mut V` - The visitor that receives the values as they are read.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the value was read, or the visitor stopped reading.
+    /// * `Err(NbtError)` - On failure, returns an NbtError.
+    pub fn visit_nbt_value<V: NbtVisitor>(
+        &mut self,
+        tag_type: u8,
+        visitor: &mut V,
+    ) -> Result<(), NbtError> {
+        self.visit_value(tag_type, visitor)
+            .map_err(|error| error.at(Some(self.reader.position)))?;
+        Ok(())
     }
 
-    fn parse_nbt_tag(&mut self) -> Result<(String, NbtValue), NbtError> {
-        let mut header = [0_u8; 1];
-        self.reader.read_exact(&mut header)?;
+    /// Skips over an NBT value of the given tag type without allocating it.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_type: u8` - The tag type of the NBT value to be skipped.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the value was skipped.
+    /// * `Err(NbtError)` - On failure, returns an NbtError.
+    pub fn skip_nbt_value(&mut self, tag_type: u8) -> Result<(), NbtError> {
+        self.skip_value(tag_type)
+            .map_err(|error| error.at(Some(self.reader.position)))
+    }
 
-        if let Some(tag) = NbtValue::from_binary(header[0]) {
-            if let NbtValue::End = tag {
-                return Ok((String::new(), NbtValue::End));
+    fn skip_value(&mut self, tag_type: u8) -> Result<(), NbtError> {
+        match tag_type {
+            0x00 => {}
+            0x01..=0x06 => self.skip_bytes(fixed_size(tag_type).unwrap_or(0))?,
+            0x07 => {
+                let length = self.parse_array_length(NbtError::InvalidByteArrayLength)?;
+                self.skip_bytes(length as u64)?;
+            }
+            0x08 => {
+                let length = self.parse_short()? as u16;
+                self.skip_bytes(length as u64)?;
             }
+            0x09 => {
+                let (list_tag_type, length) = self.parse_list_header()?;
+                self.skip_list_elements(list_tag_type, length)?;
+            }
+            0x0A => loop {
+                let tag = self.parse_byte()? as u8;
+                if tag == 0x00 {
+                    break;
+                }
+                if NbtValue::from_binary(tag).is_none() {
+                    return Err(NbtError::InvalidTagType(tag));
+                }
+                let name_length = self.parse_short()? as u16;
+                self.skip_bytes(name_length as u64)?;
+                self.skip_value(tag)?;
+            },
+            0x0B => {
+                let length = self.parse_array_length(NbtError::InvalidIntArrayLength)?;
+                self.skip_bytes(length as u64 * 4)?;
+            }
+            0x0C => {
+                let length = self.parse_array_length(NbtError::InvalidLongArrayLength)?;
+                self.skip_bytes(length as u64 * 8)?;
+            }
+            _ => return Err(NbtError::InvalidTagType(tag_type)),
+        }
+        Ok(())
+    }
 
-            let name = self.parse_string()?;
-            let value = self.parse_nbt_value(tag.to_binary())?;
+    /// Reads a value and reports it to the visitor, returning `false` if the visitor asked to stop.
+    fn visit_value<V: NbtVisitor>(
+        &mut self,
+        tag_type: u8,
+        visitor: &mut V,
+    ) -> Result<bool, NbtError> {
+        match tag_type {
+            0x00 => {}
+            0x01 => visitor.visit_byte(self.parse_byte()?)?,
+            0x02 => visitor.visit_short(self.parse_short()?)?,
+            0x03 => visitor.visit_int(self.parse_int()?)?,
+            0x04 => visitor.visit_long(self.parse_long()?)?,
+            0x05 => visitor.visit_float(self.parse_float()?)?,
+            0x06 => visitor.visit_double(self.parse_double()?)?,
+            0x07 => visitor.visit_byte_array(self.parse_byte_array()?)?,
+            0x08 => visitor.visit_string(self.parse_string()?)?,
+            0x09 => {
+                let (list_tag_type, length) = self.parse_list_header()?;
+                match visitor.visit_list_start(list_tag_type, length)? {
+                    Visit::Continue => {
+                        for index in 0..length {
+                            let visited =
+                                self.visit_value(list_tag_type, visitor).map_err(|error| {
+                                    error.within(
+                                        NbtPathNode::Index(index as i32),
+                                        Some(self.reader.position),
+                                    )
+                                })?;
+                            if !visited {
+                                return Ok(false);
+                            }
+                        }
+                        visitor.visit_list_end()?;
+                    }
+                    Visit::Skip => self.skip_list_elements(list_tag_type, length)?,
+                    Visit::Stop => return Ok(false),
+                }
+            }
+            0x0A => match visitor.visit_compound_start()? {
+                Visit::Continue => {
+                    loop {
+                        let tag = self.parse_byte()? as u8;
+                        if tag == 0x00 {
+                            break;
+                        }
+                        if NbtValue::from_binary(tag).is_none() {
+                            return Err(NbtError::InvalidTagType(tag));
+                        }
+                        let start = self.push_key()?;
+                        let visited = match visitor.visit_key(&self.keys[start..], tag) {
+                            Ok(Visit::Continue) => self.visit_value(tag, visitor),
+                            Ok(Visit::Skip) => self.skip_value(tag).map(|_| true),
+                            Ok(Visit::Stop) => Ok(false),
+                            Err(error) => Err(error),
+                        }
+                        .map_err(|error| {
+                            let key = self.keys[start..].to_string();
+                            error.within(NbtPathNode::Key(key), Some(self.reader.position))
+                        });
+                        self.keys.truncate(start);
+                        if !visited? {
+                            return Ok(false);
+                        }
+                    }
+                    visitor.visit_compound_end()?;
+                }
+                Visit::Skip => self.skip_value(tag_type)?,
+                Visit::Stop => return Ok(false),
+            },
+            0x0B => visitor.visit_int_array(self.parse_int_array()?)?,
+            0x0C => visitor.visit_long_array(self.parse_long_array()?)?,
+            _ => return Err(NbtError::InvalidTagType(tag_type)),
+        }
+        Ok(true)
+    }
+
+    /// Reads a list's element tag type and length. Empty lists may carry any element tag type.
+    fn parse_list_header(&mut self) -> Result<(u8, usize), NbtError> {
+        let list_tag_type = self.parse_byte()? as u8;
+        let length = self.parse_int()?.max(0) as usize;
+
+        if length > 0
+            && matches!(
+                NbtValue::from_binary(list_tag_type),
+                None | Some(NbtValue::End)
+            )
+        {
+            return Err(NbtError::InvalidTagType(list_tag_type));
+        }
+
+        Ok((list_tag_type, length))
+    }
+
+    fn parse_array_length(&mut self, error: fn(usize) -> NbtError) -> Result<usize, NbtError> {
+        let length = self.parse_int()?;
+        if length < 0 {
+            return Err(error(length as usize));
+        }
+        Ok(length as usize)
+    }
 
-            Ok((name, value))
-        } else {
-            Err(NbtError::InvalidTagType(header[0]))
+    fn skip_list_elements(&mut self, list_tag_type: u8, length: usize) -> Result<(), NbtError> {
+        match fixed_size(list_tag_type) {
+            Some(size) => self.skip_bytes(size * length as u64)?,
+            None => {
+                for _ in 0..length {
+                    self.skip_value(list_tag_type)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_bytes(&mut self, length: u64) -> Result<(), NbtError> {
+        let skipped = std::io::copy(&mut (&mut self.reader).take(length), &mut std::io::sink())?;
+        if skipped < length {
+            return Err(NbtError::UnexpectedEof);
         }
+        Ok(())
     }
 
     fn parse_double(&mut self) -> Result<f64, NbtError> {
@@ -251,7 +559,7 @@ impl<R: Read> NbtReader<R> {
     }
 
     fn parse_string(&mut self) -> Result<String, NbtError> {
-        let string_length = self.parse_short()? as usize;
+        let string_length = self.parse_short()? as u16 as usize;
         let mut string = String::with_capacity(string_length);
         let mut buffer = vec![0u8; string_length];
         self.reader.read_exact(&mut buffer)?;
@@ -259,14 +567,78 @@ impl<R: Read> NbtReader<R> {
         Ok(string)
     }
 
+    /// Reads a compound key onto the end of `keys`, reusing its buffers, and returns where it starts.
+    fn push_key(&mut self) -> Result<usize, NbtError> {
+        let key_length = self.parse_short()? as u16 as usize;
+        self.key_bytes.resize(key_length, 0);
+        self.reader.read_exact(&mut self.key_bytes)?;
+        let start = self.keys.len();
+        self.keys
+            .push_str(&String::from_utf8_lossy(&self.key_bytes));
+        Ok(start)
+    }
+
     fn parse_byte_array(&mut self) -> Result<Vec<i8>, NbtError> {
-        let array_length = self.parse_int()? as usize;
-        let mut array = Vec::with_capacity(array_length);
-        for _ in 0..array_length {
-            let value = self.parse_byte()?;
-            array.push(value);
+        let array_length = self.parse_array_length(NbtError::InvalidByteArrayLength)?;
+        let reader = &mut self.reader;
+        read_array(array_length, |chunk| reader.read_i8_into(chunk))
+    }
+
+    fn parse_int_array(&mut self) -> Result<Vec<i32>, NbtError> {
+        let array_length = self.parse_array_length(NbtError::InvalidIntArrayLength)?;
+        let reader = &mut self.reader;
+        match self.endian {
+            Endian::Big => read_array(array_length, |chunk| {
+                reader.read_i32_into::<BigEndian>(chunk)
+            }),
+            Endian::Little => read_array(array_length, |chunk| {
+                reader.read_i32_into::<LittleEndian>(chunk)
+            }),
+        }
+    }
+
+    fn parse_long_array(&mut self) -> Result<Vec<i64>, NbtError> {
+        let array_length = self.parse_array_length(NbtError::InvalidLongArrayLength)?;
+        let reader = &mut self.reader;
+        match self.endian {
+            Endian::Big => read_array(array_length, |chunk| {
+                reader.read_i64_into::<BigEndian>(chunk)
+            }),
+            Endian::Little => read_array(array_length, |chunk| {
+                reader.read_i64_into::<LittleEndian>(chunk)
+            }),
         }
-        Ok(array)
+    }
+}
+
+/// The number of array elements read at a time, so a corrupted length cannot force a huge allocation
+/// before the data runs out.
+pub(crate) const ARRAY_CHUNK_LENGTH: usize = 64 * 1024;
+
+/// Reads `length` array elements in bulk. Each chunk is filled with a single `read_exact` and then
+/// byte-swapped in place by `read_into`.
+fn read_array<T: Default + Clone>(
+    length: usize,
+    mut read_into: impl FnMut(&mut [T]) -> std::io::Result<()>,
+) -> Result<Vec<T>, NbtError> {
+    let mut array = Vec::with_capacity(length.min(ARRAY_CHUNK_LENGTH));
+    while array.len() < length {
+        let start = array.len();
+        let end = start + (length - start).min(ARRAY_CHUNK_LENGTH);
+        array.resize(end, T::default());
+        read_into(&mut array[start..end])?;
+    }
+    Ok(array)
+}
+
+/// Returns the encoded size of tag types with a fixed size, i.e. the numeric tags.
+fn fixed_size(tag_type: u8) -> Option<u64> {
+    match tag_type {
+        0x01 => Some(1),
+        0x02 => Some(2),
+        0x03 | 0x05 => Some(4),
+        0x04 | 0x06 => Some(8),
+        _ => None,
     }
 }
 
@@ -298,24 +670,7 @@ pub fn read_from_file(
     compression: Compression,
     endian_style: Endian,
 ) -> Result<(String, NbtValue), NbtError> {
-    let mut file = File::open(path)?;
-
-    match compression {
-        Compression::Uncompressed => {
-            let mut parser = NbtReader::new(file, endian_style);
-            Ok(parser.parse_data()?)
-        }
-        Compression::Gzip => {
-            let mut decoder = GzDecoder::new(&mut file);
-            let mut parser = NbtReader::new(&mut decoder, endian_style);
-            Ok(parser.parse_data()?)
-        }
-        Compression::Zlib => {
-            let mut decoder = ZlibDecoder::new(&mut file);
-            let mut parser = NbtReader::new(&mut decoder, endian_style);
-            Ok(parser.parse_data()?)
-        }
-    }
+    read_from_reader(File::open(path)?, compression, endian_style)
 }
 
 /// Reads an NBT file from the given reader, decompresses it if necessary, and returns the parsed NBT value.
@@ -342,24 +697,236 @@ pub fn read_from_file(
 /// * `Ok((String, NbtValue))` - Returns a tuple containing the root tag name and the parsed NBT value on success.
 /// * `Err(NbtError)` - Returns an NbtError on failure.
 pub fn read_from_reader<R: Read>(
-    mut reader: R,
+    reader: R,
     compression: Compression,
     endian_style: Endian,
 ) -> Result<(String, NbtValue), NbtError> {
-    match compression {
-        Compression::Uncompressed => {
-            let mut parser = NbtReader::new(reader, endian_style);
-            Ok(parser.parse_data()?)
+    let mut parser = NbtReader::new(decoder(reader, compression)?, endian_style);
+    parser.parse_data()
+}
+
+/// Reads NBT data from the given reader like `read_from_reader`, recovering what it can from corrupted data.
+///
+/// See `NbtReader::parse_data_lenient` for how data is recovered. Data that stops part way through a compressed
+/// stream is recovered the same way as truncated uncompressed data.
+///
+/// # Arguments
+///
+/// * `reader: R` - The reader from which NBT data is read. This reader must implement the `Read` trait.
+/// * `compression: Compression` - The compression method used in the NBT file.
+/// * `endian_style: Endian` - The endian style of the NBT file (Big or Little).
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::nbt::{read_from_reader_lenient, Compression, Endian};
+/// use std::fs::File;
+///
+/// let file = File::open("./tests/data/java_level.dat").unwrap();
+///
+/// let (name, value, problem) = read_from_reader_lenient(file, Compression::Gzip, Endian::Big).unwrap();
+///
+/// assert!(problem.is_none());
+/// ```
+///
+/// # Returns
+///
+/// * `Ok((String, NbtValue, Option<NbtError>))` - The root tag name, the recovered NBT value, and the problem
+///   that stopped reading, if any.
+/// * `Err(NbtError)` - If the compression is not supported by this build.
+pub fn read_from_reader_lenient<R: Read>(
+    reader: R,
+    compression: Compression,
+    endian_style: Endian,
+) -> Result<(String, NbtValue, Option<NbtError>), NbtError> {
+    let mut parser = NbtReader::new(decoder(reader, compression)?, endian_style);
+    Ok(parser.parse_data_lenient())
+}
+
+/// Reads a region file chunk: a big endian length, the compression type id, then the compressed NBT data.
+///
+/// # Arguments
+///
+/// * `reader: R` - The reader positioned at the start of the chunk.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::nbt::{read_chunk, write_chunk, Compression, NbtValue};
+///
+/// let mut chunk = Vec::new();
+/// write_chunk(&NbtValue::new(), &mut chunk, Compression::Gzip, 1).unwrap();
+///
+/// let (value, compression) = read_chunk(&chunk[..]).unwrap();
+///
+/// assert_eq!(compression, Compression::Gzip);
+/// ```
+///
+/// # Returns
+///
+/// * `Ok((NbtValue, Compression))` - Returns the chunk NBT value and the compression it was stored with.
+/// * `Err(NbtError)` - Returns an NbtError on failure, `UnsupportedCompression` for custom compression.
+pub fn read_chunk<R: Read>(mut reader: R) -> Result<(NbtValue, Compression), NbtError> {
+    let length = reader.read_u32::<BigEndian>()?;
+    let compression = Compression::from_region_id(reader.read_u8()?)?;
+
+    let data = reader.take(u64::from(length.saturating_sub(1)));
+    let (_, value) = read_from_reader(data, compression, Endian::Big)?;
+    Ok((value, compression))
+}
+
+/// Reads an NBT file without knowing how it was stored, detecting the compression and endian style from its
+/// contents.
+///
+/// # Arguments
+///
+/// * `path: PathBuf` - The path to the NBT file.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::nbt::{read_auto, Compression, Endian};
+/// use std::path::PathBuf;
+///
+/// let (name, value, compression, endian, header) =
+///     read_auto(PathBuf::from("./tests/data/java_level.dat")).unwrap();
+///
+/// assert_eq!(compression, Compression::Gzip);
+/// assert_eq!(endian, Endian::Big);
+/// assert!(!header);
+/// ```
+///
+/// # Returns
+///
+/// * `Ok((String, NbtValue, Compression, Endian, bool))` - Returns the root tag name, the parsed NBT value, the
+///   detected compression and endian style, and whether the data started with the Bedrock header, so the data
+///   can be written back the same way.
+/// * `Err(NbtError)` - Returns an NbtError if the data could not be read in any known format.
+pub fn read_auto(path: PathBuf) -> Result<(String, NbtValue, Compression, Endian, bool), NbtError> {
+    read_auto_from_reader(File::open(path)?)
+}
+
+/// Reads NBT data from the given reader without knowing how it was stored, detecting the compression and
+/// endian style from its contents.
+///
+/// Gzip, zlib and LZ4 streams are recognised by their magic bytes. The decompressed data is then read as Bedrock
+/// Edition data if it starts with a valid 8-byte header, and as Java Edition data otherwise, falling back to
+/// little endian data without the header, as found in `.mcstructure` files, LevelDB values and network buffers,
+/// if the first guess does not parse. Data read without the header can be written back with
+/// `NbtWriter::write_root`, which does not add one.
+///
+/// # Arguments
+///
+/// * `reader: R` - The reader from which NBT data is read. This reader must implement the `Read` trait.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::nbt::{read_auto_from_reader, Compression, Endian};
+/// use std::fs::File;
+///
+/// let file = File::open("./tests/data/bedrock_level.dat").unwrap();
+///
+/// let (name, value, compression, endian, header) = read_auto_from_reader(file).unwrap();
+///
+/// assert_eq!(compression, Compression::Uncompressed);
+/// assert_eq!(endian, Endian::Little);
+/// assert!(header);
+/// ```
+///
+/// # Returns
+///
+/// * `Ok((String, NbtValue, Compression, Endian, bool))` - Returns the root tag name, the parsed NBT value, the
+///   detected compression and endian style, and whether the data started with the Bedrock header.
+/// * `Err(NbtError)` - Returns an NbtError if the data could not be read in any known format.
+pub fn read_auto_from_reader<R: Read>(
+    mut reader: R,
+) -> Result<(String, NbtValue, Compression, Endian, bool), NbtError> {
+    let mut raw = Vec::new();
+    reader.read_to_end(&mut raw)?;
+
+    let (data, compression) = decompress_auto(raw)?;
+
+    // Each candidate is an endian style and whether the data starts with the Bedrock header.
+    let candidates = if has_bedrock_header(&data) {
+        [
+            (Endian::Little, true),
+            (Endian::Big, false),
+            (Endian::Little, false),
+        ]
+    } else {
+        [
+            (Endian::Big, false),
+            (Endian::Little, false),
+            (Endian::Little, true),
+        ]
+    };
+
+    // A wrong guess can still parse a prefix of the data, so a candidate that consumes every byte wins. Failing
+    // that the first candidate that parsed at all is used, and failing that the error of the most likely one.
+    let mut partial = None;
+    let mut first_error = None;
+    for (endian, header) in candidates {
+        let mut parser = NbtReader::new(&data[..], endian);
+        let parsed = match header {
+            true => parser.parse_data(),
+            false => parser.parse_root(),
+        };
+        match parsed {
+            Ok((name, value)) if parser.position() == data.len() as u64 => {
+                return Ok((name, value, compression, endian, header));
+            }
+            Ok((name, value)) => {
+                partial.get_or_insert((name, value, compression, endian, header));
+            }
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    match (partial, first_error) {
+        (Some(result), _) => Ok(result),
+        (None, Some(error)) => Err(error),
+        (None, None) => unreachable!("at least one endian style is always tried"),
+    }
+}
+
+/// Detects compressed streams by their magic bytes and decompresses them, returning the data as is otherwise.
+fn decompress_auto(raw: Vec<u8>) -> Result<(Vec<u8>, Compression), NbtError> {
+    match raw[..] {
+        [0x1F, 0x8B, ..] => {
+            let mut data = Vec::new();
+            GzDecoder::new(&raw[..]).read_to_end(&mut data)?;
+            Ok((data, Compression::Gzip))
         }
-        Compression::Gzip => {
-            let mut decoder = GzDecoder::new(&mut reader);
-            let mut parser = NbtReader::new(&mut decoder, endian_style);
-            Ok(parser.parse_data()?)
+        [b'L', b'Z', b'4', b'B', b'l', b'o', b'c', b'k', ..] => {
+            let mut data = Vec::new();
+            decoder(&raw[..], Compression::Lz4)?.read_to_end(&mut data)?;
+            Ok((data, Compression::Lz4))
         }
-        Compression::Zlib => {
-            let mut decoder = ZlibDecoder::new(&mut reader);
-            let mut parser = NbtReader::new(&mut decoder, endian_style);
-            Ok(parser.parse_data()?)
+        [cmf, flg, ..]
+            if cmf & 0x0F == 0x08 && (u16::from(cmf) << 8 | u16::from(flg)) % 31 == 0 =>
+        {
+            // The zlib header check is only two bytes wide, so uncompressed data can pass it by chance.
+            let mut data = Vec::new();
+            match ZlibDecoder::new(&raw[..]).read_to_end(&mut data) {
+                Ok(_) => Ok((data, Compression::Zlib)),
+                Err(_) => Ok((raw, Compression::Uncompressed)),
+            }
+        }
+        _ => Ok((raw, Compression::Uncompressed)),
+    }
+}
+
+/// Checks for the Bedrock Edition header: a storage version followed by the length of the remaining data, both
+/// little endian.
+fn has_bedrock_header(data: &[u8]) -> bool {
+    match data {
+        [_, _, _, _, l0, l1, l2, l3, rest @ ..] => {
+            i32::from_le_bytes([*l0, *l1, *l2, *l3]) as usize == rest.len()
+                && rest.first() == Some(&0x0A)
         }
+        _ => false,
     }
 }
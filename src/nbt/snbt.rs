@@ -0,0 +1,406 @@
+use std::collections::HashMap;
+
+use crate::nbt::types::{NbtError, NbtValue};
+
+/// `SnbtParser` is a struct that parses stringified NBT (SNBT), the text format used by Minecraft commands,
+/// into `NbtValue`s.
+///
+/// # Fields
+///
+/// * `input: &str` - The SNBT text being parsed.
+/// * `position: usize` - The byte offset of the next character to be read.
+pub(crate) struct SnbtParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> SnbtParser<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        SnbtParser { input, position: 0 }
+    }
+
+    /// Returns the byte offset of the next character to be read.
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the parser to the given byte offset.
+    pub(crate) fn seek(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    pub(crate) fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn error(&self, message: &str) -> NbtError {
+        NbtError::InvalidSnbt(format!("{} at position {}", message, self.position))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), NbtError> {
+        self.skip_whitespace();
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(self.error(&format!("Expected '{}'", expected))),
+        }
+    }
+
+    /// Parses a single SNBT value starting at the current position.
+    pub(crate) fn parse_value(&mut self) -> Result<NbtValue, NbtError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.parse_compound(),
+            Some('[') => self.parse_list_or_array(),
+            Some('"') | Some('\'') => Ok(NbtValue::String(self.parse_quoted_string()?)),
+            Some(_) => {
+                let token = self.parse_unquoted_string();
+                if token.is_empty() {
+                    return Err(self.error("Expected value"));
+                }
+                Ok(parse_primitive(&token))
+            }
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    /// Parses an SNBT compound, e.g. `{id:"minecraft:stone",Count:1b}`, starting at the current position.
+    pub(crate) fn parse_compound(&mut self) -> Result<NbtValue, NbtError> {
+        self.expect('{')?;
+        let mut map = HashMap::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.next();
+            return Ok(NbtValue::Compound(map));
+        }
+
+        loop {
+            let key = self.parse_key()?;
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => break,
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+
+        Ok(NbtValue::Compound(map))
+    }
+
+    fn parse_key(&mut self) -> Result<String, NbtError> {
+        self.skip_whitespace();
+        let key = match self.peek() {
+            Some('"') | Some('\'') => self.parse_quoted_string()?,
+            _ => self.parse_unquoted_string(),
+        };
+        if key.is_empty() {
+            return Err(self.error("Expected key"));
+        }
+        Ok(key)
+    }
+
+    fn parse_list_or_array(&mut self) -> Result<NbtValue, NbtError> {
+        self.expect('[')?;
+
+        let rest = &self.input[self.position..];
+        let mut chars = rest.chars();
+        if let (Some(prefix), Some(';')) = (chars.next(), chars.next()) {
+            if matches!(prefix, 'B' | 'I' | 'L') {
+                self.position += 2;
+                return self.parse_array(prefix);
+            }
+        }
+
+        let mut vec: Vec<NbtValue> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.next();
+            return Ok(NbtValue::List(vec.into()));
+        }
+
+        loop {
+            let value = self.parse_value()?;
+            if let Some(first) = vec.first() {
+                if first.to_binary() != value.to_binary() {
+                    return Err(NbtError::InvalidListType(value.to_binary()));
+                }
+            }
+            vec.push(value);
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => break,
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+
+        Ok(NbtValue::List(vec.into()))
+    }
+
+    fn parse_array(&mut self, prefix: char) -> Result<NbtValue, NbtError> {
+        let mut values = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.next();
+        } else {
+            loop {
+                values.push(self.parse_value()?);
+                self.skip_whitespace();
+                match self.next() {
+                    Some(',') => continue,
+                    Some(']') => break,
+                    _ => return Err(self.error("Expected ',' or ']'")),
+                }
+            }
+        }
+
+        let (tag, array) = match prefix {
+            'B' => (
+                0x01,
+                values
+                    .iter()
+                    .map(|value| match value {
+                        NbtValue::Byte(v) => Some(*v),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<i8>>>()
+                    .map(NbtValue::ByteArray),
+            ),
+            'I' => (
+                0x03,
+                values
+                    .iter()
+                    .map(|value| match value {
+                        NbtValue::Int(v) => Some(*v),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<i32>>>()
+                    .map(NbtValue::IntArray),
+            ),
+            _ => (
+                0x04,
+                values
+                    .iter()
+                    .map(|value| match value {
+                        NbtValue::Long(v) => Some(*v),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<i64>>>()
+                    .map(NbtValue::LongArray),
+            ),
+        };
+
+        array.ok_or_else(|| {
+            let found = values
+                .iter()
+                .map(NbtValue::to_binary)
+                .find(|found| *found != tag)
+                .unwrap_or(0);
+            NbtError::TypeMismatch(tag, found)
+        })
+    }
+
+    pub(crate) fn parse_quoted_string(&mut self) -> Result<String, NbtError> {
+        let quote = match self.next() {
+            Some(c @ '"') | Some(c @ '\'') => c,
+            _ => return Err(self.error("Expected quoted string")),
+        };
+
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                Some('\\') => match self.next() {
+                    Some(c) if c == '\\' || c == quote => string.push(c),
+                    _ => return Err(self.error("Invalid escape sequence")),
+                },
+                Some(c) if c == quote => return Ok(string),
+                Some(c) => string.push(c),
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    fn parse_unquoted_string(&mut self) -> String {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if !is_unquoted_char(c) {
+                break;
+            }
+            self.position += c.len_utf8();
+        }
+        self.input[start..self.position].to_string()
+    }
+}
+
+/// Returns `true` if the character may appear in an unquoted SNBT string or key.
+pub(crate) fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+fn parse_primitive(token: &str) -> NbtValue {
+    match token {
+        "true" => return NbtValue::Byte(1),
+        "false" => return NbtValue::Byte(0),
+        _ => {}
+    }
+
+    let is_numeric = |body: &str| {
+        body.starts_with(|c: char| c.is_ascii_digit() || matches!(c, '-' | '+' | '.'))
+            && body
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+    };
+
+    // `to_snbt` writes NaN and infinite floats and doubles like this, so they read back as the same type.
+    let is_non_finite = |body: &str| matches!(body, "NaN" | "inf" | "-inf");
+
+    let (body, suffix) = token.split_at(token.len() - 1);
+    let parsed = match suffix {
+        _ if !is_numeric(body) && !is_numeric(token) && !is_non_finite(body) => None,
+        "b" | "B" => body.parse::<i8>().ok().map(NbtValue::Byte),
+        "s" | "S" => body.parse::<i16>().ok().map(NbtValue::Short),
+        "l" | "L" => body.parse::<i64>().ok().map(NbtValue::Long),
+        "f" | "F" => body.parse::<f32>().ok().map(NbtValue::Float),
+        "d" | "D" => body.parse::<f64>().ok().map(NbtValue::Double),
+        _ if !is_numeric(token) => None,
+        _ => token.parse::<i32>().ok().map(NbtValue::Int).or_else(|| {
+            if token.contains(['.', 'e', 'E']) {
+                token.parse::<f64>().ok().map(NbtValue::Double)
+            } else {
+                None
+            }
+        }),
+    };
+
+    if let Some(value) = parsed {
+        return value;
+    }
+
+    NbtValue::String(token.to_string())
+}
+
+/// Formats a string as an SNBT string literal, quoting and escaping it.
+pub(crate) fn quote_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats a compound key, leaving it unquoted when possible.
+pub(crate) fn format_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(is_unquoted_char) {
+        key.to_string()
+    } else {
+        quote_string(key)
+    }
+}
+
+pub(crate) fn write_snbt(value: &NbtValue, out: &mut String) {
+    fn join<T>(out: &mut String, items: &[T], mut write: impl FnMut(&mut String, &T)) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            write(out, item);
+        }
+    }
+
+    match value {
+        NbtValue::End => {}
+        NbtValue::Byte(v) => out.push_str(&format!("{}b", v)),
+        NbtValue::Short(v) => out.push_str(&format!("{}s", v)),
+        NbtValue::Int(v) => out.push_str(&v.to_string()),
+        NbtValue::Long(v) => out.push_str(&format!("{}L", v)),
+        NbtValue::Float(v) => out.push_str(&format!("{}f", v)),
+        NbtValue::Double(v) => out.push_str(&format!("{}d", v)),
+        NbtValue::String(v) => out.push_str(&quote_string(v)),
+        NbtValue::ByteArray(v) => {
+            out.push_str("[B;");
+            join(out, v, |out, item| out.push_str(&format!("{}b", item)));
+            out.push(']');
+        }
+        NbtValue::IntArray(v) => {
+            out.push_str("[I;");
+            join(out, v, |out, item| out.push_str(&item.to_string()));
+            out.push(']');
+        }
+        NbtValue::LongArray(v) => {
+            out.push_str("[L;");
+            join(out, v, |out, item| out.push_str(&format!("{}L", item)));
+            out.push(']');
+        }
+        NbtValue::List(v) => {
+            out.push('[');
+            join(out, v, |out, item| write_snbt(item, out));
+            out.push(']');
+        }
+        NbtValue::Compound(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            join(out, &keys, |out, key| {
+                out.push_str(&format_key(key));
+                out.push(':');
+                write_snbt(&map[*key], out);
+            });
+            out.push('}');
+        }
+    }
+}
+
+/// Parses a stringified NBT (SNBT) value, the text format used by Minecraft commands.
+///
+/// # Arguments
+///
+/// * `input: &str` - The SNBT text, e.g. `{id:"minecraft:stone",Count:1b}`.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::nbt::{parse_snbt, NbtValue};
+///
+/// let value = parse_snbt(r#"{id:"minecraft:stone",Count:1b}"#).unwrap();
+/// assert_eq!(value.get("Count"), Some(&NbtValue::Byte(1)));
+/// ```
+///
+/// # Returns
+///
+/// * `Ok(NbtValue)` - On success, returns the parsed NBT value.
+/// * `Err(NbtError)` - If the text is not valid SNBT.
+pub fn parse_snbt(input: &str) -> Result<NbtValue, NbtError> {
+    let mut parser = SnbtParser::new(input);
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(parser.error("Trailing data"));
+    }
+    Ok(value)
+}
@@ -7,8 +7,13 @@ use std::{
 #[cfg(feature = "serde")]
 use serde::{ser::SerializeMap, Serialize};
 
+use crate::nbt::{
+    list::NbtList,
+    path::{NbtPath, NbtPathNode},
+};
+
 /// Enum representing the different types of compression that can be used.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Compression {
     /// No compression is used.
     Uncompressed,
@@ -16,19 +21,74 @@ pub enum Compression {
     Gzip,
     /// Zlib compression is used.
     Zlib,
+    /// LZ4 compression is used, in the block stream format Java Edition uses for region chunks.
+    ///
+    /// Reading and writing LZ4 data requires the `lz4` feature.
+    Lz4,
+}
+
+impl Compression {
+    /// Returns the compression for a compression type id as stored in a region file chunk header.
+    ///
+    /// # Arguments
+    ///
+    /// * `id: u8` - The compression type id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::Compression;
+    ///
+    /// assert_eq!(Compression::from_region_id(2).unwrap(), Compression::Zlib);
+    /// assert!(Compression::from_region_id(127).is_err());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Compression)` - The compression for the id.
+    /// * `Err(NbtError)` - `UnsupportedCompression` for custom compression (127) and chunks stored outside the
+    ///   region file, or `InvalidCompression` for unknown ids.
+    pub fn from_region_id(id: u8) -> Result<Compression, NbtError> {
+        match id {
+            1 => Ok(Compression::Gzip),
+            2 => Ok(Compression::Zlib),
+            3 => Ok(Compression::Uncompressed),
+            4 => Ok(Compression::Lz4),
+            127 => Err(NbtError::UnsupportedCompression(
+                "custom compression (127)".to_string(),
+            )),
+            id if id & 0x80 != 0 && Compression::from_region_id(id & 0x7F).is_ok() => Err(
+                NbtError::UnsupportedCompression(format!("external chunk data ({})", id)),
+            ),
+            id => Err(NbtError::InvalidCompression(id)),
+        }
+    }
+
+    /// Returns the compression type id used for this compression in a region file chunk header.
+    pub fn region_id(&self) -> u8 {
+        match self {
+            Compression::Gzip => 1,
+            Compression::Zlib => 2,
+            Compression::Uncompressed => 3,
+            Compression::Lz4 => 4,
+        }
+    }
 }
 
 /// Enum representing the endianness of the data.
 ///
 /// Big is used mostly for Java, while Little is used for everything else, i.e., Bedrock.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Endian {
     Big,
     Little,
 }
 
 /// Enum representing the different types of NBT errors that can occur.
+///
+/// New variants may be added in minor releases, so matches on it need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum NbtError {
     /// Represents an error where an NBT list is empty.
     EmptyList,
@@ -50,11 +110,115 @@ pub enum NbtError {
     InvalidIntArrayLength(usize),
     /// Represents an error where an invalid long array length is used.
     InvalidLongArrayLength(usize),
+    /// Represents an error where a list does not have the number of elements its field requires.
+    InvalidListLength(usize),
+    /// Represents an error where a value of one tag type was expected (first) but another was found (second).
+    TypeMismatch(u8, u8),
+    /// Represents an error where SNBT text could not be parsed.
+    InvalidSnbt(String),
+    /// Represents an error where an NBT path could not be parsed.
+    InvalidPath(String),
+    /// Represents an error where an NBT path did not match any value.
+    PathNotFound(String),
+    /// Represents an error where data uses a compression this build cannot read or write.
+    UnsupportedCompression(String),
+    /// Represents an error where the data ended before a complete value was read.
+    UnexpectedEof,
+    /// Represents an error where a string or key is longer than the 65535 bytes NBT can store.
+    StringTooLong(usize),
+    /// Represents an error where a Bedrock world's LevelDB database could not be read.
+    InvalidLevelDb(String),
+    /// Represents an error where Bedrock world data, such as a subchunk, could not be decoded.
+    InvalidBedrockData(String),
+    /// Represents an error where a value cannot be converted to another format without losing data.
+    UnsupportedConversion(String),
+    /// Wraps an error that occurred while reading or validating NBT data with where it happened.
+    Context {
+        /// The byte offset reading had reached when the error was detected, if it happened while reading.
+        offset: Option<u64>,
+        /// The path of the value being read or validated, empty for the root value.
+        path: NbtPath,
+        /// The error that occurred.
+        error: Box<NbtError>,
+    },
+}
+
+impl NbtError {
+    /// Returns the byte offset at which reading failed, if the error carries one.
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            NbtError::Context { offset, .. } => *offset,
+            _ => None,
+        }
+    }
+
+    /// Returns the path of the value that was being read or validated when the error happened, if the error
+    /// carries one.
+    pub fn path(&self) -> Option<&NbtPath> {
+        match self {
+            NbtError::Context { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying error, without the offset and path of `NbtError::Context`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{NbtError, NbtReader, Endian};
+    ///
+    /// let data = [0x00, 0x00, 0x00, 0x04, 0x01];
+    /// let error = NbtReader::new(&data[..], Endian::Big).parse_nbt_value(0x07).unwrap_err();
+    ///
+    /// assert_eq!(error.offset(), Some(5));
+    /// assert!(matches!(error.without_context(), NbtError::UnexpectedEof));
+    /// ```
+    pub fn without_context(&self) -> &NbtError {
+        match self {
+            NbtError::Context { error, .. } => error.without_context(),
+            error => error,
+        }
+    }
+
+    /// Adds the offset to an error that has no context yet.
+    pub(crate) fn at(self, offset: Option<u64>) -> NbtError {
+        match self {
+            NbtError::Context { .. } => self,
+            error => NbtError::Context {
+                offset,
+                path: NbtPath::new(Vec::new()),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Records that the error happened inside the value at `node`, adding the offset if it has no context yet.
+    pub(crate) fn within(self, node: NbtPathNode, offset: Option<u64>) -> NbtError {
+        match self.at(offset) {
+            NbtError::Context {
+                offset,
+                mut path,
+                error,
+            } => {
+                path.prepend(node);
+                NbtError::Context {
+                    offset,
+                    path,
+                    error,
+                }
+            }
+            error => error,
+        }
+    }
 }
 
 impl From<std::io::Error> for NbtError {
     fn from(e: std::io::Error) -> NbtError {
-        NbtError::IoError(e)
+        match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => NbtError::UnexpectedEof,
+            _ => NbtError::IoError(e),
+        }
     }
 }
 
@@ -79,6 +243,43 @@ impl Display for NbtError {
             NbtError::InvalidLongArrayLength(ref len) => {
                 write!(f, "Invalid long array length: {}", len)
             }
+            NbtError::InvalidListLength(ref len) => write!(f, "Invalid list length: {}", len),
+            NbtError::TypeMismatch(ref expected, ref found) => {
+                write!(
+                    f,
+                    "Type mismatch: expected tag {}, found tag {}",
+                    expected, found
+                )
+            }
+            NbtError::InvalidSnbt(ref message) => write!(f, "Invalid SNBT: {}", message),
+            NbtError::InvalidPath(ref message) => write!(f, "Invalid path: {}", message),
+            NbtError::PathNotFound(ref path) => write!(f, "Path not found: {}", path),
+            NbtError::UnsupportedCompression(ref compression) => {
+                write!(f, "Unsupported compression: {}", compression)
+            }
+            NbtError::UnexpectedEof => write!(f, "Unexpected end of data"),
+            NbtError::StringTooLong(ref len) => write!(f, "String too long: {} bytes", len),
+            NbtError::InvalidLevelDb(ref message) => write!(f, "Invalid LevelDB data: {}", message),
+            NbtError::InvalidBedrockData(ref message) => {
+                write!(f, "Invalid Bedrock data: {}", message)
+            }
+            NbtError::UnsupportedConversion(ref message) => {
+                write!(f, "Unsupported conversion: {}", message)
+            }
+            NbtError::Context {
+                offset,
+                ref path,
+                ref error,
+            } => {
+                write!(f, "{}", error)?;
+                if let Some(offset) = offset {
+                    write!(f, " at byte {}", offset)?;
+                }
+                if !path.nodes().is_empty() {
+                    write!(f, " in {}", path)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -96,6 +297,18 @@ impl Error for NbtError {
             NbtError::InvalidByteArrayLength(_) => None,
             NbtError::InvalidIntArrayLength(_) => None,
             NbtError::InvalidLongArrayLength(_) => None,
+            NbtError::InvalidListLength(_) => None,
+            NbtError::TypeMismatch(_, _) => None,
+            NbtError::InvalidSnbt(_) => None,
+            NbtError::InvalidPath(_) => None,
+            NbtError::PathNotFound(_) => None,
+            NbtError::UnsupportedCompression(_) => None,
+            NbtError::UnexpectedEof => None,
+            NbtError::StringTooLong(_) => None,
+            NbtError::InvalidLevelDb(_) => None,
+            NbtError::InvalidBedrockData(_) => None,
+            NbtError::UnsupportedConversion(_) => None,
+            NbtError::Context { ref error, .. } => Some(error.as_ref()),
         }
     }
 }
@@ -122,8 +335,8 @@ pub enum NbtValue {
     ByteArray(Vec<i8>),
     /// Represents a string.
     String(String),
-    /// Represents a list of NBT values.
-    List(Vec<NbtValue>),
+    /// Represents a list of NBT values, which keeps its element tag type even when empty.
+    List(NbtList),
     /// Represents a compound NBT tag, which is a collection of NBT tags.
     Compound(HashMap<String, NbtValue>),
     /// Represents an array of integers (32 bits each).
@@ -202,6 +415,12 @@ impl Debug for NbtValue {
     }
 }
 
+impl Default for NbtValue {
+    fn default() -> Self {
+        NbtValue::new()
+    }
+}
+
 impl NbtValue {
     /// Creates a new `NbtValue::Compound` with an empty `HashMap`.
     ///
@@ -325,7 +544,7 @@ impl NbtValue {
         }
     }
 
-    /// Returns the number of elements in the `NbtValue::Compound`.
+    /// Returns the number of elements in the `NbtValue::Compound` or `NbtValue::List`.
     ///
     /// # Examples
     ///
@@ -339,15 +558,16 @@ impl NbtValue {
     ///
     /// # Returns
     ///
-    /// * `usize` - The number of elements in the `NbtValue::Compound`.
+    /// * `usize` - The number of elements in the `NbtValue::Compound` or `NbtValue::List`.
     pub fn len(&self) -> usize {
         match self {
             NbtValue::Compound(ref map) => map.len(),
-            _ => panic!("Cannot get length of non-compound NBT value"),
+            NbtValue::List(ref vec) => vec.len(),
+            _ => panic!("Cannot get length of non-compound, non-list NBT value"),
         }
     }
 
-    /// Returns `true` if the `NbtValue::Compound` contains no elements.
+    /// Returns `true` if the `NbtValue::Compound` or `NbtValue::List` contains no elements.
     ///
     /// # Examples
     ///
@@ -360,12 +580,13 @@ impl NbtValue {
     ///
     /// # Returns
     ///
-    /// * `true` - If the `NbtValue::Compound` contains no elements.
+    /// * `true` - If the `NbtValue::Compound` or `NbtValue::List` contains no elements.
     /// * `false` - Otherwise.
     pub fn is_empty(&self) -> bool {
         match self {
             NbtValue::Compound(ref map) => map.is_empty(),
-            _ => panic!("Cannot get length of non-compound NBT value"),
+            NbtValue::List(ref vec) => vec.is_empty(),
+            _ => panic!("Cannot get length of non-compound, non-list NBT value"),
         }
     }
 
@@ -428,7 +649,7 @@ impl NbtValue {
     /// # Returns
     ///
     /// * `std::collections::hash_map::Iter<String, NbtValue>` - An iterator over the `NbtValue::Compound`.
-    pub fn iter(&self) -> std::collections::hash_map::Iter<String, NbtValue> {
+    pub fn iter(&self) -> std::collections::hash_map::Iter<'_, String, NbtValue> {
         match self {
             NbtValue::Compound(ref map) => map.iter(),
             _ => panic!("Cannot iterate over non-compound NBT value"),
@@ -450,13 +671,296 @@ impl NbtValue {
     /// # Returns
     ///
     /// * `std::collections::hash_map::IterMut<String, NbtValue>` - A mutable iterator over the `NbtValue::Compound`.
-    pub fn iter_mut(&mut self) -> std::collections::hash_map::IterMut<String, NbtValue> {
+    pub fn iter_mut(&mut self) -> std::collections::hash_map::IterMut<'_, String, NbtValue> {
         match self {
             NbtValue::Compound(ref mut map) => map.iter_mut(),
             _ => panic!("Cannot iterate over non-compound NBT value"),
         }
     }
 
+    /// Returns a reference to the inner map if this is an `NbtValue::Compound`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::NbtValue;
+    ///
+    /// let nbt = NbtValue::new();
+    /// assert!(nbt.as_compound().is_some());
+    /// assert!(NbtValue::Int(1).as_compound().is_none());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&HashMap<String, NbtValue>)` - If the value is a compound.
+    /// * `None` - Otherwise.
+    pub fn as_compound(&self) -> Option<&HashMap<String, NbtValue>> {
+        match self {
+            NbtValue::Compound(ref map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the inner map if this is an `NbtValue::Compound`.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&mut HashMap<String, NbtValue>)` - If the value is a compound.
+    /// * `None` - Otherwise.
+    pub fn as_compound_mut(&mut self) -> Option<&mut HashMap<String, NbtValue>> {
+        match self {
+            NbtValue::Compound(ref mut map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the inner list if this is an `NbtValue::List`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::NbtValue;
+    ///
+    /// let list = NbtValue::from(vec![NbtValue::Int(1)]);
+    /// assert_eq!(list.as_list().map(|v| v.len()), Some(1));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&NbtList)` - If the value is a list.
+    /// * `None` - Otherwise.
+    pub fn as_list(&self) -> Option<&NbtList> {
+        match self {
+            NbtValue::List(ref vec) => Some(vec),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the inner list if this is an `NbtValue::List`.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&mut NbtList)` - If the value is a list.
+    /// * `None` - Otherwise.
+    pub fn as_list_mut(&mut self) -> Option<&mut NbtList> {
+        match self {
+            NbtValue::List(ref mut vec) => Some(vec),
+            _ => None,
+        }
+    }
+
+    /// Inserts a key-value pair into the `NbtValue::Compound` without panicking.
+    ///
+    /// # Arguments
+    ///
+    /// * `key: String` - A string that holds the key.
+    /// * `value: T` - A value that can be converted into `NbtValue`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::NbtValue;
+    ///
+    /// let mut nbt = NbtValue::new();
+    /// assert!(nbt.try_insert("key".to_string(), 1).is_ok());
+    /// assert!(NbtValue::Int(0).try_insert("key".to_string(), 1).is_err());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Option<NbtValue>)` - The previous value at the key, if any.
+    /// * `Err(NbtError::TypeMismatch)` - If the value is not a compound.
+    pub fn try_insert<T: Into<NbtValue>>(
+        &mut self,
+        key: String,
+        value: T,
+    ) -> Result<Option<NbtValue>, NbtError> {
+        let found = self.to_binary();
+        match self.as_compound_mut() {
+            Some(map) => Ok(map.insert(key, value.into())),
+            None => Err(NbtError::TypeMismatch(0x0A, found)),
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key, or `None` if the key is missing
+    /// or the value is not an `NbtValue::Compound`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key: &str` - A string slice that holds the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::NbtValue;
+    ///
+    /// let mut nbt = NbtValue::new();
+    /// nbt.insert("key".to_string(), 1);
+    /// assert_eq!(nbt.try_get("key"), Some(&NbtValue::Int(1)));
+    /// assert_eq!(NbtValue::Int(0).try_get("key"), None);
+    /// ```
+    pub fn try_get(&self, key: &str) -> Option<&NbtValue> {
+        self.as_compound().and_then(|map| map.get(key))
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key, or `None` if the key is
+    /// missing or the value is not an `NbtValue::Compound`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key: &str` - A string slice that holds the key.
+    pub fn try_get_mut(&mut self, key: &str) -> Option<&mut NbtValue> {
+        self.as_compound_mut().and_then(|map| map.get_mut(key))
+    }
+
+    /// Removes a key from the `NbtValue::Compound`, returning `None` if the key is missing or the
+    /// value is not a compound.
+    ///
+    /// # Arguments
+    ///
+    /// * `key: &str` - A string slice that holds the key.
+    pub fn try_remove(&mut self, key: &str) -> Option<NbtValue> {
+        self.as_compound_mut().and_then(|map| map.remove(key))
+    }
+
+    /// Returns the number of elements in the `NbtValue::Compound` or `NbtValue::List`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::NbtValue;
+    ///
+    /// assert_eq!(NbtValue::new().try_len(), Some(0));
+    /// assert_eq!(NbtValue::Int(0).try_len(), None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Some(usize)` - The number of elements.
+    /// * `None` - If the value is neither a compound nor a list.
+    pub fn try_len(&self) -> Option<usize> {
+        match self {
+            NbtValue::Compound(ref map) => Some(map.len()),
+            NbtValue::List(ref vec) => Some(vec.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns the keys of the `NbtValue::Compound`, or `None` if the value is not a compound.
+    pub fn try_keys(&self) -> Option<Vec<&String>> {
+        self.as_compound().map(|map| map.keys().collect())
+    }
+
+    /// Returns the values of the `NbtValue::Compound`, or `None` if the value is not a compound.
+    pub fn try_values(&self) -> Option<Vec<&NbtValue>> {
+        self.as_compound().map(|map| map.values().collect())
+    }
+
+    /// Returns an iterator over the `NbtValue::Compound`, or `None` if the value is not a compound.
+    pub fn try_iter(&self) -> Option<std::collections::hash_map::Iter<'_, String, NbtValue>> {
+        self.as_compound().map(|map| map.iter())
+    }
+
+    /// Returns a mutable iterator over the `NbtValue::Compound`, or `None` if the value is not a
+    /// compound.
+    pub fn try_iter_mut(
+        &mut self,
+    ) -> Option<std::collections::hash_map::IterMut<'_, String, NbtValue>> {
+        self.as_compound_mut().map(|map| map.iter_mut())
+    }
+
+    /// Appends a value to the end of the `NbtValue::List`.
+    ///
+    /// NBT lists are homogeneous, so the value must have the list's element tag type. An empty list of `End`
+    /// tags accepts a value of any type.
+    ///
+    /// # Arguments
+    ///
+    /// * `value: T` - A value that can be converted into `NbtValue`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{NbtList, NbtValue};
+    ///
+    /// let mut list = NbtValue::List(NbtList::default());
+    /// list.push(1).unwrap();
+    /// assert!(list.push("not an int").is_err());
+    /// assert_eq!(list.len(), 1);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the value was appended.
+    /// * `Err(NbtError::TypeMismatch)` - If the value is not a list.
+    /// * `Err(NbtError::InvalidListType)` - If the element type does not match the list.
+    pub fn push<T: Into<NbtValue>>(&mut self, value: T) -> Result<(), NbtError> {
+        let found = self.to_binary();
+        let list = self
+            .as_list_mut()
+            .ok_or(NbtError::TypeMismatch(0x09, found))?;
+        let value = value.into();
+
+        let element_type = list.element_type();
+        if (!list.is_empty() || element_type != 0x00) && element_type != value.to_binary() {
+            return Err(NbtError::InvalidListType(value.to_binary()));
+        }
+
+        list.push(value);
+        Ok(())
+    }
+
+    /// Returns a reference to the element at the given index of the `NbtValue::List`.
+    ///
+    /// # Arguments
+    ///
+    /// * `index: usize` - The position of the element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::NbtValue;
+    ///
+    /// let list = NbtValue::from(vec![NbtValue::Int(1)]);
+    /// assert_eq!(list.get_index(0), Some(&NbtValue::Int(1)));
+    /// assert_eq!(list.get_index(1), None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&NbtValue)` - If the value is a list and the index is in bounds.
+    /// * `None` - Otherwise.
+    pub fn get_index(&self, index: usize) -> Option<&NbtValue> {
+        self.as_list().and_then(|vec| vec.get(index))
+    }
+
+    /// Returns a mutable reference to the element at the given index of the `NbtValue::List`.
+    ///
+    /// # Arguments
+    ///
+    /// * `index: usize` - The position of the element.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut NbtValue> {
+        self.as_list_mut().and_then(|vec| vec.get_mut(index))
+    }
+
+    /// Removes and returns the element at the given index of the `NbtValue::List`, shifting all
+    /// elements after it to the left.
+    ///
+    /// # Arguments
+    ///
+    /// * `index: usize` - The position of the element.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(NbtValue)` - If the value is a list and the index is in bounds.
+    /// * `None` - Otherwise.
+    pub fn remove_index(&mut self, index: usize) -> Option<NbtValue> {
+        match self.as_list_mut() {
+            Some(vec) if index < vec.len() => Some(vec.remove(index)),
+            _ => None,
+        }
+    }
+
     pub fn from_binary(value: u8) -> Option<NbtValue> {
         match value {
             0x0 => Some(NbtValue::End),
@@ -468,7 +972,7 @@ impl NbtValue {
             0x6 => Some(NbtValue::Double(0.0)),
             0x7 => Some(NbtValue::ByteArray(Vec::new())),
             0x8 => Some(NbtValue::String(String::new())),
-            0x9 => Some(NbtValue::List(Vec::new())),
+            0x9 => Some(NbtValue::List(NbtList::default())),
             0xA => Some(NbtValue::Compound(HashMap::new())),
             0xB => Some(NbtValue::IntArray(Vec::new())),
             0xC => Some(NbtValue::LongArray(Vec::new())),
@@ -494,8 +998,49 @@ impl NbtValue {
         }
     }
 
+    /// Returns the name of the value's tag type, e.g. `"Int"` or `"Compound"`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            NbtValue::End => "End",
+            NbtValue::Byte(_) => "Byte",
+            NbtValue::Short(_) => "Short",
+            NbtValue::Int(_) => "Int",
+            NbtValue::Long(_) => "Long",
+            NbtValue::Float(_) => "Float",
+            NbtValue::Double(_) => "Double",
+            NbtValue::ByteArray(_) => "ByteArray",
+            NbtValue::String(_) => "String",
+            NbtValue::List(_) => "List",
+            NbtValue::Compound(_) => "Compound",
+            NbtValue::IntArray(_) => "IntArray",
+            NbtValue::LongArray(_) => "LongArray",
+        }
+    }
+
+    /// Formats the value as stringified NBT (SNBT), the text format used by Minecraft commands.
+    ///
+    /// Compound keys are sorted so the output is stable, and the result can be read back with `parse_snbt`.
+    ///
+    /// NaN and infinite floats and doubles are written as `NaNf`, `inff` and `-inff`, or with a `d` suffix.
+    /// `parse_snbt` reads these back as the same type, but Minecraft has no syntax for them and reads them as
+    /// strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{parse_snbt, NbtValue};
+    ///
+    /// let mut nbt = NbtValue::new();
+    /// nbt.insert("Count".to_string(), NbtValue::Byte(1));
+    /// nbt.insert("id".to_string(), "minecraft:stone");
+    ///
+    /// assert_eq!(nbt.to_snbt(), r#"{Count:1b,id:"minecraft:stone"}"#);
+    /// assert_eq!(parse_snbt(&nbt.to_snbt()).unwrap(), nbt);
+    /// ```
     pub fn to_snbt(&self) -> String {
-        format!("{:?}", self)
+        let mut out = String::new();
+        crate::nbt::snbt::write_snbt(self, &mut out);
+        out
     }
 }
 
@@ -569,6 +1114,12 @@ impl From<String> for NbtValue {
 
 impl From<Vec<NbtValue>> for NbtValue {
     fn from(value: Vec<NbtValue>) -> Self {
+        NbtValue::List(value.into())
+    }
+}
+
+impl From<NbtList> for NbtValue {
+    fn from(value: NbtList) -> Self {
         NbtValue::List(value)
     }
 }
@@ -668,7 +1219,7 @@ impl From<&NbtValue> for String {
 impl From<&NbtValue> for Vec<NbtValue> {
     fn from(value: &NbtValue) -> Self {
         match value {
-            NbtValue::List(v) => v.clone(),
+            NbtValue::List(v) => v.to_vec(),
             _ => panic!("Cannot convert {:?} to Vec<NbtValue>", value),
         }
     }
@@ -0,0 +1,95 @@
+use crate::nbt::{
+    path::NbtPathNode,
+    types::{NbtError, NbtValue},
+};
+
+/// The longest string NBT can store, as its length is written as an unsigned short.
+pub(crate) const MAX_STRING_LENGTH: usize = u16::MAX as usize;
+
+/// The longest array or list NBT can store, as its length is written as an int.
+const MAX_ARRAY_LENGTH: usize = i32::MAX as usize;
+
+fn validate_string(value: &str) -> Result<(), NbtError> {
+    if value.len() > MAX_STRING_LENGTH {
+        return Err(NbtError::StringTooLong(value.len()));
+    }
+    Ok(())
+}
+
+fn validate_child(value: &NbtValue, node: impl FnOnce() -> NbtPathNode) -> Result<(), NbtError> {
+    let result = match value {
+        // An End tag inside a compound would end it early, and lists cannot hold End tags.
+        NbtValue::End => Err(NbtError::InvalidTagType(0x00)),
+        value => validate_value(value),
+    };
+    result.map_err(|error| error.within(node(), None))
+}
+
+fn validate_value(value: &NbtValue) -> Result<(), NbtError> {
+    match value {
+        NbtValue::String(value) => validate_string(value),
+        NbtValue::ByteArray(vec) if vec.len() > MAX_ARRAY_LENGTH => {
+            Err(NbtError::InvalidByteArrayLength(vec.len()))
+        }
+        NbtValue::IntArray(vec) if vec.len() > MAX_ARRAY_LENGTH => {
+            Err(NbtError::InvalidIntArrayLength(vec.len()))
+        }
+        NbtValue::LongArray(vec) if vec.len() > MAX_ARRAY_LENGTH => {
+            Err(NbtError::InvalidLongArrayLength(vec.len()))
+        }
+        NbtValue::List(list) => {
+            let element_type = list.element_type();
+            for (index, item) in list.iter().enumerate() {
+                let node = || NbtPathNode::Index(index as i32);
+                if item.to_binary() != element_type {
+                    return Err(NbtError::InvalidListType(item.to_binary()).within(node(), None));
+                }
+                validate_child(item, node)?;
+            }
+            Ok(())
+        }
+        NbtValue::Compound(map) => {
+            for (key, item) in map {
+                let node = || NbtPathNode::Key(key.clone());
+                validate_string(key).map_err(|error| error.within(node(), None))?;
+                validate_child(item, node)?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+impl NbtValue {
+    /// Checks that the value can be written as NBT, so that writing it cannot fail part way through.
+    ///
+    /// The checks are that lists only hold elements of their element tag type, that strings and compound keys
+    /// fit in 65535 bytes, that arrays fit the length NBT can store, and that no `End` values are nested inside
+    /// lists or compounds. `write_to_file` and `write_to_writer` validate values before writing anything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use commandblock::nbt::{NbtError, NbtList, NbtValue};
+    ///
+    /// let mut list = NbtList::new(0x03);
+    /// list.push(NbtValue::Int(1));
+    /// list.push(NbtValue::String("two".to_string()));
+    ///
+    /// let mut value = NbtValue::new();
+    /// value.insert("Items".to_string(), list);
+    ///
+    /// let error = value.validate().unwrap_err();
+    /// assert_eq!(error.path().unwrap().to_string(), "Items[1]");
+    /// assert!(matches!(error.without_context(), NbtError::InvalidListType(0x08)));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the value can be written.
+    /// * `Err(NbtError)` - The first problem found, wrapped in `NbtError::Context` with its path when it is inside
+    ///   a list or compound.
+    pub fn validate(&self) -> Result<(), NbtError> {
+        validate_value(self)
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+
+use crate::nbt::{
+    list::NbtList,
+    reader::ARRAY_CHUNK_LENGTH,
+    types::{NbtError, NbtValue},
+};
+
+/// Enum representing what an `NbtReader` should do after a visitor callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    /// Read the value and report it to the visitor.
+    Continue,
+    /// Skip over the value without reporting or allocating it.
+    Skip,
+    /// Stop reading immediately, leaving the rest of the stream unread.
+    Stop,
+}
+
+/// `NbtVisitor` receives NBT values from `NbtReader::visit_data` and `NbtReader::visit_nbt_value` as they are
+/// read, without building an `NbtValue` tree.
+///
+/// Every callback has a default implementation that ignores the value, so visitors only implement what they
+/// need. Returning `Visit::Skip` from `visit_key`, `visit_list_start` or `visit_compound_start` skips the
+/// value's bytes without allocating, and `Visit::Stop` ends reading altogether.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::nbt::{NbtError, NbtReader, NbtVisitor, Endian, Visit};
+/// use std::fs::File;
+///
+/// // Finds the top level `LevelName` and skips everything else.
+/// #[derive(Default)]
+/// struct LevelName {
+///     depth: usize,
+///     wanted: bool,
+///     name: Option<String>,
+/// }
+///
+/// impl NbtVisitor for LevelName {
+///     fn visit_compound_start(&mut self) -> Result<Visit, NbtError> {
+///         self.depth += 1;
+///         Ok(Visit::Continue)
+///     }
+///
+///     fn visit_compound_end(&mut self) -> Result<(), NbtError> {
+///         self.depth -= 1;
+///         Ok(())
+///     }
+///
+///     fn visit_key(&mut self, key: &str, _tag_type: u8) -> Result<Visit, NbtError> {
+///         if self.name.is_some() {
+///             return Ok(Visit::Stop);
+///         }
+///         self.wanted = self.depth == 1 && key == "LevelName";
+///         Ok(if self.wanted { Visit::Continue } else { Visit::Skip })
+///     }
+///
+///     fn visit_string(&mut self, value: String) -> Result<(), NbtError> {
+///         if self.wanted {
+///             self.name = Some(value);
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let file = File::open("./tests/data/bedrock_level.dat").unwrap();
+/// let mut visitor = LevelName::default();
+/// NbtReader::new(file, Endian::Little).visit_data(&mut visitor).unwrap();
+///
+/// assert!(visitor.name.is_some());
+/// ```
+pub trait NbtVisitor {
+    /// Called with the name and tag type of the root tag read by `NbtReader::visit_data`.
+    fn visit_root(&mut self, _name: String, _tag_type: u8) -> Result<Visit, NbtError> {
+        Ok(Visit::Continue)
+    }
+
+    /// Called before the entries of a compound are read.
+    fn visit_compound_start(&mut self) -> Result<Visit, NbtError> {
+        Ok(Visit::Continue)
+    }
+
+    /// Called with the key and tag type of each compound entry, before its value is read. The key is borrowed
+    /// from the reader, so skipping an entry allocates nothing.
+    fn visit_key(&mut self, _key: &str, _tag_type: u8) -> Result<Visit, NbtError> {
+        Ok(Visit::Continue)
+    }
+
+    /// Called after the last entry of a compound has been read.
+    fn visit_compound_end(&mut self) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    /// Called with the element tag type and length of a list, before its elements are read.
+    fn visit_list_start(&mut self, _tag_type: u8, _length: usize) -> Result<Visit, NbtError> {
+        Ok(Visit::Continue)
+    }
+
+    /// Called after the last element of a list has been read.
+    fn visit_list_end(&mut self) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn visit_byte(&mut self, _value: i8) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn visit_short(&mut self, _value: i16) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn visit_int(&mut self, _value: i32) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn visit_long(&mut self, _value: i64) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn visit_float(&mut self, _value: f32) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn visit_double(&mut self, _value: f64) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn visit_string(&mut self, _value: String) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn visit_byte_array(&mut self, _value: Vec<i8>) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn visit_int_array(&mut self, _value: Vec<i32>) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn visit_long_array(&mut self, _value: Vec<i64>) -> Result<(), NbtError> {
+        Ok(())
+    }
+}
+
+/// `TreeBuilder` is the visitor behind `NbtReader::parse_nbt_value`, assembling visited values into an `NbtValue`.
+#[derive(Default)]
+pub(crate) struct TreeBuilder {
+    name: String,
+    key: Option<String>,
+    stack: Vec<(Option<String>, NbtValue)>,
+    root: Option<NbtValue>,
+}
+
+impl TreeBuilder {
+    pub(crate) fn new() -> Self {
+        TreeBuilder::default()
+    }
+
+    /// Returns the root tag name and the built value.
+    pub(crate) fn finish(self) -> (String, NbtValue) {
+        (self.name, self.root.unwrap_or(NbtValue::End))
+    }
+
+    /// Closes any lists and compounds that are still open, then returns the root tag name and the built value.
+    pub(crate) fn finish_partial(mut self) -> (String, NbtValue) {
+        while !self.stack.is_empty() {
+            self.pop();
+        }
+        self.finish()
+    }
+
+    fn add(&mut self, value: NbtValue) {
+        match self.stack.last_mut() {
+            Some((_, NbtValue::Compound(map))) => {
+                map.insert(self.key.take().unwrap_or_default(), value);
+            }
+            Some((_, NbtValue::List(vec))) => vec.push(value),
+            _ => self.root = Some(value),
+        }
+    }
+
+    fn pop(&mut self) {
+        if let Some((key, value)) = self.stack.pop() {
+            self.key = key;
+            self.add(value);
+        }
+    }
+}
+
+impl NbtVisitor for TreeBuilder {
+    fn visit_root(&mut self, name: String, _tag_type: u8) -> Result<Visit, NbtError> {
+        self.name = name;
+        Ok(Visit::Continue)
+    }
+
+    fn visit_compound_start(&mut self) -> Result<Visit, NbtError> {
+        self.stack
+            .push((self.key.take(), NbtValue::Compound(HashMap::new())));
+        Ok(Visit::Continue)
+    }
+
+    fn visit_key(&mut self, key: &str, _tag_type: u8) -> Result<Visit, NbtError> {
+        self.key = Some(key.to_string());
+        Ok(Visit::Continue)
+    }
+
+    fn visit_compound_end(&mut self) -> Result<(), NbtError> {
+        self.pop();
+        Ok(())
+    }
+
+    fn visit_list_start(&mut self, tag_type: u8, length: usize) -> Result<Visit, NbtError> {
+        // The length comes from the data, so only reserve up to a chunk and let the list grow past it.
+        let list = NbtList::with_capacity(tag_type, length.min(ARRAY_CHUNK_LENGTH));
+        self.stack.push((self.key.take(), NbtValue::List(list)));
+        Ok(Visit::Continue)
+    }
+
+    fn visit_list_end(&mut self) -> Result<(), NbtError> {
+        self.pop();
+        Ok(())
+    }
+
+    fn visit_byte(&mut self, value: i8) -> Result<(), NbtError> {
+        self.add(NbtValue::Byte(value));
+        Ok(())
+    }
+
+    fn visit_short(&mut self, value: i16) -> Result<(), NbtError> {
+        self.add(NbtValue::Short(value));
+        Ok(())
+    }
+
+    fn visit_int(&mut self, value: i32) -> Result<(), NbtError> {
+        self.add(NbtValue::Int(value));
+        Ok(())
+    }
+
+    fn visit_long(&mut self, value: i64) -> Result<(), NbtError> {
+        self.add(NbtValue::Long(value));
+        Ok(())
+    }
+
+    fn visit_float(&mut self, value: f32) -> Result<(), NbtError> {
+        self.add(NbtValue::Float(value));
+        Ok(())
+    }
+
+    fn visit_double(&mut self, value: f64) -> Result<(), NbtError> {
+        self.add(NbtValue::Double(value));
+        Ok(())
+    }
+
+    fn visit_string(&mut self, value: String) -> Result<(), NbtError> {
+        self.add(NbtValue::String(value));
+        Ok(())
+    }
+
+    fn visit_byte_array(&mut self, value: Vec<i8>) -> Result<(), NbtError> {
+        self.add(NbtValue::ByteArray(value));
+        Ok(())
+    }
+
+    fn visit_int_array(&mut self, value: Vec<i32>) -> Result<(), NbtError> {
+        self.add(NbtValue::IntArray(value));
+        Ok(())
+    }
+
+    fn visit_long_array(&mut self, value: Vec<i64>) -> Result<(), NbtError> {
+        self.add(NbtValue::LongArray(value));
+        Ok(())
+    }
+}
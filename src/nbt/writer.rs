@@ -1,8 +1,19 @@
-use std::{collections::HashMap, io::Write, path::PathBuf};
+use std::{borrow::Borrow, collections::HashMap, io::Write, path::PathBuf};
 
 use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
 
-use crate::nbt::types::{Compression, Endian, NbtError, NbtValue};
+use crate::nbt::{
+    compression::{encode, DEFAULT_COMPRESSION_LEVEL},
+    list::NbtList,
+    types::{Compression, Endian, NbtError, NbtValue},
+    validate::MAX_STRING_LENGTH,
+};
+
+/// The size in bytes of the stack buffer arrays are encoded into before being written.
+const WRITE_BUFFER_SIZE: usize = 8 * 1024;
+
+/// The storage version written in the Bedrock header when the value has no `StorageVersion` of its own.
+const DEFAULT_STORAGE_VERSION: i32 = 10;
 
 /// `NbtWriter` is a struct that writes NBT data to a writer encoding it according to the specified endian style.
 ///
@@ -31,6 +42,20 @@ pub struct NbtWriter<W: Write> {
     endian: Endian,
 }
 
+/// Discards what is written to it and counts the bytes, to measure encoded data without buffering it.
+struct ByteCounter(u64);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 impl<W: Write> NbtWriter<W> {
     /// Creates a new NbtWriter.
     ///
@@ -57,9 +82,12 @@ impl<W: Write> NbtWriter<W> {
 
     /// Writes an NbtValue to the writer.
     ///
+    /// The value can be passed either owned or by reference. Writing a reference does not clone or
+    /// allocate anything.
+    ///
     /// # Arguments
     ///
-    /// * `value: NbtValue` - The NBT value to be written.
+    /// * `value: V` - The NBT value to be written, either a `NbtValue` or a `&NbtValue`.
     ///
     /// # Examples
     ///
@@ -71,6 +99,7 @@ impl<W: Write> NbtWriter<W> {
     /// value.insert("test".to_string(), "test string");
     ///
     /// let mut writer = NbtWriter::new(Cursor::new(Vec::new()), Endian::Little);
+    /// writer.write_nbt_value(&value).unwrap();
     /// writer.write_nbt_value(value).unwrap();
     /// ```
     ///
@@ -78,28 +107,28 @@ impl<W: Write> NbtWriter<W> {
     ///
     /// * `Ok(())` - If the NBT value is successfully written.
     /// * `Err(NbtError)` - If there is an error while writing the NBT value.
-    pub fn write_nbt_value(&mut self, value: NbtValue) -> Result<(), NbtError> {
-        match value {
+    pub fn write_nbt_value<V: Borrow<NbtValue>>(&mut self, value: V) -> Result<(), NbtError> {
+        match value.borrow() {
             NbtValue::End => {
                 self.write_byte(0x00)?;
             }
             NbtValue::Byte(value) => {
-                self.write_byte(value)?;
+                self.write_byte(*value)?;
             }
             NbtValue::Short(value) => {
-                self.write_short(value)?;
+                self.write_short(*value)?;
             }
             NbtValue::Int(value) => {
-                self.write_int(value)?;
+                self.write_int(*value)?;
             }
             NbtValue::Long(value) => {
-                self.write_long(value)?;
+                self.write_long(*value)?;
             }
             NbtValue::Float(value) => {
-                self.write_float(value)?;
+                self.write_float(*value)?;
             }
             NbtValue::Double(value) => {
-                self.write_double(value)?;
+                self.write_double(*value)?;
             }
             NbtValue::ByteArray(value) => {
                 self.write_byte_array(value)?;
@@ -126,10 +155,14 @@ impl<W: Write> NbtWriter<W> {
 
     /// Writes an NbtValue with a name to the writer.
     ///
+    /// Little endian data starts with the header Bedrock files use: the storage version, taken from the
+    /// value's `StorageVersion` if it has one, and the length of the data that follows in bytes. The length is
+    /// counted by encoding the value once without storing the bytes, so nothing is buffered.
+    ///
     /// # Arguments
     ///
     /// * `data_name: Option<&str>` - Optional name for the root tag of the NBT data.
-    /// * `value: NbtValue` - The NBT value to be written.
+    /// * `value: V` - The NBT value to be written, either a `NbtValue` or a `&NbtValue`.
     ///
     /// # Examples
     ///
@@ -148,23 +181,54 @@ impl<W: Write> NbtWriter<W> {
     ///
     /// * `Ok(())` - If the NBT value is successfully written.
     /// * `Err(NbtError)` - If there is an error while writing the NBT value.
-    pub fn write_data(&mut self, data_name: Option<&str>, value: NbtValue) -> Result<(), NbtError> {
+    pub fn write_data<V: Borrow<NbtValue>>(
+        &mut self,
+        data_name: Option<&str>,
+        value: V,
+    ) -> Result<(), NbtError> {
+        let value = value.borrow();
         match self.endian {
-            Endian::Big => {
-                self.write_byte(0x0A)?;
-                self.write_string(data_name.unwrap_or("Data").to_string())?;
-                self.write_nbt_value(value)?;
-            }
+            Endian::Big => self.write_root(data_name.unwrap_or("Data"), value),
             Endian::Little => {
-                self.write_int(3)?;
-                self.write_int(value.len() as i32)?;
-                self.write_byte(0x0A)?;
-                self.write_string(data_name.unwrap_or("").to_string())?;
-                self.write_nbt_value(value)?;
+                // The header needs the payload length, so the payload is encoded once to count its bytes
+                // and then again into the writer.
+                let name = data_name.unwrap_or("");
+                let mut counter = NbtWriter::new(ByteCounter(0), Endian::Little);
+                counter.write_root(name, value)?;
+
+                let storage_version = match value.try_get("StorageVersion") {
+                    Some(NbtValue::Int(version)) => *version,
+                    _ => DEFAULT_STORAGE_VERSION,
+                };
+                self.write_int(storage_version)?;
+                self.write_int(counter.writer.0 as i32)?;
+                self.write_root(name, value)
             }
         }
+    }
 
-        Ok(())
+    /// Writes a compound with a name to the writer, without the header Bedrock files start with.
+    ///
+    /// This is how Bedrock stores NBT inside its world database, for example block states in subchunk palettes.
+    /// For big endian data it writes the same as `write_data`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name: &str` - The name of the root tag, usually empty.
+    /// * `value: V` - The NBT compound to be written, either a `NbtValue` or a `&NbtValue`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the NBT value is successfully written.
+    /// * `Err(NbtError)` - If there is an error while writing the NBT value.
+    pub fn write_root<V: Borrow<NbtValue>>(
+        &mut self,
+        name: &str,
+        value: V,
+    ) -> Result<(), NbtError> {
+        self.write_byte(0x0A)?;
+        self.write_string(name)?;
+        self.write_nbt_value(value.borrow())
     }
 
     fn write_byte(&mut self, value: i8) -> Result<(), NbtError> {
@@ -232,60 +296,72 @@ impl<W: Write> NbtWriter<W> {
         Ok(())
     }
 
-    fn write_byte_array(&mut self, value: Vec<i8>) -> Result<(), NbtError> {
+    fn write_byte_array(&mut self, value: &[i8]) -> Result<(), NbtError> {
         self.write_int(value.len() as i32)?;
-        for byte in value {
-            self.write_byte(byte)?;
-        }
-        Ok(())
+        self.write_array(value, |item| [*item as u8])
     }
 
-    fn write_string(&mut self, value: String) -> Result<(), NbtError> {
-        self.write_short(value.len() as i16)?;
+    fn write_string(&mut self, value: &str) -> Result<(), NbtError> {
+        if value.len() > MAX_STRING_LENGTH {
+            return Err(NbtError::StringTooLong(value.len()));
+        }
+        self.write_short(value.len() as u16 as i16)?;
         self.writer.write_all(value.as_bytes())?;
         Ok(())
     }
 
-    fn write_list(&mut self, value: Vec<NbtValue>) -> Result<(), NbtError> {
-        if let Some(first_value) = value.first() {
-            let tag = first_value.to_binary();
-            self.write_byte(tag as i8)?;
-            self.write_int(value.len() as i32)?;
-            for item in &value {
-                if item.to_binary() != tag {
-                    return Err(NbtError::InvalidListType(item.to_binary()));
-                }
-                self.write_nbt_value(item.clone())?;
+    fn write_list(&mut self, value: &NbtList) -> Result<(), NbtError> {
+        let tag = value.element_type();
+        self.write_byte(tag as i8)?;
+        self.write_int(value.len() as i32)?;
+        for item in value {
+            if item.to_binary() != tag {
+                return Err(NbtError::InvalidListType(item.to_binary()));
             }
-        } else {
-            self.write_byte(0)?;
-            self.write_int(0)?;
+            self.write_nbt_value(item)?;
         }
         Ok(())
     }
 
-    fn write_compound(&mut self, value: HashMap<String, NbtValue>) -> Result<(), NbtError> {
+    fn write_compound(&mut self, value: &HashMap<String, NbtValue>) -> Result<(), NbtError> {
         for (name, value) in value {
             self.write_byte(value.to_binary() as i8)?;
             self.write_string(name)?;
             self.write_nbt_value(value)?;
         }
-        self.write_nbt_value(NbtValue::End)?;
+        self.write_byte(0x00)?;
         Ok(())
     }
 
-    fn write_int_array(&mut self, value: Vec<i32>) -> Result<(), NbtError> {
+    fn write_int_array(&mut self, value: &[i32]) -> Result<(), NbtError> {
         self.write_int(value.len() as i32)?;
-        for item in value {
-            self.write_int(item)?;
+        match self.endian {
+            Endian::Big => self.write_array(value, |item| item.to_be_bytes()),
+            Endian::Little => self.write_array(value, |item| item.to_le_bytes()),
         }
-        Ok(())
     }
 
-    fn write_long_array(&mut self, value: Vec<i64>) -> Result<(), NbtError> {
+    fn write_long_array(&mut self, value: &[i64]) -> Result<(), NbtError> {
         self.write_int(value.len() as i32)?;
-        for item in value {
-            self.write_long(item)?;
+        match self.endian {
+            Endian::Big => self.write_array(value, |item| item.to_be_bytes()),
+            Endian::Little => self.write_array(value, |item| item.to_le_bytes()),
+        }
+    }
+
+    /// Encodes array elements into a fixed stack buffer and writes it in bulk, instead of issuing one
+    /// write per element.
+    fn write_array<T, const N: usize>(
+        &mut self,
+        value: &[T],
+        encode: impl Fn(&T) -> [u8; N],
+    ) -> Result<(), NbtError> {
+        let mut buffer = [0u8; WRITE_BUFFER_SIZE];
+        for chunk in value.chunks(WRITE_BUFFER_SIZE / N) {
+            for (index, item) in chunk.iter().enumerate() {
+                buffer[index * N..(index + 1) * N].copy_from_slice(&encode(item));
+            }
+            self.writer.write_all(&buffer[..chunk.len() * N])?;
         }
         Ok(())
     }
@@ -297,7 +373,7 @@ impl<W: Write> NbtWriter<W> {
 /// # Arguments
 ///
 /// * `data_name: Option<&str>` - Optional name for the root tag of the NBT data.
-/// * `value: NbtValue` - The NBT value to write.
+/// * `value: V` - The NBT value to write, either a `NbtValue` or a `&NbtValue`.
 /// * `path: PathBuf` - The path to the file to write to.
 /// * `compression: Compression` - The compression method to use.
 /// * `endian: Endian` - The byte order to use.
@@ -313,40 +389,77 @@ impl<W: Write> NbtWriter<W> {
 ///
 /// let path = PathBuf::from("./tests/data/test.dat");
 ///
-/// write_to_file(None, value, path, Compression::Uncompressed, Endian::Little).unwrap();
+/// write_to_file(None, &value, path, Compression::Uncompressed, Endian::Little).unwrap();
 /// ```
 ///
 /// # Returns
 ///
 /// * `Ok(())` - If the NBT value is successfully written to the file.
 /// * `Err(NbtError)` - If there is an error while writing the NBT value to the file.
-pub fn write_to_file(
+pub fn write_to_file<V: Borrow<NbtValue>>(
     data_name: Option<&str>,
-    value: NbtValue,
+    value: V,
     path: PathBuf,
     compression: Compression,
     endian: Endian,
 ) -> Result<(), NbtError> {
-    let file = std::fs::File::create(path)?;
-
-    match compression {
-        Compression::Uncompressed => {
-            let mut writer = NbtWriter::new(file, endian);
-            writer.write_data(data_name, value)?;
-        }
-        Compression::Gzip => {
-            let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
-            let mut writer = NbtWriter::new(&mut encoder, endian);
-            writer.write_data(data_name, value)?;
-        }
-        Compression::Zlib => {
-            let mut encoder = flate2::write::ZlibEncoder::new(file, flate2::Compression::default());
-            let mut writer = NbtWriter::new(&mut encoder, endian);
-            writer.write_data(data_name, value)?;
-        }
-    }
+    write_to_file_with_level(
+        data_name,
+        value,
+        path,
+        compression,
+        endian,
+        DEFAULT_COMPRESSION_LEVEL,
+    )
+}
 
-    Ok(())
+/// Writes an NBT file to the requested path like `write_to_file`, using the given compression level.
+///
+/// The value is checked with `NbtValue::validate` before the file is created.
+///
+/// # Arguments
+///
+/// * `data_name: Option<&str>` - Optional name for the root tag of the NBT data.
+/// * `value: V` - The NBT value to write, either a `NbtValue` or a `&NbtValue`.
+/// * `path: PathBuf` - The path to the file to write to.
+/// * `compression: Compression` - The compression method to use.
+/// * `endian: Endian` - The byte order to use.
+/// * `level: u32` - The compression level, from 0 (fastest) to 9 (smallest). Only gzip and zlib use it.
+///
+/// # Example
+///
+/// ```
+/// use commandblock::nbt::{NbtValue, write_to_file_with_level, Compression, Endian};
+/// use std::path::PathBuf;
+///
+/// let mut value = NbtValue::new();
+/// value.insert("test".to_string(), "test string");
+///
+/// let path = PathBuf::from("./tests/data/test.dat");
+///
+/// write_to_file_with_level(None, &value, path, Compression::Gzip, Endian::Big, 9).unwrap();
+/// ```
+///
+/// # Returns
+///
+/// * `Ok(())` - If the NBT value is successfully written to the file.
+/// * `Err(NbtError)` - If there is an error while writing the NBT value to the file.
+pub fn write_to_file_with_level<V: Borrow<NbtValue>>(
+    data_name: Option<&str>,
+    value: V,
+    path: PathBuf,
+    compression: Compression,
+    endian: Endian,
+    level: u32,
+) -> Result<(), NbtError> {
+    write_checked(
+        data_name,
+        value.borrow(),
+        || Ok(std::fs::File::create(path)?),
+        compression,
+        endian,
+        level,
+    )
 }
 
 /// Writes an NBT data to the given writer using the given compression and endian style.
@@ -354,7 +467,7 @@ pub fn write_to_file(
 /// # Arguments
 ///
 /// * `data_name: Option<&str>` - Optional name for the root tag of the NBT data.
-/// * `value: NbtValue` - The NBT value to write.
+/// * `value: V` - The NBT value to write, either a `NbtValue` or a `&NbtValue`.
 /// * `writer: W` - The writer to write to.
 /// * `compression: Compression` - The compression method to use.
 /// * `endian: Endian` - The byte order to use.
@@ -370,37 +483,122 @@ pub fn write_to_file(
 ///
 /// let mut writer = Cursor::new(Vec::new());
 ///
-/// write_to_writer(None, value, &mut writer, Compression::Uncompressed, Endian::Little).unwrap();
+/// write_to_writer(None, &value, &mut writer, Compression::Uncompressed, Endian::Little).unwrap();
 /// ```
 ///
 /// # Returns
 ///
 /// * `Ok(())` - If the NBT value is successfully written to the writer.
 /// * `Err(NbtError)` - If there is an error while writing the NBT value to the writer.
-pub fn write_to_writer<W: Write>(
+pub fn write_to_writer<V: Borrow<NbtValue>, W: Write>(
     data_name: Option<&str>,
-    value: NbtValue,
+    value: V,
     writer: &mut W,
     compression: Compression,
     endian: Endian,
 ) -> Result<(), NbtError> {
-    match compression {
-        Compression::Uncompressed => {
-            let mut nbt_writer = NbtWriter::new(writer, endian);
-            nbt_writer.write_data(data_name, value)?;
-        }
-        Compression::Gzip => {
-            let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
-            let mut nbt_writer = NbtWriter::new(&mut encoder, endian);
-            nbt_writer.write_data(data_name, value)?;
-        }
-        Compression::Zlib => {
-            let mut encoder =
-                flate2::write::ZlibEncoder::new(writer, flate2::Compression::default());
-            let mut nbt_writer = NbtWriter::new(&mut encoder, endian);
-            nbt_writer.write_data(data_name, value)?;
-        }
-    }
+    write_to_writer_with_level(
+        data_name,
+        value,
+        writer,
+        compression,
+        endian,
+        DEFAULT_COMPRESSION_LEVEL,
+    )
+}
+
+/// Writes an NBT data to the given writer like `write_to_writer`, using the given compression level.
+///
+/// The value is checked with `NbtValue::validate` before anything is written.
+///
+/// # Arguments
+///
+/// * `data_name: Option<&str>` - Optional name for the root tag of the NBT data.
+/// * `value: V` - The NBT value to write, either a `NbtValue` or a `&NbtValue`.
+/// * `writer: W` - The writer to write to.
+/// * `compression: Compression` - The compression method to use.
+/// * `endian: Endian` - The byte order to use.
+/// * `level: u32` - The compression level, from 0 (fastest) to 9 (smallest). Only gzip and zlib use it.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the NBT value is successfully written to the writer.
+/// * `Err(NbtError)` - If there is an error while writing the NBT value to the writer.
+pub fn write_to_writer_with_level<V: Borrow<NbtValue>, W: Write>(
+    data_name: Option<&str>,
+    value: V,
+    writer: &mut W,
+    compression: Compression,
+    endian: Endian,
+    level: u32,
+) -> Result<(), NbtError> {
+    write_checked(
+        data_name,
+        value.borrow(),
+        || Ok(writer),
+        compression,
+        endian,
+        level,
+    )
+}
+
+/// Checks the value with `NbtValue::validate`, then opens the destination and writes the value to it, so no file
+/// is created for a value that cannot be written.
+fn write_checked<W: Write>(
+    data_name: Option<&str>,
+    value: &NbtValue,
+    open: impl FnOnce() -> Result<W, NbtError>,
+    compression: Compression,
+    endian: Endian,
+    level: u32,
+) -> Result<(), NbtError> {
+    value.validate()?;
+
+    encode(open()?, compression, level, |writer| {
+        NbtWriter::new(writer, endian).write_data(data_name, value)
+    })
+}
+
+/// Writes NBT data as a region file chunk: a big endian length, the compression type id, then the compressed
+/// data. The caller is responsible for the chunk's place in the region file and its padding to whole sectors.
+///
+/// # Arguments
+///
+/// * `value: V` - The chunk NBT value to write, either a `NbtValue` or a `&NbtValue`.
+/// * `writer: W` - The writer to write to.
+/// * `compression: Compression` - The compression method to use.
+/// * `level: u32` - The compression level, from 0 (fastest) to 9 (smallest). Only gzip and zlib use it.
+///
+/// # Example
+///
+/// ```
+/// use commandblock::nbt::{NbtValue, read_chunk, write_chunk, Compression, DEFAULT_COMPRESSION_LEVEL};
+///
+/// let mut value = NbtValue::new();
+/// value.insert("DataVersion".to_string(), 3465);
+///
+/// let mut chunk = Vec::new();
+/// write_chunk(&value, &mut chunk, Compression::Zlib, DEFAULT_COMPRESSION_LEVEL).unwrap();
+///
+/// assert_eq!(chunk[4], 2);
+/// assert_eq!(read_chunk(&chunk[..]).unwrap(), (value, Compression::Zlib));
+/// ```
+///
+/// # Returns
+///
+/// * `Ok(())` - If the chunk is successfully written to the writer.
+/// * `Err(NbtError)` - If there is an error while writing the chunk to the writer.
+pub fn write_chunk<V: Borrow<NbtValue>, W: Write>(
+    value: V,
+    writer: &mut W,
+    compression: Compression,
+    level: u32,
+) -> Result<(), NbtError> {
+    let mut data = Vec::new();
+    write_to_writer_with_level(Some(""), value, &mut data, compression, Endian::Big, level)?;
 
+    writer.write_u32::<BigEndian>(data.len() as u32 + 1)?;
+    writer.write_u8(compression.region_id())?;
+    writer.write_all(&data)?;
     Ok(())
 }
@@ -0,0 +1,326 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::nbt::{
+    fields::{from_nbt_field, list_of, Fields},
+    read_from_file, write_to_file, Compression, Endian, NbtError, NbtPathNode, NbtReader, NbtValue,
+    NbtWriter,
+};
+
+use super::item::ItemStack;
+
+/// `Edition` is the edition of Minecraft a file was written by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edition {
+    /// Java Edition, which writes big endian NBT.
+    Java,
+    /// Bedrock Edition, which writes little endian NBT.
+    Bedrock,
+}
+
+/// `EffectId` identifies a status effect: by number in Bedrock and Java before 1.20.2, and by name since.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EffectId {
+    /// A numeric id, such as 1 for speed, stored with capitalized keys like `Amplifier`.
+    Numeric(i8),
+    /// A named id, such as `minecraft:speed`, stored with snake case keys like `amplifier`.
+    Named(String),
+}
+
+/// `Effect` is a status effect active on a player or mob.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Effect {
+    pub id: EffectId,
+    /// The level of the effect minus one, so 0 is level I.
+    pub amplifier: i8,
+    /// The number of ticks left, or -1 for an infinite effect.
+    pub duration: i32,
+    /// Whether the effect comes from a beacon or conduit, which makes its particles fainter.
+    pub ambient: bool,
+    pub show_particles: bool,
+    /// The fields not covered above, such as `show_icon`.
+    pub other: HashMap<String, NbtValue>,
+}
+
+impl Effect {
+    /// The keys of the amplifier, duration, ambient and particles fields for each kind of id.
+    const NAMED_KEYS: [&'static str; 4] = ["amplifier", "duration", "ambient", "show_particles"];
+    const NUMERIC_KEYS: [&'static str; 4] = ["Amplifier", "Duration", "Ambient", "ShowParticles"];
+
+    /// Reads an effect from its compound, in either of the shapes described by `EffectId`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Effect)` - The effect.
+    /// * `Err(NbtError)` - `PathNotFound` if a field is missing, or `TypeMismatch` if one has the wrong type.
+    pub fn from_nbt(value: NbtValue) -> Result<Effect, NbtError> {
+        let mut fields = Fields::new(value)?;
+        let (id, [amplifier, duration, ambient, show_particles]) = match fields.optional("id")? {
+            Some(name) => (EffectId::Named(name), Effect::NAMED_KEYS),
+            None => (
+                EffectId::Numeric(fields.required("Id")?),
+                Effect::NUMERIC_KEYS,
+            ),
+        };
+
+        Ok(Effect {
+            id,
+            amplifier: fields.optional(amplifier)?.unwrap_or(0),
+            duration: fields.required(duration)?,
+            ambient: fields.optional(ambient)?.unwrap_or(false),
+            show_particles: fields.optional(show_particles)?.unwrap_or(true),
+            other: fields.into_rest(),
+        })
+    }
+
+    /// Converts the effect back into a compound, with the keys its id kind uses.
+    pub fn to_nbt(&self) -> NbtValue {
+        let mut map = self.other.clone();
+        let [amplifier, duration, ambient, show_particles] = match &self.id {
+            EffectId::Named(name) => {
+                map.insert("id".to_string(), name.as_str().into());
+                Effect::NAMED_KEYS
+            }
+            EffectId::Numeric(id) => {
+                map.insert("Id".to_string(), (*id).into());
+                Effect::NUMERIC_KEYS
+            }
+        };
+        map.insert(amplifier.to_string(), self.amplifier.into());
+        map.insert(duration.to_string(), self.duration.into());
+        map.insert(ambient.to_string(), self.ambient.into());
+        map.insert(show_particles.to_string(), self.show_particles.into());
+        NbtValue::Compound(map)
+    }
+}
+
+from_nbt_field!(Effect);
+
+/// The keys a field is stored under in each edition.
+struct Keys {
+    xp_level: &'static str,
+    xp_progress: &'static str,
+    ender_chest: &'static str,
+}
+
+const JAVA_KEYS: Keys = Keys {
+    xp_level: "XpLevel",
+    xp_progress: "XpP",
+    ender_chest: "EnderItems",
+};
+
+const BEDROCK_KEYS: Keys = Keys {
+    xp_level: "PlayerLevel",
+    xp_progress: "PlayerLevelProgress",
+    ender_chest: "EnderChestInventory",
+};
+
+/// `PlayerData` is the saved state of a player, as stored in a Java world's `playerdata/<uuid>.dat` files and
+/// the `Player` compound of its `level.dat`, or under a Bedrock world's `~local_player` and `player_` keys.
+///
+/// Fields this struct does not know, such as health or the `Armor` and `Offhand` lists of Bedrock players, are
+/// kept in `other` and written back unchanged. Java stores armor and the offhand in `inventory`, in slots 100
+/// to 103 and -106.
+///
+/// # Examples
+///
+/// ```no_run
+/// use commandblock::player::{ItemFormat, ItemStack, PlayerData};
+/// use std::path::PathBuf;
+///
+/// let path = PathBuf::from("./world/playerdata/1f0a8ac4-6647-4cad-a500-ba8bae1d4bb1.dat");
+/// let mut player = PlayerData::read_from_file(path.clone()).unwrap();
+///
+/// let mut item = ItemStack::new(ItemFormat::Java, "minecraft:diamond", 64);
+/// item.slot = Some(8);
+/// player.inventory.push(item);
+/// player.write_to_file(path).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerData {
+    pub edition: Edition,
+    /// The position of the player. Bedrock stores the position of the player's eyes, 1.62 above their feet.
+    pub position: [f64; 3],
+    /// The yaw and pitch of the player, in degrees.
+    pub rotation: [f32; 2],
+    pub xp_level: i32,
+    /// The progress towards the next level, from 0 to 1.
+    pub xp_progress: f32,
+    /// The total experience points collected, which only Java stores.
+    pub xp_total: Option<i32>,
+    pub inventory: Vec<ItemStack>,
+    pub ender_chest: Vec<ItemStack>,
+    pub effects: Vec<Effect>,
+    /// The fields not covered above.
+    pub other: HashMap<String, NbtValue>,
+}
+
+impl PlayerData {
+    fn keys(edition: Edition) -> &'static Keys {
+        match edition {
+            Edition::Java => &JAVA_KEYS,
+            Edition::Bedrock => &BEDROCK_KEYS,
+        }
+    }
+
+    /// Reads a player from their compound.
+    ///
+    /// # Arguments
+    ///
+    /// * `value: NbtValue` - The player compound.
+    /// * `edition: Edition` - The edition the compound was written by.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PlayerData)` - The player.
+    /// * `Err(NbtError)` - `PathNotFound` if a field such as `Pos` is missing, `TypeMismatch` if a field has the
+    ///   wrong type, or `InvalidListLength` if `Pos` or `Rotation` has the wrong length.
+    pub fn from_nbt(value: NbtValue, edition: Edition) -> Result<PlayerData, NbtError> {
+        let keys = PlayerData::keys(edition);
+        let mut fields = Fields::new(value)?;
+
+        let position = match edition {
+            Edition::Java => fields.required("Pos")?,
+            Edition::Bedrock => fields
+                .required::<Vec<f32>>("Pos")?
+                .into_iter()
+                .map(f64::from)
+                .collect(),
+        };
+        let position = fixed_length("Pos", position)?;
+        let rotation = fixed_length("Rotation", fields.required("Rotation")?)?;
+
+        let effects = match fields.optional("active_effects")? {
+            Some(effects) => effects,
+            None => fields.optional("ActiveEffects")?.unwrap_or_default(),
+        };
+
+        Ok(PlayerData {
+            edition,
+            position,
+            rotation,
+            xp_level: fields.required(keys.xp_level)?,
+            xp_progress: fields.required(keys.xp_progress)?,
+            xp_total: match edition {
+                Edition::Java => fields.optional("XpTotal")?,
+                Edition::Bedrock => None,
+            },
+            inventory: fields.optional("Inventory")?.unwrap_or_default(),
+            ender_chest: fields.optional(keys.ender_chest)?.unwrap_or_default(),
+            effects,
+            other: fields.into_rest(),
+        })
+    }
+
+    /// Converts the player back into a compound in the shape of their edition.
+    pub fn to_nbt(&self) -> NbtValue {
+        let keys = PlayerData::keys(self.edition);
+        let mut map = self.other.clone();
+
+        let position = match self.edition {
+            Edition::Java => list_of(0x06, self.position),
+            Edition::Bedrock => list_of(0x05, self.position.map(|value| value as f32)),
+        };
+        map.insert("Pos".to_string(), position);
+        map.insert("Rotation".to_string(), list_of(0x05, self.rotation));
+        map.insert(keys.xp_level.to_string(), self.xp_level.into());
+        map.insert(keys.xp_progress.to_string(), self.xp_progress.into());
+        if let Some(xp_total) = self.xp_total {
+            map.insert("XpTotal".to_string(), xp_total.into());
+        }
+
+        // Java writes empty lists as lists of End tags.
+        let empty_type = match self.edition {
+            Edition::Java => 0x00,
+            Edition::Bedrock => 0x0A,
+        };
+        map.insert(
+            "Inventory".to_string(),
+            list_of(empty_type, self.inventory.iter().map(ItemStack::to_nbt)),
+        );
+        map.insert(
+            keys.ender_chest.to_string(),
+            list_of(empty_type, self.ender_chest.iter().map(ItemStack::to_nbt)),
+        );
+
+        // The game leaves the effects list out when no effect is active.
+        if !self.effects.is_empty() {
+            let key = match self.effects[0].id {
+                EffectId::Named(_) => "active_effects",
+                EffectId::Numeric(_) => "ActiveEffects",
+            };
+            map.insert(
+                key.to_string(),
+                list_of(0x0A, self.effects.iter().map(Effect::to_nbt)),
+            );
+        }
+        NbtValue::Compound(map)
+    }
+
+    /// Reads a Java player from a gzip compressed `playerdata/<uuid>.dat` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: PathBuf` - The path to the player file.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PlayerData)` - The player.
+    /// * `Err(NbtError)` - If the file could not be read or a field is missing or has the wrong type.
+    pub fn read_from_file(path: PathBuf) -> Result<PlayerData, NbtError> {
+        let (_, value) = read_from_file(path, Compression::Gzip, Endian::Big)?;
+        PlayerData::from_nbt(value, Edition::Java)
+    }
+
+    /// Writes a Java player to a gzip compressed `playerdata/<uuid>.dat` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path: PathBuf` - The path to write to. An existing file is overwritten.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the file was written.
+    /// * `Err(NbtError)` - If the file could not be written.
+    pub fn write_to_file(&self, path: PathBuf) -> Result<(), NbtError> {
+        write_to_file(
+            Some(""),
+            self.to_nbt(),
+            path,
+            Compression::Gzip,
+            Endian::Big,
+        )
+    }
+
+    /// Parses a Bedrock player record, the value stored under a `~local_player` or `player_` key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PlayerData)` - The player.
+    /// * `Err(NbtError)` - If the record is not a compound or a field is missing or has the wrong type.
+    pub fn from_bedrock_record(data: &[u8]) -> Result<PlayerData, NbtError> {
+        let (_, value) = NbtReader::new(data, Endian::Little).parse_root()?;
+        PlayerData::from_nbt(value, Edition::Bedrock)
+    }
+
+    /// Encodes the player as a Bedrock player record, to be stored under its key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<u8>)` - The encoded record.
+    /// * `Err(NbtError)` - If a field fails `NbtValue::validate`.
+    pub fn to_bedrock_record(&self) -> Result<Vec<u8>, NbtError> {
+        let value = self.to_nbt();
+        value.validate()?;
+
+        let mut bytes = Vec::new();
+        NbtWriter::new(&mut bytes, Endian::Little).write_root("", &value)?;
+        Ok(bytes)
+    }
+}
+
+/// Converts a list that must have exactly `N` elements, such as a position, into an array.
+fn fixed_length<T, const N: usize>(key: &str, values: Vec<T>) -> Result<[T; N], NbtError> {
+    <[T; N]>::try_from(values).map_err(|values| {
+        NbtError::InvalidListLength(values.len()).within(NbtPathNode::Key(key.to_string()), None)
+    })
+}
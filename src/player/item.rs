@@ -0,0 +1,760 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::nbt::{
+    fields::{from_nbt_field, list_of, Fields},
+    NbtError, NbtValue,
+};
+
+const CUSTOM_NAME: &str = "minecraft:custom_name";
+const LORE: &str = "minecraft:lore";
+const ENCHANTMENTS: &str = "minecraft:enchantments";
+const DAMAGE: &str = "minecraft:damage";
+const CUSTOM_DATA: &str = "minecraft:custom_data";
+const UNBREAKABLE: &str = "minecraft:unbreakable";
+const DYED_COLOR: &str = "minecraft:dyed_color";
+const MAP_COLOR: &str = "minecraft:map_color";
+const STORED_ENCHANTMENTS: &str = "minecraft:stored_enchantments";
+const ATTRIBUTE_MODIFIERS: &str = "minecraft:attribute_modifiers";
+const HIDE_ADDITIONAL_TOOLTIP: &str = "minecraft:hide_additional_tooltip";
+
+/// Tag fields that hold the same value as the component they become.
+const SCALAR_FIELDS: [(&str, &str); 3] = [
+    ("Damage", DAMAGE),
+    ("RepairCost", "minecraft:repair_cost"),
+    ("CustomModelData", "minecraft:custom_model_data"),
+];
+
+/// Tag fields of vanilla items whose components the conversion does not build. The game ignores
+/// `minecraft:custom_data`, so these are refused rather than kept there.
+const UNCONVERTED_FIELDS: [&str; 33] = [
+    "AttributeModifiers",
+    "CanDestroy",
+    "CanPlaceOn",
+    "Trim",
+    "BlockEntityTag",
+    "BlockStateTag",
+    "EntityTag",
+    "BucketVariantTag",
+    "SkullOwner",
+    "pages",
+    "filtered_pages",
+    "title",
+    "filtered_title",
+    "author",
+    "generation",
+    "resolved",
+    "Potion",
+    "CustomPotionEffects",
+    "CustomPotionColor",
+    "effects",
+    "Fireworks",
+    "Explosion",
+    "ChargedProjectiles",
+    "Charged",
+    "Items",
+    "map",
+    "Decorations",
+    "LodestoneTracked",
+    "LodestonePos",
+    "LodestoneDimension",
+    "Recipes",
+    "instrument",
+    "DebugProperty",
+];
+
+/// The `HideFlags` bits that hide the tooltip lines of the enchantments, attribute modifiers, unbreakable flag,
+/// additional information such as stored enchantments, and dye color.
+const HIDE_ENCHANTMENTS: i32 = 1;
+const HIDE_MODIFIERS: i32 = 2;
+const HIDE_UNBREAKABLE: i32 = 4;
+const HIDE_ADDITIONAL: i32 = 32;
+const HIDE_DYE: i32 = 64;
+const CONVERTED_HIDE_FLAGS: i32 =
+    HIDE_ENCHANTMENTS | HIDE_MODIFIERS | HIDE_UNBREAKABLE | HIDE_ADDITIONAL | HIDE_DYE;
+
+/// `ItemFormat` is the shape an item stack is stored in, which differs between editions and Java versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemFormat {
+    /// Java Edition before 1.20.5: `id`, a byte `Count` and an optional `tag` compound.
+    Java,
+    /// Java Edition since 1.20.5: `id`, an int `count` and an optional `components` compound.
+    Components,
+    /// Bedrock Edition: `Name`, a byte `Count`, a `Damage` data value and an optional `tag` compound.
+    Bedrock,
+}
+
+/// Returns the number stored in a byte, short or int.
+fn as_int(value: &NbtValue) -> Option<i32> {
+    match *value {
+        NbtValue::Byte(value) => Some(i32::from(value)),
+        NbtValue::Short(value) => Some(i32::from(value)),
+        NbtValue::Int(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn as_str(value: &NbtValue) -> Option<&str> {
+    match value {
+        NbtValue::String(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// Converts a `tag` style enchantment list into enchantment levels by id.
+fn enchantment_levels(list: &NbtValue) -> BTreeMap<String, i32> {
+    let Some(list) = list.as_list() else {
+        return BTreeMap::new();
+    };
+    list.iter()
+        .filter_map(|entry| {
+            let entry = entry.as_compound()?;
+            let id = match entry.get("id")? {
+                NbtValue::String(id) => id.clone(),
+                id => as_int(id)?.to_string(),
+            };
+            Some((id, as_int(entry.get("lvl")?)?))
+        })
+        .collect()
+}
+
+/// Converts an enchantments component, with or without its `levels` compound, into enchantment levels by id.
+fn component_levels(component: &NbtValue) -> BTreeMap<String, i32> {
+    let Some(enchantments) = component.as_compound() else {
+        return BTreeMap::new();
+    };
+    // Before 1.21.5 the levels are in a `levels` compound, since they are the component itself.
+    let levels = match enchantments.get("levels") {
+        Some(NbtValue::Compound(levels)) => levels,
+        _ => enchantments,
+    };
+    levels
+        .iter()
+        .filter_map(|(id, level)| Some((id.clone(), as_int(level)?)))
+        .collect()
+}
+
+/// Creates an enchantments component in the shape used from 1.20.5 to 1.21.4.
+fn levels_component(levels: &BTreeMap<String, i32>, hidden: bool) -> NbtValue {
+    let levels: HashMap<String, NbtValue> = levels
+        .iter()
+        .map(|(id, &level)| (id.clone(), level.into()))
+        .collect();
+    let mut component = NbtValue::new();
+    component.insert("levels".to_string(), levels);
+    with_tooltip(component, hidden)
+}
+
+/// Creates a Java `tag` style enchantment list.
+fn enchantment_list(levels: &BTreeMap<String, i32>) -> NbtValue {
+    let list = levels.iter().map(|(id, &level)| {
+        let mut entry = NbtValue::new();
+        entry.insert("id".to_string(), id.as_str());
+        entry.insert("lvl".to_string(), level as i16);
+        entry
+    });
+    list_of(0x0A, list)
+}
+
+/// Adds `show_in_tooltip: false` to a component compound if its tooltip line is hidden.
+fn with_tooltip(mut component: NbtValue, hidden: bool) -> NbtValue {
+    if hidden {
+        component.insert("show_in_tooltip".to_string(), false);
+    }
+    component
+}
+
+/// Returns whether a component compound hides its tooltip line.
+fn is_hidden(component: &NbtValue) -> bool {
+    component
+        .as_compound()
+        .and_then(|component| component.get("show_in_tooltip"))
+        .and_then(as_int)
+        == Some(0)
+}
+
+fn unsupported(message: &str) -> NbtError {
+    NbtError::UnsupportedConversion(message.to_string())
+}
+
+/// `ItemStack` is a stack of items in an inventory or container, in any of the formats of `ItemFormat`.
+///
+/// Fields this struct does not know, such as the `Damage` data value and `WasPickedUp` flag of Bedrock items,
+/// are kept in `other` and written back unchanged. The custom name, lore, enchantments and damage are read from
+/// the `tag` or `components` compound, whichever the format uses.
+///
+/// # Examples
+///
+/// ```
+/// use commandblock::player::{ItemFormat, ItemStack};
+///
+/// let mut item = ItemStack::new(ItemFormat::Java, "minecraft:diamond_sword", 1);
+/// item.set_custom_name(Some(r#"{"text":"Excalibur"}"#));
+/// item.set_enchantment("minecraft:sharpness", 5).unwrap();
+///
+/// let item = item.into_components().unwrap();
+/// assert_eq!(item.format, ItemFormat::Components);
+/// assert_eq!(item.custom_name(), Some(r#"{"text":"Excalibur"}"#));
+/// assert_eq!(item.enchantments()["minecraft:sharpness"], 5);
+///
+/// let value = item.to_nbt();
+/// assert_eq!(value.get("count"), Some(&1.into()));
+/// assert_eq!(ItemStack::from_nbt(value).unwrap(), item);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack {
+    pub format: ItemFormat,
+    /// The item id, such as `minecraft:diamond`. Empty Bedrock inventory slots have an empty id.
+    pub id: String,
+    /// The number of items. The `Java` and `Bedrock` formats store it as a byte, so counts above 127 are written
+    /// as 127.
+    pub count: i32,
+    /// The inventory slot of the stack, for items in an inventory or container.
+    pub slot: Option<i8>,
+    /// The `tag` compound of the `Java` and `Bedrock` formats.
+    pub tag: Option<HashMap<String, NbtValue>>,
+    /// The `components` compound of the `Components` format, by component id such as `minecraft:damage`.
+    pub components: Option<HashMap<String, NbtValue>>,
+    /// The fields not covered above.
+    pub other: HashMap<String, NbtValue>,
+}
+
+impl ItemStack {
+    /// Creates a stack of `count` items with the given id, in no slot and without a tag or components.
+    pub fn new(format: ItemFormat, id: &str, count: i32) -> Self {
+        ItemStack {
+            format,
+            id: id.to_string(),
+            count,
+            slot: None,
+            tag: None,
+            components: None,
+            other: HashMap::new(),
+        }
+    }
+
+    /// Reads an item stack from its compound, detecting its format: Bedrock items have a `Name`, Java items
+    /// from before 1.20.5 a byte `Count`, and any other item is read in the `Components` format.
+    ///
+    /// # Arguments
+    ///
+    /// * `value: NbtValue` - The item compound, such as an entry of a player's `Inventory` list.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ItemStack)` - The item stack.
+    /// * `Err(NbtError)` - `PathNotFound` if the compound has no id, or `TypeMismatch` if a field has the wrong
+    ///   type.
+    pub fn from_nbt(value: NbtValue) -> Result<ItemStack, NbtError> {
+        let mut fields = Fields::new(value)?;
+        let format = if fields.contains("Name") {
+            ItemFormat::Bedrock
+        } else if fields.contains("Count") {
+            ItemFormat::Java
+        } else {
+            ItemFormat::Components
+        };
+
+        let id = match format {
+            ItemFormat::Bedrock => fields.required("Name")?,
+            ItemFormat::Java | ItemFormat::Components => fields.required("id")?,
+        };
+        let count = match format {
+            // A missing count means a single item.
+            ItemFormat::Components => fields.optional("count")?.unwrap_or(1),
+            ItemFormat::Java | ItemFormat::Bedrock => i32::from(fields.required::<i8>("Count")?),
+        };
+        let slot = fields.optional("Slot")?;
+        let (tag, components) = match format {
+            ItemFormat::Components => (None, fields.optional("components")?),
+            ItemFormat::Java | ItemFormat::Bedrock => (fields.optional("tag")?, None),
+        };
+
+        Ok(ItemStack {
+            format,
+            id,
+            count,
+            slot,
+            tag,
+            components,
+            other: fields.into_rest(),
+        })
+    }
+
+    /// Converts the item stack back into a compound in its format.
+    pub fn to_nbt(&self) -> NbtValue {
+        let mut map = self.other.clone();
+        match self.format {
+            ItemFormat::Java | ItemFormat::Bedrock => {
+                let id_key = match self.format {
+                    ItemFormat::Bedrock => "Name",
+                    _ => "id",
+                };
+                map.insert(id_key.to_string(), self.id.as_str().into());
+                map.insert(
+                    "Count".to_string(),
+                    (self.count.clamp(0, i32::from(i8::MAX)) as i8).into(),
+                );
+                if let Some(tag) = &self.tag {
+                    map.insert("tag".to_string(), tag.clone().into());
+                }
+            }
+            ItemFormat::Components => {
+                map.insert("id".to_string(), self.id.as_str().into());
+                map.insert("count".to_string(), self.count.into());
+                if let Some(components) = &self.components {
+                    map.insert("components".to_string(), components.clone().into());
+                }
+            }
+        }
+        if let Some(slot) = self.slot {
+            map.insert("Slot".to_string(), slot.into());
+        }
+        NbtValue::Compound(map)
+    }
+
+    /// Returns whether the stack is empty, as the empty slots Bedrock stores in inventories are.
+    pub fn is_empty(&self) -> bool {
+        self.id.is_empty() || self.id == "minecraft:air" || self.count <= 0
+    }
+
+    fn component(&self, id: &str) -> Option<&NbtValue> {
+        self.components.as_ref()?.get(id)
+    }
+
+    fn set_component(&mut self, id: &str, value: Option<NbtValue>) {
+        match value {
+            Some(value) => {
+                self.components
+                    .get_or_insert_with(HashMap::new)
+                    .insert(id.to_string(), value);
+            }
+            None => {
+                if let Some(components) = &mut self.components {
+                    components.remove(id);
+                }
+            }
+        }
+    }
+
+    fn tag_field(&self, key: &str) -> Option<&NbtValue> {
+        self.tag.as_ref()?.get(key)
+    }
+
+    fn set_tag_field(&mut self, key: &str, value: Option<NbtValue>) {
+        match value {
+            Some(value) => {
+                self.tag
+                    .get_or_insert_with(HashMap::new)
+                    .insert(key.to_string(), value);
+            }
+            None => {
+                if let Some(tag) = &mut self.tag {
+                    tag.remove(key);
+                    if tag.is_empty() {
+                        self.tag = None;
+                    }
+                }
+            }
+        }
+    }
+
+    fn display_field(&self, key: &str) -> Option<&NbtValue> {
+        self.tag_field("display")?.as_compound()?.get(key)
+    }
+
+    fn set_display_field(&mut self, key: &str, value: Option<NbtValue>) {
+        let mut display = match self.tag_field("display") {
+            Some(NbtValue::Compound(display)) => display.clone(),
+            _ => HashMap::new(),
+        };
+        match value {
+            Some(value) => {
+                display.insert(key.to_string(), value);
+            }
+            None => {
+                display.remove(key);
+            }
+        }
+        let display = (!display.is_empty()).then(|| display.into());
+        self.set_tag_field("display", display);
+    }
+
+    /// Returns the custom name of the item. Java stores it as a JSON text component, such as
+    /// `{"text":"Excalibur"}`, and Bedrock as plain text. Names stored as compounds, as Java does since 1.21.5,
+    /// are not returned.
+    pub fn custom_name(&self) -> Option<&str> {
+        match self.format {
+            ItemFormat::Components => self.component(CUSTOM_NAME),
+            ItemFormat::Java | ItemFormat::Bedrock => self.display_field("Name"),
+        }
+        .and_then(as_str)
+    }
+
+    /// Sets the custom name of the item, or removes it if `name` is `None`.
+    pub fn set_custom_name(&mut self, name: Option<&str>) {
+        let name = name.map(NbtValue::from);
+        match self.format {
+            ItemFormat::Components => self.set_component(CUSTOM_NAME, name),
+            ItemFormat::Java | ItemFormat::Bedrock => self.set_display_field("Name", name),
+        }
+    }
+
+    /// Returns the lines of the item's lore, in the same text format as `custom_name`.
+    pub fn lore(&self) -> Vec<String> {
+        let lore = match self.format {
+            ItemFormat::Components => self.component(LORE),
+            ItemFormat::Java | ItemFormat::Bedrock => self.display_field("Lore"),
+        };
+        lore.and_then(NbtValue::as_list)
+            .map(|lines| {
+                lines
+                    .iter()
+                    .filter_map(as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Sets the lines of the item's lore, removing it if `lore` is empty.
+    pub fn set_lore(&mut self, lore: Vec<String>) {
+        let lore = (!lore.is_empty()).then(|| list_of(0x08, lore));
+        match self.format {
+            ItemFormat::Components => self.set_component(LORE, lore),
+            ItemFormat::Java | ItemFormat::Bedrock => self.set_display_field("Lore", lore),
+        }
+    }
+
+    /// Returns the level of each enchantment on the item by enchantment id, such as `minecraft:sharpness`.
+    /// Bedrock stores enchantments by number, which are returned as strings such as `"9"`.
+    pub fn enchantments(&self) -> BTreeMap<String, i32> {
+        match self.format {
+            ItemFormat::Components => self
+                .component(ENCHANTMENTS)
+                .map(component_levels)
+                .unwrap_or_default(),
+            ItemFormat::Java => self
+                .tag_field("Enchantments")
+                .map(enchantment_levels)
+                .unwrap_or_default(),
+            ItemFormat::Bedrock => self
+                .tag_field("ench")
+                .map(enchantment_levels)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Sets the level of an enchantment, or removes it if `level` is 0.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the enchantment was set.
+    /// * `Err(NbtError)` - `InvalidBedrockData` if the item is a Bedrock item and the id is not a number.
+    pub fn set_enchantment(&mut self, id: &str, level: i32) -> Result<(), NbtError> {
+        let mut enchantments = self.enchantments();
+        if level == 0 {
+            enchantments.remove(id);
+        } else {
+            enchantments.insert(id.to_string(), level);
+        }
+        self.set_enchantments(&enchantments)
+    }
+
+    /// Replaces the enchantments of the item, removing them if `enchantments` is empty.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the enchantments were set.
+    /// * `Err(NbtError)` - `InvalidBedrockData` if the item is a Bedrock item and an id is not a number.
+    pub fn set_enchantments(
+        &mut self,
+        enchantments: &BTreeMap<String, i32>,
+    ) -> Result<(), NbtError> {
+        match self.format {
+            ItemFormat::Components => {
+                let levels: HashMap<String, NbtValue> = enchantments
+                    .iter()
+                    .map(|(id, &level)| (id.clone(), level.into()))
+                    .collect();
+                let value = match self.component(ENCHANTMENTS) {
+                    _ if levels.is_empty() => None,
+                    // Keep fields such as `show_in_tooltip` next to the levels.
+                    Some(NbtValue::Compound(existing)) if existing.contains_key("levels") => {
+                        let mut existing = existing.clone();
+                        existing.insert("levels".to_string(), levels.into());
+                        Some(existing.into())
+                    }
+                    Some(NbtValue::Compound(existing)) if !existing.is_empty() => {
+                        Some(levels.into())
+                    }
+                    _ => Some(levels_component(enchantments, false)),
+                };
+                self.set_component(ENCHANTMENTS, value);
+            }
+            ItemFormat::Java => {
+                let list = (!enchantments.is_empty()).then(|| enchantment_list(enchantments));
+                self.set_tag_field("Enchantments", list);
+            }
+            ItemFormat::Bedrock => {
+                let mut list = Vec::new();
+                for (id, &level) in enchantments {
+                    let id: i16 = id.parse().map_err(|_| {
+                        NbtError::InvalidBedrockData(format!(
+                            "enchantment id {} is not a number",
+                            id
+                        ))
+                    })?;
+                    let mut entry = NbtValue::new();
+                    entry.insert("id".to_string(), id);
+                    entry.insert("lvl".to_string(), level as i16);
+                    list.push(entry);
+                }
+                let list = (!list.is_empty()).then(|| list_of(0x0A, list));
+                self.set_tag_field("ench", list);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the damage taken by a tool or armor piece, 0 if it has none.
+    pub fn damage(&self) -> i32 {
+        match self.format {
+            ItemFormat::Components => self.component(DAMAGE),
+            ItemFormat::Java | ItemFormat::Bedrock => self.tag_field("Damage"),
+        }
+        .and_then(as_int)
+        .unwrap_or(0)
+    }
+
+    /// Sets the damage taken by a tool or armor piece, removing it if `damage` is 0.
+    pub fn set_damage(&mut self, damage: i32) {
+        let damage = (damage != 0).then(|| damage.into());
+        match self.format {
+            ItemFormat::Components => self.set_component(DAMAGE, damage),
+            ItemFormat::Java | ItemFormat::Bedrock => self.set_tag_field("Damage", damage),
+        }
+    }
+
+    /// Converts a Java item from its `tag` compound into the components used since 1.20.5, as the game does when
+    /// it loads an older world.
+    ///
+    /// The custom name, lore, enchantments, stored enchantments, damage, repair cost, custom model data, dye and
+    /// map colors and the unbreakable flag become their components, and `HideFlags` hides their tooltip lines.
+    /// Fields vanilla items do not use, such as those added by plugins, are kept in the `minecraft:custom_data`
+    /// component. Items already in the `Components` format are returned unchanged.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ItemStack)` - The item in the `Components` format.
+    /// * `Err(NbtError)` - `UnsupportedConversion` if the item is a Bedrock item, which has no components, or
+    ///   has vanilla fields such as `CanDestroy` or `HideFlags` bits whose components are not built;
+    ///   `TypeMismatch` if a converted field has the wrong type.
+    pub fn into_components(self) -> Result<ItemStack, NbtError> {
+        match self.format {
+            ItemFormat::Components => return Ok(self),
+            ItemFormat::Bedrock => return Err(unsupported("Bedrock items have no components")),
+            ItemFormat::Java => {}
+        }
+
+        let mut item = self;
+        item.format = ItemFormat::Components;
+        let Some(tag) = item.tag.take() else {
+            return Ok(item);
+        };
+        let mut tag = Fields::new(tag.into())?;
+
+        if let Some(key) = UNCONVERTED_FIELDS.iter().find(|key| tag.contains(key)) {
+            return Err(unsupported(&format!(
+                "the {} tag has no component this conversion builds",
+                key
+            )));
+        }
+        let hide_flags = tag.optional::<i32>("HideFlags")?.unwrap_or(0);
+        if hide_flags & !CONVERTED_HIDE_FLAGS != 0 {
+            return Err(unsupported(&format!(
+                "HideFlags {} hides tooltip lines this conversion has no components for",
+                hide_flags
+            )));
+        }
+
+        for (key, id) in SCALAR_FIELDS {
+            item.set_component(id, tag.optional(key)?);
+        }
+        // The game drops `Unbreakable: 0b`, which is the same as no flag.
+        if tag.optional("Unbreakable")?.unwrap_or(false) {
+            let unbreakable = with_tooltip(NbtValue::new(), hide_flags & HIDE_UNBREAKABLE != 0);
+            item.set_component(UNBREAKABLE, Some(unbreakable));
+        }
+
+        let mut display = match tag.optional("display")? {
+            Some(display) => Fields::new(display)?,
+            None => Fields::new(NbtValue::new())?,
+        };
+        item.set_component(CUSTOM_NAME, display.optional("Name")?);
+        item.set_component(LORE, display.optional("Lore")?);
+        if let Some(color) = display.optional::<i32>("color")? {
+            let mut dyed_color = NbtValue::new();
+            dyed_color.insert("rgb".to_string(), color);
+            let dyed_color = with_tooltip(dyed_color, hide_flags & HIDE_DYE != 0);
+            item.set_component(DYED_COLOR, Some(dyed_color));
+        }
+        item.set_component(
+            MAP_COLOR,
+            display.optional::<i32>("MapColor")?.map(NbtValue::from),
+        );
+
+        if let Some(list) = tag.optional("Enchantments")? {
+            let hidden = hide_flags & HIDE_ENCHANTMENTS != 0;
+            let enchantments = levels_component(&enchantment_levels(&list), hidden);
+            item.set_component(ENCHANTMENTS, Some(enchantments));
+        }
+        if let Some(list) = tag.optional("StoredEnchantments")? {
+            let hidden = hide_flags & HIDE_ADDITIONAL != 0;
+            let enchantments = levels_component(&enchantment_levels(&list), hidden);
+            item.set_component(STORED_ENCHANTMENTS, Some(enchantments));
+        }
+        if hide_flags & HIDE_ADDITIONAL != 0 {
+            item.set_component(HIDE_ADDITIONAL_TOOLTIP, Some(NbtValue::new()));
+        }
+        // Hiding the attribute modifiers also hides the default ones, such as a sword's attack damage.
+        if hide_flags & HIDE_MODIFIERS != 0 {
+            let mut modifiers = NbtValue::new();
+            modifiers.insert(
+                "modifiers".to_string(),
+                list_of(0x00, Vec::<NbtValue>::new()),
+            );
+            item.set_component(ATTRIBUTE_MODIFIERS, Some(with_tooltip(modifiers, true)));
+        }
+
+        let mut custom_data = tag.into_rest();
+        let display = display.into_rest();
+        if !display.is_empty() {
+            custom_data.insert("display".to_string(), display.into());
+        }
+        if !custom_data.is_empty() {
+            item.set_component(CUSTOM_DATA, Some(custom_data.into()));
+        }
+        Ok(item)
+    }
+
+    /// Converts a Java item from components back into the `tag` compound used before 1.20.5.
+    ///
+    /// This reverses `into_components`: the components it builds move back into their tag fields, and the
+    /// content of the `minecraft:custom_data` component becomes the rest of the tag. Items already in the `Java`
+    /// format are returned unchanged.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ItemStack)` - The item in the `Java` format.
+    /// * `Err(NbtError)` - `UnsupportedConversion` if the item is a Bedrock item or has a component with no
+    ///   place in the tag, such as `minecraft:max_stack_size`; `TypeMismatch` if `minecraft:custom_data` is
+    ///   not a compound.
+    pub fn into_legacy(self) -> Result<ItemStack, NbtError> {
+        match self.format {
+            ItemFormat::Java => return Ok(self),
+            ItemFormat::Bedrock => return Err(unsupported("Bedrock items have no components")),
+            ItemFormat::Components => {}
+        }
+
+        let mut item = self;
+        item.format = ItemFormat::Java;
+        let mut components = Fields::new(item.components.take().unwrap_or_default().into())?;
+
+        let mut tag: HashMap<String, NbtValue> =
+            components.optional(CUSTOM_DATA)?.unwrap_or_default();
+        let mut display = match tag.remove("display") {
+            Some(NbtValue::Compound(display)) => display,
+            Some(display) => {
+                tag.insert("display".to_string(), display);
+                HashMap::new()
+            }
+            None => HashMap::new(),
+        };
+        let mut hide_flags = 0;
+
+        for (key, id) in SCALAR_FIELDS {
+            if let Some(value) = components.optional::<NbtValue>(id)? {
+                tag.insert(key.to_string(), value);
+            }
+        }
+        if let Some(unbreakable) = components.optional::<NbtValue>(UNBREAKABLE)? {
+            tag.insert("Unbreakable".to_string(), true.into());
+            if is_hidden(&unbreakable) {
+                hide_flags |= HIDE_UNBREAKABLE;
+            }
+        }
+
+        if let Some(name) = components.optional(CUSTOM_NAME)? {
+            display.insert("Name".to_string(), name);
+        }
+        if let Some(lore) = components.optional(LORE)? {
+            display.insert("Lore".to_string(), lore);
+        }
+        if let Some(dyed_color) = components.optional::<NbtValue>(DYED_COLOR)? {
+            // Since 1.21.5 the component is the color itself.
+            let color = match &dyed_color {
+                NbtValue::Compound(dyed_color) => dyed_color.get("rgb").cloned(),
+                color => Some(color.clone()),
+            };
+            let color = color.ok_or_else(|| unsupported("the dyed color has no rgb value"))?;
+            display.insert("color".to_string(), color);
+            if is_hidden(&dyed_color) {
+                hide_flags |= HIDE_DYE;
+            }
+        }
+        if let Some(color) = components.optional(MAP_COLOR)? {
+            display.insert("MapColor".to_string(), color);
+        }
+
+        if let Some(enchantments) = components.optional::<NbtValue>(ENCHANTMENTS)? {
+            let list = enchantment_list(&component_levels(&enchantments));
+            tag.insert("Enchantments".to_string(), list);
+            if is_hidden(&enchantments) {
+                hide_flags |= HIDE_ENCHANTMENTS;
+            }
+        }
+        if let Some(enchantments) = components.optional::<NbtValue>(STORED_ENCHANTMENTS)? {
+            let list = enchantment_list(&component_levels(&enchantments));
+            tag.insert("StoredEnchantments".to_string(), list);
+            if is_hidden(&enchantments) {
+                hide_flags |= HIDE_ADDITIONAL;
+            }
+        }
+        if components
+            .optional::<NbtValue>(HIDE_ADDITIONAL_TOOLTIP)?
+            .is_some()
+        {
+            hide_flags |= HIDE_ADDITIONAL;
+        }
+        if let Some(modifiers) = components.optional::<NbtValue>(ATTRIBUTE_MODIFIERS)? {
+            let empty = modifiers
+                .as_compound()
+                .and_then(|modifiers| modifiers.get("modifiers"))
+                .and_then(NbtValue::as_list)
+                .is_some_and(|list| list.is_empty());
+            if !(empty && is_hidden(&modifiers)) {
+                return Err(unsupported(
+                    "attribute modifiers have no tag this conversion builds",
+                ));
+            }
+            hide_flags |= HIDE_MODIFIERS;
+        }
+
+        if let Some(id) = components.keys().into_iter().min() {
+            return Err(unsupported(&format!(
+                "the {} component has no tag this conversion builds",
+                id
+            )));
+        }
+        if !display.is_empty() {
+            tag.insert("display".to_string(), display.into());
+        }
+        if hide_flags != 0 {
+            tag.insert("HideFlags".to_string(), hide_flags.into());
+        }
+        item.tag = (!tag.is_empty()).then_some(tag);
+        Ok(item)
+    }
+}
+
+from_nbt_field!(ItemStack);
@@ -0,0 +1,5 @@
+mod data;
+mod item;
+
+pub use data::*;
+pub use item::*;
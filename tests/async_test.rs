@@ -0,0 +1,83 @@
+#![cfg(feature = "async")]
+
+use std::path::PathBuf;
+
+use commandblock::nbt::{
+    read_from_async_reader, read_from_file, read_from_reader, write_to_async_writer,
+    write_to_async_writer_with_level, Compression, Endian,
+};
+
+#[tokio::test]
+async fn test_read_from_async_reader() {
+    for (path, compression, endian) in [
+        ("tests/data/java_level.dat", Compression::Gzip, Endian::Big),
+        (
+            "tests/data/bedrock_level.dat",
+            Compression::Uncompressed,
+            Endian::Little,
+        ),
+    ] {
+        let file = tokio::fs::File::open(path).await.unwrap();
+        let value = read_from_async_reader(file, compression, endian)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            value,
+            read_from_file(PathBuf::from(path), compression, endian).unwrap()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_write_to_async_writer() {
+    let (name, value) = read_from_file(
+        PathBuf::from("tests/data/java_level.dat"),
+        Compression::Gzip,
+        Endian::Big,
+    )
+    .unwrap();
+
+    for compression in [
+        Compression::Uncompressed,
+        Compression::Gzip,
+        Compression::Zlib,
+    ] {
+        let mut data = Vec::new();
+        write_to_async_writer(Some(&name), &value, &mut data, compression, Endian::Big)
+            .await
+            .unwrap();
+
+        // The async writer must produce data the blocking reader understands and the other way around.
+        let (_, read_value) = read_from_reader(&data[..], compression, Endian::Big).unwrap();
+        assert_eq!(read_value, value);
+        let (_, read_value) = read_from_async_reader(&data[..], compression, Endian::Big)
+            .await
+            .unwrap();
+        assert_eq!(read_value, value);
+    }
+
+    let mut fastest = Vec::new();
+    write_to_async_writer_with_level(
+        None,
+        &value,
+        &mut fastest,
+        Compression::Zlib,
+        Endian::Big,
+        0,
+    )
+    .await
+    .unwrap();
+    let mut smallest = Vec::new();
+    write_to_async_writer_with_level(
+        None,
+        &value,
+        &mut smallest,
+        Compression::Zlib,
+        Endian::Big,
+        9,
+    )
+    .await
+    .unwrap();
+    assert!(smallest.len() < fastest.len());
+}
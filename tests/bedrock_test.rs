@@ -0,0 +1,598 @@
+use std::{fs, io::Write, path::PathBuf};
+
+use commandblock::{
+    bedrock::{
+        parse_digest, write_digest, write_records, BedrockKey, BedrockLevelData, BedrockWorld,
+        BlockStorage, ChunkPos, Data2D, Data3D, Dimension, GameRule, LevelDb, NbtRecords, SubChunk,
+    },
+    nbt::{read_from_file, Compression, Endian, NbtError, NbtReader, NbtValue, NbtWriter},
+};
+use flate2::write::DeflateEncoder;
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn write_length_prefixed(bytes: &mut Vec<u8>, data: &[u8]) {
+    write_varint(bytes, data.len() as u64);
+    bytes.extend_from_slice(data);
+}
+
+fn internal_key(key: &[u8], sequence: u64, is_value: bool) -> Vec<u8> {
+    let mut bytes = key.to_vec();
+    bytes.extend_from_slice(&(sequence << 8 | is_value as u64).to_le_bytes());
+    bytes
+}
+
+/// Builds a block without prefix compression, with a single restart point.
+fn block(entries: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (key, value) in entries {
+        write_varint(&mut bytes, 0);
+        write_varint(&mut bytes, key.len() as u64);
+        write_varint(&mut bytes, value.len() as u64);
+        bytes.extend_from_slice(key);
+        bytes.extend_from_slice(value);
+    }
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes
+}
+
+/// Appends a block with its trailer, returning its handle.
+fn write_block(table: &mut Vec<u8>, block: &[u8], compress: bool) -> Vec<u8> {
+    let (data, compression) = if compress {
+        let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(block).unwrap();
+        (encoder.finish().unwrap(), 4)
+    } else {
+        (block.to_vec(), 0)
+    };
+
+    let mut handle = Vec::new();
+    write_varint(&mut handle, table.len() as u64);
+    write_varint(&mut handle, data.len() as u64);
+
+    table.extend_from_slice(&data);
+    table.push(compression);
+    table.extend_from_slice(&[0; 4]);
+    handle
+}
+
+/// Builds a table with one data block per entry, so lookups have to pick the right block.
+fn table(entries: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut index = Vec::new();
+    for entry in entries {
+        let handle = write_block(&mut bytes, &block(std::slice::from_ref(entry)), true);
+        index.push((entry.0.clone(), handle));
+    }
+    let metaindex = write_block(&mut bytes, &block(&[]), false);
+    let index = write_block(&mut bytes, &block(&index), false);
+
+    let mut footer = [metaindex, index].concat();
+    footer.resize(40, 0);
+    footer.extend_from_slice(&0xdb47_7524_8b80_fb57u64.to_le_bytes());
+    bytes.extend_from_slice(&footer);
+    bytes
+}
+
+/// Wraps a record in a single log fragment.
+fn log_record(record: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0; 4];
+    bytes.extend_from_slice(&(record.len() as u16).to_le_bytes());
+    bytes.push(1);
+    bytes.extend_from_slice(record);
+    bytes
+}
+
+fn new_file(edit: &mut Vec<u8>, level: u64, number: u64, smallest: &[u8], largest: &[u8]) {
+    write_varint(edit, 7);
+    write_varint(edit, level);
+    write_varint(edit, number);
+    write_varint(edit, 0);
+    write_length_prefixed(edit, smallest);
+    write_length_prefixed(edit, largest);
+}
+
+fn with_id(id: &str) -> NbtValue {
+    let mut value = NbtValue::new();
+    value.insert("id".to_string(), id);
+    value
+}
+
+fn create_world(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&path);
+    let db = path.join("db");
+    fs::create_dir_all(&db).unwrap();
+
+    let version = BedrockKey::Version(ChunkPos::new(0, 0, Dimension::Overworld)).to_bytes();
+    let nether = BedrockKey::Version(ChunkPos::new(-3, 7, Dimension::Nether)).to_bytes();
+    let subchunk =
+        BedrockKey::SubChunkPrefix(ChunkPos::new(0, 0, Dimension::Overworld), -4).to_bytes();
+
+    // Level 1 holds the oldest data.
+    let mut level_one = vec![
+        (internal_key(&version, 1, true), vec![40]),
+        (internal_key(&subchunk, 2, true), vec![9, 1]),
+        (internal_key(&nether, 3, true), vec![40]),
+        (internal_key(b"BiomeData", 4, true), b"old".to_vec()),
+        (internal_key(b"mobevents", 5, true), b"events".to_vec()),
+    ];
+    level_one.sort();
+    fs::write(db.join("000005.ldb"), table(&level_one)).unwrap();
+
+    // Level 0 is newer, updating one key and deleting another.
+    let level_zero = vec![
+        (internal_key(b"BiomeData", 6, true), b"new".to_vec()),
+        (internal_key(b"mobevents", 7, false), Vec::new()),
+    ];
+    fs::write(db.join("000006.ldb"), table(&level_zero)).unwrap();
+
+    let mut edit = Vec::new();
+    write_varint(&mut edit, 1);
+    write_length_prefixed(&mut edit, b"leveldb.BytewiseComparator");
+    write_varint(&mut edit, 2);
+    write_varint(&mut edit, 7);
+    new_file(
+        &mut edit,
+        1,
+        5,
+        &level_one[0].0,
+        &level_one[level_one.len() - 1].0,
+    );
+    new_file(&mut edit, 0, 6, &level_zero[0].0, &level_zero[1].0);
+    fs::write(db.join("MANIFEST-000002"), log_record(&edit)).unwrap();
+    fs::write(db.join("CURRENT"), "MANIFEST-000002\n").unwrap();
+
+    // The log is newest of all: it deletes the nether chunk and adds the local player and the chunk's block
+    // entities and entities. The digest lists an actor that has no record.
+    let pos = ChunkPos::new(0, 0, Dimension::Overworld);
+    let actor = [1, 0, 0, 0, 2, 0, 0, 0];
+    let writes = [
+        (BedrockKey::LocalPlayer.to_bytes(), b"player".to_vec()),
+        (
+            BedrockKey::BlockEntity(pos).to_bytes(),
+            write_records([with_id("Chest"), with_id("Sign")]).unwrap(),
+        ),
+        (
+            BedrockKey::Entity(pos).to_bytes(),
+            write_records([with_id("minecraft:cow")]).unwrap(),
+        ),
+        (
+            BedrockKey::Digest(pos).to_bytes(),
+            write_digest(&[actor, [9; 8]]),
+        ),
+        (
+            BedrockKey::Actor(actor).to_bytes(),
+            write_records([with_id("minecraft:pig")]).unwrap(),
+        ),
+    ];
+
+    let mut batch = Vec::new();
+    batch.extend_from_slice(&8u64.to_le_bytes());
+    batch.extend_from_slice(&(writes.len() as u32 + 1).to_le_bytes());
+    batch.push(0);
+    write_length_prefixed(&mut batch, &nether);
+    for (key, value) in writes {
+        batch.push(1);
+        write_length_prefixed(&mut batch, &key);
+        write_length_prefixed(&mut batch, &value);
+    }
+    fs::write(db.join("000007.log"), log_record(&batch)).unwrap();
+
+    path
+}
+
+#[test]
+fn test_key_round_trip() {
+    let pos = ChunkPos::new(-12, 300, Dimension::Overworld);
+    let keys = [
+        BedrockKey::Data3D(pos),
+        BedrockKey::Version(ChunkPos::new(5, -5, Dimension::End)),
+        BedrockKey::SubChunkPrefix(pos, -4),
+        BedrockKey::BlockEntity(ChunkPos::new(1, 2, Dimension::Other(7))),
+        BedrockKey::Entity(pos),
+        BedrockKey::LegacyVersion(pos),
+        BedrockKey::Digest(ChunkPos::new(1, 2, Dimension::Nether)),
+        BedrockKey::Actor([0, 0, 0, 1, 0, 0, 0, 2]),
+        BedrockKey::LocalPlayer,
+        BedrockKey::PlayerServer("5c2d7c3f".to_string()),
+        BedrockKey::Player("abc".to_string()),
+        BedrockKey::Map(-4294967295),
+        BedrockKey::Named("Overworld".to_string()),
+        BedrockKey::Unknown(vec![0xFF, 0x00]),
+    ];
+
+    for key in keys {
+        assert_eq!(BedrockKey::from_bytes(&key.to_bytes()), key);
+    }
+
+    let bytes = [1, 0, 0, 0, 2, 0, 0, 0, 47, 0xFC];
+    assert_eq!(
+        BedrockKey::from_bytes(&bytes),
+        BedrockKey::SubChunkPrefix(ChunkPos::new(1, 2, Dimension::Overworld), -4)
+    );
+    assert_eq!(
+        BedrockKey::from_bytes(b"mobevents"),
+        BedrockKey::Named("mobevents".to_string())
+    );
+}
+
+#[test]
+fn test_read_leveldb() {
+    let path = create_world("commandblock_test_read_leveldb");
+    let db = LevelDb::open(path.join("db")).unwrap();
+
+    assert_eq!(db.get(b"BiomeData").unwrap(), Some(b"new".to_vec()));
+    assert_eq!(db.get(b"mobevents").unwrap(), None);
+    assert_eq!(db.get(b"~local_player").unwrap(), Some(b"player".to_vec()));
+    assert_eq!(db.get(b"missing").unwrap(), None);
+
+    let keys = db.keys().unwrap();
+    assert_eq!(keys.len(), 8);
+    assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn test_list_chunks() {
+    let path = create_world("commandblock_test_list_chunks");
+    let world = BedrockWorld::open(path).unwrap();
+    let pos = ChunkPos::new(0, 0, Dimension::Overworld);
+
+    assert_eq!(world.chunks().unwrap(), vec![pos]);
+    assert_eq!(
+        world.get(&BedrockKey::SubChunkPrefix(pos, -4)).unwrap(),
+        Some(vec![9, 1])
+    );
+    assert!(world.keys().unwrap().contains(&BedrockKey::LocalPlayer));
+}
+
+#[test]
+fn test_read_real_leveldb() {
+    // Written by LevelDB 1.22 in three sessions with compression off: the first two were flushed to tables,
+    // the second overwriting BiomeData and deleting mobevents and a chunk, and the last is still in the log.
+    let world = BedrockWorld::open(PathBuf::from("tests/data/bedrock_world")).unwrap();
+    let db = world.db();
+
+    assert_eq!(db.get(b"BiomeData").unwrap(), Some(b"new".to_vec()));
+    assert_eq!(db.get(b"mobevents").unwrap(), None);
+    assert_eq!(db.get(b"~local_player").unwrap(), Some(b"player".to_vec()));
+    assert_eq!(db.get(b"map_-1000").unwrap(), Some(b"updated".to_vec()));
+    assert_eq!(db.get(b"map_-1039").unwrap(), Some(vec![b'm'; 40]));
+
+    let keys = db.keys().unwrap();
+    assert_eq!(keys.len(), 44);
+    assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let pos = ChunkPos::new(0, 0, Dimension::Overworld);
+    assert_eq!(world.chunks().unwrap(), vec![pos]);
+    assert!(world.keys().unwrap().contains(&BedrockKey::Map(-1020)));
+
+    let sub_chunk = world.sub_chunk(pos, -4).unwrap().unwrap();
+    assert_eq!(
+        sub_chunk.get_block(4, 15, 4, 0).unwrap().get("name"),
+        Some(&"minecraft:oak_log".into())
+    );
+}
+
+#[test]
+fn test_read_corrupt_table() {
+    let path = create_world("commandblock_test_read_corrupt_table");
+
+    // A footer whose index block handle claims an enormous block far past the end of the file.
+    let mut footer = vec![0, 0, 0];
+    write_varint(&mut footer, 1 << 62);
+    footer.resize(40, 0);
+    footer.extend_from_slice(&0xdb47_7524_8b80_fb57u64.to_le_bytes());
+    fs::write(path.join("db").join("000005.ldb"), footer).unwrap();
+
+    let result = LevelDb::open(path.join("db")).and_then(|db| db.get(b"BiomeData"));
+    assert!(matches!(result, Err(NbtError::InvalidLevelDb(_))));
+}
+
+fn block_state(name: &str) -> Vec<u8> {
+    let mut value = NbtValue::new();
+    value.insert("name".to_string(), name);
+    value.insert("states".to_string(), NbtValue::new());
+    value.insert("version".to_string(), 18100737);
+
+    let mut bytes = Vec::new();
+    NbtWriter::new(&mut bytes, Endian::Little)
+        .write_root("", value)
+        .unwrap();
+    bytes
+}
+
+#[test]
+fn test_parse_sub_chunk() {
+    let mut data = vec![9, 2, 0xFC];
+
+    // Blocks use 3 bits per block, 10 blocks per word, with the block at 1, 2, 3 set to stone.
+    data.push(3 << 1);
+    let mut words = vec![0u32; 410];
+    let index = (1 << 8) | (3 << 4) | 2;
+    words[index / 10] |= 1 << ((index % 10) * 3);
+    for word in words {
+        data.extend_from_slice(&word.to_le_bytes());
+    }
+    data.extend_from_slice(&2i32.to_le_bytes());
+    data.extend(block_state("minecraft:air"));
+    data.extend(block_state("minecraft:stone"));
+
+    // The waterlogging layer is all water, with no words and no palette size.
+    data.push(0);
+    data.extend(block_state("minecraft:water"));
+
+    let sub_chunk = SubChunk::parse(&data).unwrap();
+    assert_eq!(sub_chunk.version(), 9);
+    assert_eq!(sub_chunk.y(), Some(-4));
+    assert_eq!(sub_chunk.layers().len(), 2);
+
+    let name = |block: Option<&NbtValue>| block.unwrap().get("name").unwrap().clone();
+    assert_eq!(
+        name(sub_chunk.get_block(1, 2, 3, 0)),
+        "minecraft:stone".into()
+    );
+    assert_eq!(
+        name(sub_chunk.get_block(1, 3, 2, 0)),
+        "minecraft:air".into()
+    );
+    assert_eq!(
+        name(sub_chunk.get_block(15, 15, 15, 1)),
+        "minecraft:water".into()
+    );
+    assert_eq!(sub_chunk.get_block(0, 0, 0, 2), None);
+    assert_eq!(sub_chunk.get_block(16, 0, 0, 0), None);
+
+    assert!(SubChunk::parse(&data[..500]).is_err());
+    assert!(matches!(
+        SubChunk::parse(&[2]),
+        Err(NbtError::InvalidBedrockData(_))
+    ));
+}
+
+#[test]
+fn test_write_sub_chunk() {
+    let block = |name: &str| {
+        let (_, value) = NbtReader::new(&block_state(name)[..], Endian::Little)
+            .parse_root()
+            .unwrap();
+        value
+    };
+
+    let mut sub_chunk = SubChunk::new(-4, block("minecraft:air"));
+    sub_chunk
+        .set_block(1, 2, 3, 0, block("minecraft:stone"))
+        .unwrap();
+    sub_chunk
+        .layers_mut()
+        .push(BlockStorage::new(block("minecraft:air")));
+    sub_chunk
+        .set_block(1, 2, 3, 1, block("minecraft:water"))
+        .unwrap();
+    assert!(sub_chunk
+        .set_block(0, 0, 0, 2, block("minecraft:air"))
+        .is_err());
+
+    let bytes = sub_chunk.to_bytes().unwrap();
+    assert_eq!(&bytes[..4], &[9, 2, 0xFC, 1 << 1]);
+    // Two block states fit in one bit per block, 32 blocks per word.
+    assert_eq!(bytes[4 + 128 * 4..4 + 128 * 4 + 4], 2i32.to_le_bytes());
+    assert_eq!(SubChunk::parse(&bytes).unwrap(), sub_chunk);
+
+    // Palette entries no block uses are left out, and a single block state is still written with one bit.
+    sub_chunk
+        .set_block(1, 2, 3, 0, block("minecraft:air"))
+        .unwrap();
+    sub_chunk.set_version(8).unwrap();
+    let bytes = sub_chunk.to_bytes().unwrap();
+    assert_eq!(&bytes[..4], &[8, 2, 1 << 1, 0]);
+    assert!(bytes[3..3 + 128 * 4].iter().all(|&byte| byte == 0));
+    assert_eq!(bytes[3 + 128 * 4..3 + 128 * 4 + 4], 1i32.to_le_bytes());
+
+    let written = SubChunk::parse(&bytes).unwrap();
+    assert_eq!(written.version(), 8);
+    assert_eq!(written.layers()[0].palette().len(), 1);
+    assert_eq!(
+        written.get_block(1, 2, 3, 1).unwrap().get("name"),
+        Some(&"minecraft:water".into())
+    );
+    assert!(sub_chunk.set_version(7).is_err());
+}
+
+#[test]
+fn test_sub_chunk_fixture() {
+    // Assembled byte by byte from the format description rather than by this crate, as no subchunk saved by
+    // the game is available: stone up to Y 11, dirt up to Y 14 and grass on top, with an oak log at 4, 15, 4,
+    // air at 15, 15, 15 and a layer of still water stored with 0 bits per block.
+    let data = fs::read("tests/data/bedrock_sub_chunk.dat").unwrap();
+    let sub_chunk = SubChunk::parse(&data).unwrap();
+    assert_eq!(sub_chunk.version(), 9);
+    assert_eq!(sub_chunk.y(), Some(-4));
+    assert_eq!(sub_chunk.layers().len(), 2);
+    assert_eq!(sub_chunk.layers()[0].palette().len(), 5);
+
+    let name = |x, y, z, layer| match sub_chunk.get_block(x, y, z, layer).unwrap().get("name") {
+        Some(NbtValue::String(name)) => name.clone(),
+        other => panic!("unexpected block name {:?}", other),
+    };
+    assert_eq!(name(0, 0, 0, 0), "minecraft:stone");
+    assert_eq!(name(9, 11, 2, 0), "minecraft:stone");
+    assert_eq!(name(9, 12, 2, 0), "minecraft:dirt");
+    assert_eq!(name(0, 15, 9, 0), "minecraft:grass_block");
+    assert_eq!(name(4, 15, 4, 0), "minecraft:oak_log");
+    assert_eq!(name(15, 15, 15, 0), "minecraft:air");
+    assert_eq!(name(15, 15, 15, 1), "minecraft:water");
+
+    let log = sub_chunk.get_block(4, 15, 4, 0).unwrap();
+    assert_eq!(
+        log.get("states")
+            .and_then(|states| states.get("pillar_axis")),
+        Some(&"y".into())
+    );
+    assert_eq!(log.get("version"), Some(&18100737.into()));
+
+    // Compound keys may come out in another order, but the header, words and palette size match exactly. The
+    // water layer is written with 1 bit per block, adding 128 words of zeros and its palette size.
+    let bytes = sub_chunk.to_bytes().unwrap();
+    let words_end = 4 + 410 * 4 + 4;
+    assert_eq!(bytes.len(), data.len() + 128 * 4 + 4);
+    assert_eq!(bytes[..words_end], data[..words_end]);
+    assert_eq!(SubChunk::parse(&bytes).unwrap(), sub_chunk);
+}
+
+#[test]
+fn test_parse_data_3d() {
+    let mut data = Vec::new();
+    for index in 0..256i16 {
+        data.extend_from_slice(&(index % 16 + 64).to_le_bytes());
+    }
+
+    // The lowest subchunk is all plains, with no words and no palette size.
+    data.push(0);
+    data.extend_from_slice(&1i32.to_le_bytes());
+    // The next repeats it.
+    data.push(0xFF);
+    // The third is plains with a single desert block at 15, 15, 15 of the subchunk, 1 bit per block.
+    data.push(1 << 1);
+    let mut words = vec![0u32; 128];
+    words[127] = 1 << 31;
+    for word in words {
+        data.extend_from_slice(&word.to_le_bytes());
+    }
+    data.extend_from_slice(&2i32.to_le_bytes());
+    data.extend_from_slice(&1i32.to_le_bytes());
+    data.extend_from_slice(&2i32.to_le_bytes());
+
+    let data_3d = Data3D::parse(&data).unwrap();
+    assert_eq!(data_3d.height(3, 0), 67);
+    assert_eq!(data_3d.height(0, 3), 64);
+    assert_eq!(data_3d.biomes().len(), 3);
+    assert_eq!(data_3d.biomes()[1], data_3d.biomes()[0]);
+    assert_eq!(data_3d.biome(0, 20, 0), Some(1));
+    assert_eq!(data_3d.biome(15, 47, 15), Some(2));
+    assert_eq!(data_3d.biome(15, 46, 15), Some(1));
+    assert_eq!(data_3d.biome(0, 48, 0), None);
+
+    assert_eq!(data_3d.to_bytes().unwrap(), data);
+
+    assert!(Data3D::parse(&data[..512 + 3]).is_err());
+    let mut repeated_first = data[..512].to_vec();
+    repeated_first.push(0xFF);
+    assert!(matches!(
+        Data3D::parse(&repeated_first),
+        Err(NbtError::InvalidBedrockData(_))
+    ));
+}
+
+#[test]
+fn test_data_2d_round_trip() {
+    let mut data_2d = Data2D::new(1);
+    data_2d.set_height(2, 3, 70);
+    data_2d.set_biome(2, 3, 21);
+
+    let bytes = data_2d.to_bytes().unwrap();
+    assert_eq!(bytes.len(), 768);
+    assert_eq!(bytes[(3 * 16 + 2) * 2], 70);
+    assert_eq!(bytes[512 + 3 * 16 + 2], 21);
+
+    let parsed = Data2D::parse(&bytes).unwrap();
+    assert_eq!(parsed, data_2d);
+    assert_eq!(parsed.height(2, 3), 70);
+    assert_eq!(parsed.biome(2, 3), 21);
+    assert_eq!(parsed.biome(3, 2), 1);
+
+    assert!(matches!(
+        Data2D::parse(&bytes[..700]),
+        Err(NbtError::UnexpectedEof)
+    ));
+}
+
+#[test]
+fn test_read_entities() {
+    let path = create_world("commandblock_test_read_entities");
+    let world = BedrockWorld::open(path).unwrap();
+    let pos = ChunkPos::new(0, 0, Dimension::Overworld);
+
+    assert_eq!(
+        world.block_entities(pos).unwrap(),
+        vec![with_id("Chest"), with_id("Sign")]
+    );
+    assert_eq!(
+        world.entities(pos).unwrap(),
+        vec![with_id("minecraft:cow"), with_id("minecraft:pig")]
+    );
+
+    let empty = ChunkPos::new(5, 5, Dimension::Overworld);
+    assert!(world.block_entities(empty).unwrap().is_empty());
+    assert!(world.entities(empty).unwrap().is_empty());
+}
+
+#[test]
+fn test_records() {
+    let data = write_records([with_id("Chest"), with_id("Sign")]).unwrap();
+    assert_eq!(data[0], 0x0A);
+
+    let mut records = NbtRecords::new(&data[..data.len() - 1]);
+    assert_eq!(records.next().unwrap().unwrap(), with_id("Chest"));
+    assert!(records.next().unwrap().is_err());
+    assert!(records.next().is_none());
+
+    assert_eq!(NbtRecords::new(&[]).count(), 0);
+    assert!(write_records([NbtValue::Int(1)]).is_err());
+
+    let ids = [[1; 8], [2; 8]];
+    assert_eq!(parse_digest(&write_digest(&ids)).unwrap(), ids);
+    assert!(matches!(
+        parse_digest(&[0; 12]),
+        Err(NbtError::InvalidBedrockData(_))
+    ));
+}
+
+#[test]
+fn test_level_data() {
+    let source = PathBuf::from("tests/data/bedrock_level.dat");
+    let (_, value) =
+        read_from_file(source.clone(), Compression::Uncompressed, Endian::Little).unwrap();
+
+    let mut level = BedrockLevelData::read_from_file(source).unwrap();
+    assert_eq!(level.level_name, "My World");
+    assert_eq!(
+        (level.spawn_x, level.spawn_y, level.spawn_z),
+        (-94, 32767, -272)
+    );
+    assert_eq!((level.game_type, level.difficulty), (0, 2));
+    assert_eq!(level.storage_version, 10);
+    assert_eq!(level.last_opened_with_version, Some(vec![1, 20, 12, 1, 0]));
+    assert!(!level.experiments.as_ref().unwrap().ever_used);
+    assert!(level.abilities.as_ref().unwrap().build);
+    assert_eq!(level.game_rules["keepinventory"], GameRule::Bool(false));
+    assert_eq!(level.game_rules["randomtickspeed"], GameRule::Int(1));
+    assert!(level.other.contains_key("FlatWorldLayers"));
+    assert_eq!(level.to_nbt(), value);
+
+    level.level_name = "Renamed".to_string();
+    level
+        .game_rules
+        .insert("keepinventory".to_string(), GameRule::Bool(true));
+    let path = std::env::temp_dir().join("commandblock_bedrock_level.dat");
+    level.write_to_file(path.clone()).unwrap();
+
+    let data = fs::read(&path).unwrap();
+    assert_eq!(data[..4], 10i32.to_le_bytes());
+    assert_eq!(data[4..8], (data.len() as i32 - 8).to_le_bytes());
+    assert_eq!(BedrockLevelData::read_from_file(path).unwrap(), level);
+
+    let mut value = value;
+    value.remove("LevelName");
+    assert!(matches!(
+        BedrockLevelData::from_nbt(value).unwrap_err(),
+        NbtError::PathNotFound(key) if key == "LevelName"
+    ));
+}
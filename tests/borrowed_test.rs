@@ -0,0 +1,102 @@
+use std::{borrow::Cow, fs::File, io::Read, path::PathBuf};
+
+use commandblock::nbt::{read_from_file, Compression, Endian, NbtError, NbtRef};
+use flate2::read::GzDecoder;
+
+#[test]
+fn test_parse_borrowed_level_data() {
+    let mut java_bytes = Vec::new();
+    GzDecoder::new(File::open("tests/data/java_level.dat").unwrap())
+        .read_to_end(&mut java_bytes)
+        .unwrap();
+    let bedrock_bytes = std::fs::read("tests/data/bedrock_level.dat").unwrap();
+
+    let (_, java_ref) = NbtRef::parse_data(&java_bytes, Endian::Big).unwrap();
+    let (_, bedrock_ref) = NbtRef::parse_data(&bedrock_bytes, Endian::Little).unwrap();
+
+    let (_, java) = read_from_file(
+        PathBuf::from("tests/data/java_level.dat"),
+        Compression::Gzip,
+        Endian::Big,
+    )
+    .unwrap();
+    let (_, bedrock) = read_from_file(
+        PathBuf::from("tests/data/bedrock_level.dat"),
+        Compression::Uncompressed,
+        Endian::Little,
+    )
+    .unwrap();
+
+    assert_eq!(java_ref.to_nbt_value(), java);
+    assert_eq!(bedrock_ref.to_nbt_value(), bedrock);
+
+    match java_ref.get("Data").and_then(|data| data.get("LevelName")) {
+        Some(NbtRef::String(Cow::Borrowed(name))) => assert_eq!(*name, "Fucking Dorks"),
+        other => panic!("Expected a borrowed string, but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_borrowed_arrays() {
+    let java_data = [
+        0x00, 0x00, 0x00, 0x02, // array length
+        0x00, 0x00, 0x00, 0x01, // array values
+        0xFF, 0xFF, 0xFF, 0xFE, // array values
+    ];
+    let (value, consumed) = NbtRef::parse_value(&java_data, 0x0B, Endian::Big).unwrap();
+    assert_eq!(consumed, java_data.len());
+    match value {
+        NbtRef::IntArray(array) => {
+            assert_eq!(array.len(), 2);
+            assert_eq!(array.get(1), Some(-2));
+            assert_eq!(array.get(2), None);
+            assert_eq!(array.to_vec(), vec![1, -2]);
+        }
+        other => panic!("Expected NbtRef::IntArray, but got {:?}", other),
+    }
+
+    let bedrock_data = [
+        0x01, 0x00, 0x00, 0x00, // array length
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // array values
+    ];
+    let (value, _) = NbtRef::parse_value(&bedrock_data, 0x0C, Endian::Little).unwrap();
+    match value {
+        NbtRef::LongArray(array) => assert_eq!(array.iter().collect::<Vec<_>>(), vec![1]),
+        other => panic!("Expected NbtRef::LongArray, but got {:?}", other),
+    }
+
+    let byte_data = [0x00, 0x00, 0x00, 0x02, 0x7F, 0x80];
+    let (value, _) = NbtRef::parse_value(&byte_data, 0x07, Endian::Big).unwrap();
+    assert_eq!(value, NbtRef::ByteArray(&[0x7F, 0x80]));
+}
+
+#[test]
+fn test_parse_borrowed_errors() {
+    let truncated = [0x00, 0x00, 0x00, 0x04, 0x01];
+    let error = NbtRef::parse_value(&truncated, 0x07, Endian::Big).unwrap_err();
+    assert!(matches!(error.without_context(), NbtError::UnexpectedEof));
+    assert_eq!(error.offset(), Some(4));
+
+    let negative = [0xFF, 0xFF, 0xFF, 0xFF];
+    let error = NbtRef::parse_value(&negative, 0x0B, Endian::Big).unwrap_err();
+    assert!(matches!(
+        error.without_context(),
+        NbtError::InvalidIntArrayLength(_)
+    ));
+
+    let error = NbtRef::parse_value(&[], 0x0D, Endian::Big).unwrap_err();
+    assert!(matches!(
+        error.without_context(),
+        NbtError::InvalidTagType(0x0D)
+    ));
+
+    let nested = [
+        0x09, 0x00, 0x01, b'a', // list "a"
+        0x0A, 0x00, 0x00, 0x00, 0x02, // of 2 compounds
+        0x00, // first compound, empty
+        0x03, 0x00, 0x01, b'b', 0x00, // int "b", truncated
+    ];
+    let error = NbtRef::parse_value(&nested, 0x0A, Endian::Big).unwrap_err();
+    assert_eq!(error.path().unwrap().to_string(), "a[1].b");
+    assert!(matches!(error.without_context(), NbtError::UnexpectedEof));
+}
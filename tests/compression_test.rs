@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use commandblock::nbt::{
+    read_chunk, read_from_file, read_from_reader, write_chunk, write_to_writer_with_level,
+    Compression, Endian, NbtError, NbtValue, DEFAULT_COMPRESSION_LEVEL,
+};
+
+fn java_level() -> NbtValue {
+    read_from_file(
+        PathBuf::from("tests/data/java_level.dat"),
+        Compression::Gzip,
+        Endian::Big,
+    )
+    .unwrap()
+    .1
+}
+
+#[test]
+fn test_compression_level() {
+    let value = java_level();
+
+    for compression in [Compression::Gzip, Compression::Zlib] {
+        let mut fastest = Vec::new();
+        write_to_writer_with_level(None, &value, &mut fastest, compression, Endian::Big, 0)
+            .unwrap();
+        let mut smallest = Vec::new();
+        write_to_writer_with_level(None, &value, &mut smallest, compression, Endian::Big, 9)
+            .unwrap();
+
+        assert!(smallest.len() < fastest.len());
+        for data in [fastest, smallest] {
+            let (_, read_value) = read_from_reader(&data[..], compression, Endian::Big).unwrap();
+            assert_eq!(read_value, value);
+        }
+    }
+}
+
+#[test]
+fn test_region_ids() {
+    for compression in [
+        Compression::Gzip,
+        Compression::Zlib,
+        Compression::Uncompressed,
+        Compression::Lz4,
+    ] {
+        assert_eq!(
+            Compression::from_region_id(compression.region_id()).unwrap(),
+            compression
+        );
+    }
+
+    assert!(matches!(
+        Compression::from_region_id(127),
+        Err(NbtError::UnsupportedCompression(_))
+    ));
+    assert!(matches!(
+        Compression::from_region_id(130),
+        Err(NbtError::UnsupportedCompression(_))
+    ));
+    assert!(matches!(
+        Compression::from_region_id(0),
+        Err(NbtError::InvalidCompression(0))
+    ));
+}
+
+#[test]
+fn test_chunk_round_trip() {
+    let value = java_level();
+
+    for compression in [
+        Compression::Gzip,
+        Compression::Zlib,
+        Compression::Uncompressed,
+    ] {
+        let mut chunk = Vec::new();
+        write_chunk(&value, &mut chunk, compression, DEFAULT_COMPRESSION_LEVEL).unwrap();
+        assert_eq!(
+            u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as usize,
+            chunk.len() - 4
+        );
+        assert_eq!(chunk[4], compression.region_id());
+
+        // Region files pad chunks to whole sectors, which must not be read as chunk data.
+        chunk.resize(chunk.len().next_multiple_of(4096), 0);
+        assert_eq!(
+            read_chunk(&chunk[..]).unwrap(),
+            (value.clone(), compression)
+        );
+    }
+}
+
+#[test]
+fn test_chunk_fixture() {
+    let file = std::fs::File::open("tests/data/java_chunk.dat").unwrap();
+    let (chunk, compression) = read_chunk(file).unwrap();
+    assert_eq!(compression, Compression::Zlib);
+    assert_eq!(chunk.get("DataVersion"), Some(&NbtValue::Int(3218)));
+    assert_eq!(chunk.get("sections").unwrap().len(), 24);
+
+    let mut stored = Vec::new();
+    write_chunk(
+        &chunk,
+        &mut stored,
+        Compression::Zlib,
+        DEFAULT_COMPRESSION_LEVEL,
+    )
+    .unwrap();
+    assert_eq!(read_chunk(&stored[..]).unwrap(), (chunk, compression));
+}
+
+#[test]
+fn test_custom_chunk_compression() {
+    let chunk = [0, 0, 0, 1, 127];
+    assert!(matches!(
+        read_chunk(&chunk[..]),
+        Err(NbtError::UnsupportedCompression(_))
+    ));
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn test_lz4_round_trip() {
+    let mut value = java_level();
+    // Large enough to span several 64 KiB blocks.
+    value.insert(
+        "Noise".to_string(),
+        NbtValue::LongArray((0..50_000i64).map(|i| i * i * 2_654_435_761).collect()),
+    );
+
+    let mut chunk = Vec::new();
+    write_chunk(
+        &value,
+        &mut chunk,
+        Compression::Lz4,
+        DEFAULT_COMPRESSION_LEVEL,
+    )
+    .unwrap();
+    assert_eq!(chunk[4], 4);
+    assert_eq!(&chunk[5..13], b"LZ4Block");
+    assert_eq!(
+        read_chunk(&chunk[..]).unwrap(),
+        (value.clone(), Compression::Lz4)
+    );
+
+    let (_, read_value, compression, endian, _) =
+        commandblock::nbt::read_auto_from_reader(&chunk[5..]).unwrap();
+    assert_eq!(read_value, value);
+    assert_eq!((compression, endian), (Compression::Lz4, Endian::Big));
+
+    // Corrupting a compressed byte must fail the block checksum or the decompression.
+    let last = chunk.len() - 30;
+    chunk[last] ^= 0xFF;
+    assert!(read_chunk(&chunk[..]).is_err());
+}
+
+#[cfg(not(feature = "lz4"))]
+#[test]
+fn test_lz4_requires_feature() {
+    let mut data = Vec::new();
+    assert!(matches!(
+        write_chunk(NbtValue::new(), &mut data, Compression::Lz4, 0),
+        Err(NbtError::UnsupportedCompression(_))
+    ));
+}
@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use commandblock::nbt::{
+    parse_snbt, read_from_file, Compression, Endian, NbtChange, NbtError, NbtList, NbtPath,
+    NbtValue,
+};
+
+fn java_level() -> NbtValue {
+    let (_, value) = read_from_file(
+        PathBuf::from("tests/data/java_level.dat"),
+        Compression::Gzip,
+        Endian::Big,
+    )
+    .unwrap();
+    value
+}
+
+fn path(path: &str) -> NbtPath {
+    path.parse().unwrap()
+}
+
+#[test]
+fn test_diff_identical_values() {
+    let level = java_level();
+    assert!(level.diff(&level.clone()).is_empty());
+}
+
+#[test]
+fn test_diff_reports_changes() {
+    let old = parse_snbt(r#"{a:1,b:"x",c:{d:1b},list:[1,2,3],gone:1s}"#).unwrap();
+    let new = parse_snbt(r#"{a:1L,b:"y",c:{d:1b,e:2b},list:[1,5],added:[I;1]}"#).unwrap();
+
+    let changes = old.diff(&new);
+    assert_eq!(
+        changes,
+        vec![
+            NbtChange::TypeChanged {
+                path: path("a"),
+                old: NbtValue::Int(1),
+                new: NbtValue::Long(1),
+            },
+            NbtChange::Added {
+                path: path("added"),
+                value: NbtValue::IntArray(vec![1]),
+            },
+            NbtChange::Changed {
+                path: path("b"),
+                old: NbtValue::from("x"),
+                new: NbtValue::from("y"),
+            },
+            NbtChange::Added {
+                path: path("c.e"),
+                value: NbtValue::Byte(2),
+            },
+            NbtChange::Removed {
+                path: path("gone"),
+                value: NbtValue::Short(1),
+            },
+            NbtChange::Changed {
+                path: path("list[1]"),
+                old: NbtValue::Int(2),
+                new: NbtValue::Int(5),
+            },
+            NbtChange::Removed {
+                path: path("list[2]"),
+                value: NbtValue::Int(3),
+            },
+        ]
+    );
+
+    assert_eq!(changes[0].to_string(), "~ a: 1 (Int) -> 1L (Long)");
+    assert_eq!(changes[1].to_string(), "+ added: [I;1]");
+    assert_eq!(changes[4].to_string(), "- gone: 1s");
+}
+
+#[test]
+fn test_diff_and_patch_level_data() {
+    let old = java_level();
+    let mut new = old.clone();
+
+    new.set_path(&path("Data.LevelName"), "Renamed").unwrap();
+    new.set_path(&path("Data.DayTime"), 5).unwrap();
+    new.set_path(&path("Data.Player.Inventory[].Count"), NbtValue::Byte(64))
+        .unwrap();
+    new.remove_path(&path("Data.Player.Inventory[0]"));
+    new.remove_path(&path("Data.GameRules.pvp"));
+    new.set_path(
+        &path("Data.Player.Tags"),
+        NbtValue::from(vec![NbtValue::Int(1)]),
+    )
+    .unwrap();
+
+    let changes = old.diff(&new);
+    assert!(changes.contains(&NbtChange::TypeChanged {
+        path: path("Data.DayTime"),
+        old: NbtValue::Long(180187),
+        new: NbtValue::Int(5),
+    }));
+    assert!(changes.contains(&NbtChange::Removed {
+        path: path("Data.GameRules.pvp"),
+        value: NbtValue::from("true"),
+    }));
+
+    let mut patched = old.clone();
+    patched.apply_patch(&changes).unwrap();
+    assert_eq!(patched, new);
+}
+
+#[test]
+fn test_apply_patch_missing_path() {
+    let mut value = parse_snbt("{a:1}").unwrap();
+    let patch = vec![NbtChange::Changed {
+        path: path("b.c"),
+        old: NbtValue::Int(1),
+        new: NbtValue::Int(2),
+    }];
+    assert!(matches!(
+        value.apply_patch(&patch),
+        Err(NbtError::PathNotFound(_))
+    ));
+
+    let root = NbtValue::Int(2);
+    let changes = value.diff(&root);
+    value.apply_patch(&changes).unwrap();
+    assert_eq!(value, root);
+}
+
+#[test]
+fn test_diff_empty_list_element_type() {
+    let mut old = NbtValue::new();
+    old.insert("Items".to_string(), NbtValue::List(NbtList::new(0x00)));
+    let mut new = NbtValue::new();
+    new.insert("Items".to_string(), NbtValue::List(NbtList::new(0x0A)));
+
+    let changes = old.diff(&new);
+    assert_eq!(changes.len(), 1);
+
+    old.apply_patch(&changes).unwrap();
+    assert_eq!(old, new);
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use commandblock::{
+    java::JavaLevelData,
+    nbt::{read_from_file, Compression, Endian, NbtError},
+};
+
+#[test]
+fn test_level_data() {
+    let source = PathBuf::from("tests/data/java_level.dat");
+    let (_, root) = read_from_file(source.clone(), Compression::Gzip, Endian::Big).unwrap();
+    let data = root.get("Data").unwrap().clone();
+
+    let mut level = JavaLevelData::read_from_file(source).unwrap();
+    assert_eq!(level.data_version, Some(3218));
+    assert_eq!(level.version.as_ref().unwrap().name, "1.19.3");
+    assert_eq!(level.level_name, "Fucking Dorks");
+    assert_eq!((level.spawn_x, level.spawn_y, level.spawn_z), (0, 135, 0));
+    assert_eq!((level.time, level.day_time), (135000, 180187));
+    assert!(!level.raining && !level.thundering);
+    assert_eq!(level.difficulty, Some(2));
+    assert_eq!(level.game_rules.as_ref().unwrap()["randomTickSpeed"], "3");
+    assert_eq!(
+        level.world_gen_settings.as_ref().unwrap().seed,
+        -5433075525648910298
+    );
+    assert_eq!(
+        level.data_packs.as_ref().unwrap().enabled,
+        ["vanilla", "fabric"]
+    );
+    assert!(level.other.contains_key("Player"));
+    assert_eq!(level.to_nbt(), data);
+
+    level.level_name = "Renamed".to_string();
+    level.raining = true;
+    level
+        .game_rules
+        .as_mut()
+        .unwrap()
+        .insert("keepInventory".to_string(), "true".to_string());
+    let path = std::env::temp_dir().join("commandblock_java_level.dat");
+    level.write_to_file(path.clone()).unwrap();
+
+    let (name, root) = read_from_file(path.clone(), Compression::Gzip, Endian::Big).unwrap();
+    assert_eq!(name, "");
+    assert!(root.get("Data").unwrap().get("Player").is_some());
+    assert_eq!(JavaLevelData::read_from_file(path).unwrap(), level);
+
+    // Fields the model does not know are kept, and a missing GameRules compound is not written back.
+    let mut stripped = data.clone();
+    stripped.remove("GameRules");
+    stripped
+        .get_mut("DataPacks")
+        .unwrap()
+        .insert("Custom".to_string(), 1);
+    let level = JavaLevelData::from_nbt(stripped.clone()).unwrap();
+    assert_eq!(level.game_rules, None);
+    assert_eq!(
+        level.data_packs.as_ref().unwrap().other.get("Custom"),
+        Some(&1.into())
+    );
+    assert_eq!(level.to_nbt(), stripped);
+
+    let mut data = data;
+    data.get_mut("GameRules")
+        .unwrap()
+        .insert("keepInventory".to_string(), 1);
+    let error = JavaLevelData::from_nbt(data).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Type mismatch: expected tag 8, found tag 3 in GameRules.keepInventory"
+    );
+    assert!(matches!(
+        error.without_context(),
+        NbtError::TypeMismatch(8, 3)
+    ));
+}
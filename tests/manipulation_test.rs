@@ -1,6 +1,8 @@
+#![allow(clippy::assertions_on_constants)]
+
 use std::path::PathBuf;
 
-use commandblock::nbt::{read_from_file, Compression, NbtValue};
+use commandblock::nbt::{read_from_file, Compression, NbtError, NbtList, NbtValue};
 
 #[test]
 fn test_create_compound() {
@@ -111,3 +113,71 @@ fn test_reading_and_manipulating_nbtvalue() {
         }
     }
 }
+
+#[test]
+fn test_non_panicking_compound_api() {
+    let mut compound = NbtValue::new();
+    assert_eq!(compound.try_insert("key1".to_string(), 1).unwrap(), None);
+    assert_eq!(
+        compound.try_insert("key1".to_string(), 2).unwrap(),
+        Some(NbtValue::Int(1))
+    );
+    assert_eq!(compound.try_get("key1"), Some(&NbtValue::Int(2)));
+    assert_eq!(compound.try_len(), Some(1));
+    assert_eq!(compound.try_keys().map(|keys| keys.len()), Some(1));
+    assert_eq!(compound.try_remove("key1"), Some(NbtValue::Int(2)));
+
+    let mut not_compound = NbtValue::Int(0);
+    assert!(matches!(
+        not_compound.try_insert("key".to_string(), 1),
+        Err(NbtError::TypeMismatch(0x0A, 0x03))
+    ));
+    assert_eq!(not_compound.try_get("key"), None);
+    assert_eq!(not_compound.try_get_mut("key"), None);
+    assert_eq!(not_compound.try_remove("key"), None);
+    assert_eq!(not_compound.try_len(), None);
+    assert!(not_compound.try_keys().is_none());
+    assert!(not_compound.try_values().is_none());
+    assert!(not_compound.try_iter().is_none());
+    assert!(not_compound.try_iter_mut().is_none());
+}
+
+#[test]
+fn test_list_api() {
+    let mut list = NbtValue::List(NbtList::default());
+    list.push(1).unwrap();
+    list.push(NbtValue::Int(2)).unwrap();
+    assert!(matches!(
+        list.push("three"),
+        Err(NbtError::InvalidListType(0x08))
+    ));
+
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.try_len(), Some(2));
+    assert_eq!(list.get_index(1), Some(&NbtValue::Int(2)));
+    assert_eq!(list.get_index(2), None);
+
+    if let Some(value) = list.get_index_mut(0) {
+        *value = NbtValue::Int(10);
+    }
+    assert_eq!(list.remove_index(0), Some(NbtValue::Int(10)));
+    assert_eq!(list.remove_index(5), None);
+    assert_eq!(list.as_list(), Some(&NbtList::from(vec![NbtValue::Int(2)])));
+
+    let mut compound = NbtValue::new();
+    assert!(matches!(
+        compound.push(1),
+        Err(NbtError::TypeMismatch(0x09, 0x0A))
+    ));
+    assert_eq!(compound.get_index(0), None);
+
+    let mut compounds = NbtValue::List(NbtList::new(0x0A));
+    assert!(matches!(
+        compounds.push(1),
+        Err(NbtError::InvalidListType(0x03))
+    ));
+    compounds.push(NbtValue::new()).unwrap();
+    compounds.remove_index(0);
+    assert_eq!(compounds.as_list().unwrap().element_type(), 0x0A);
+    assert_ne!(compounds, NbtValue::List(NbtList::default()));
+}
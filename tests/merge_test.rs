@@ -0,0 +1,88 @@
+use commandblock::nbt::{
+    parse_snbt, ListMergeStrategy, MergeStrategy, NbtError, NbtValue, TypeConflictStrategy,
+};
+
+#[test]
+fn test_merge_default_strategy() {
+    let mut value = parse_snbt(r#"{a:1,b:{c:1b,d:"x"},list:[1,2]}"#).unwrap();
+    let other = parse_snbt(r#"{a:2L,b:{d:"y",e:1s},list:[3]}"#).unwrap();
+
+    value.merge(other, &MergeStrategy::default()).unwrap();
+    assert_eq!(
+        value,
+        parse_snbt(r#"{a:2L,b:{c:1b,d:"y",e:1s},list:[3]}"#).unwrap()
+    );
+}
+
+#[test]
+fn test_merge_append_lists() {
+    let mut value = parse_snbt(r#"{Enabled:["vanilla"]}"#).unwrap();
+    let other = parse_snbt(r#"{Enabled:["fabric"]}"#).unwrap();
+
+    let strategy = MergeStrategy::new(ListMergeStrategy::Append, TypeConflictStrategy::Error);
+    value.merge(other, &strategy).unwrap();
+    assert_eq!(
+        value,
+        parse_snbt(r#"{Enabled:["vanilla","fabric"]}"#).unwrap()
+    );
+
+    let mut empty = parse_snbt("{Enabled:[]}").unwrap();
+    empty
+        .merge(parse_snbt("{Enabled:[1]}").unwrap(), &strategy)
+        .unwrap();
+    assert_eq!(empty, parse_snbt("{Enabled:[1]}").unwrap());
+}
+
+#[test]
+fn test_merge_lists_by_key() {
+    let mut value = parse_snbt(
+        r#"{Inventory:[{Slot:0b,id:"minecraft:stone",Count:1b},{Slot:1b,id:"minecraft:dirt",Count:2b}]}"#,
+    )
+    .unwrap();
+    let other =
+        parse_snbt(r#"{Inventory:[{Slot:1b,Count:64b},{Slot:2b,id:"minecraft:torch",Count:1b}]}"#)
+            .unwrap();
+
+    let strategy = MergeStrategy::new(
+        ListMergeStrategy::MergeByKey("Slot".to_string()),
+        TypeConflictStrategy::Error,
+    );
+    value.merge(other, &strategy).unwrap();
+    assert_eq!(
+        value,
+        parse_snbt(
+            r#"{Inventory:[{Slot:0b,id:"minecraft:stone",Count:1b},{Slot:1b,id:"minecraft:dirt",Count:64b},{Slot:2b,id:"minecraft:torch",Count:1b}]}"#,
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_merge_type_conflicts() {
+    let original = parse_snbt(r#"{a:1,b:{c:1b},list:["x"]}"#).unwrap();
+
+    let error = MergeStrategy::new(ListMergeStrategy::Replace, TypeConflictStrategy::Error);
+    let mut value = original.clone();
+    assert!(matches!(
+        value.merge(parse_snbt(r#"{z:1,b:{c:1s}}"#).unwrap(), &error),
+        Err(NbtError::TypeMismatch(0x01, 0x02))
+    ));
+    assert_eq!(value, original);
+
+    let append = MergeStrategy::new(ListMergeStrategy::Append, TypeConflictStrategy::Error);
+    assert!(matches!(
+        value.merge(parse_snbt("{list:[1]}").unwrap(), &append),
+        Err(NbtError::InvalidListType(0x03))
+    ));
+    assert_eq!(value, original);
+
+    let overwrite = MergeStrategy::new(ListMergeStrategy::Append, TypeConflictStrategy::Overwrite);
+    value
+        .merge(parse_snbt(r#"{a:"one",list:[1]}"#).unwrap(), &overwrite)
+        .unwrap();
+    assert_eq!(value.get("a"), Some(&NbtValue::from("one")));
+    assert_eq!(
+        value.get("list"),
+        Some(&NbtValue::from(vec![NbtValue::Int(1)]))
+    );
+}
@@ -0,0 +1,208 @@
+use std::path::PathBuf;
+
+use commandblock::nbt::{
+    parse_snbt, read_from_file, Compression, Endian, NbtError, NbtList, NbtPath, NbtPathNode,
+    NbtValue,
+};
+
+fn java_level() -> NbtValue {
+    let (_, value) = read_from_file(
+        PathBuf::from("tests/data/java_level.dat"),
+        Compression::Gzip,
+        Endian::Big,
+    )
+    .unwrap();
+    value
+}
+
+#[test]
+fn test_parse_path() {
+    let path: NbtPath = r#"Data.Player.Inventory[0].tag{Damage:58}.Damage"#.parse().unwrap();
+    assert_eq!(
+        path.nodes(),
+        &[
+            NbtPathNode::Key("Data".to_string()),
+            NbtPathNode::Key("Player".to_string()),
+            NbtPathNode::Key("Inventory".to_string()),
+            NbtPathNode::Index(0),
+            NbtPathNode::MatchKey("tag".to_string(), parse_snbt("{Damage:58}").unwrap()),
+            NbtPathNode::Key("Damage".to_string()),
+        ]
+    );
+
+    let path: NbtPath = r#"{a:1b}."quoted key"[][{id:"minecraft:stone"}][-1]"#
+        .parse()
+        .unwrap();
+    assert_eq!(path.nodes().len(), 5);
+    assert_eq!(
+        path.to_string(),
+        r#"{a:1b}."quoted key"[][{id:"minecraft:stone"}][-1]"#
+    );
+
+    assert!(matches!(
+        NbtPath::parse("a..b"),
+        Err(NbtError::InvalidPath(_))
+    ));
+    assert!(matches!(
+        NbtPath::parse("a[x]"),
+        Err(NbtError::InvalidPath(_))
+    ));
+    assert!(matches!(
+        NbtPath::parse("a.{b:1}"),
+        Err(NbtError::InvalidPath(_))
+    ));
+    assert!(matches!(NbtPath::parse(""), Err(NbtError::InvalidPath(_))));
+}
+
+#[test]
+fn test_get_path_on_level_data() {
+    let level = java_level();
+
+    let pickaxe = level
+        .get_path(&"Data.Player.Inventory[0].id".parse().unwrap())
+        .unwrap();
+    assert_eq!(*pickaxe, NbtValue::from("minecraft:iron_pickaxe"));
+
+    let last = level
+        .get_path(&"Data.Player.Inventory[-1].id".parse().unwrap())
+        .unwrap();
+    assert_eq!(*last, NbtValue::from("minecraft:iron_helmet"));
+
+    let coal = level
+        .get_path(
+            &r#"Data.Player.Inventory[{id:"minecraft:coal"}].Count"#
+                .parse()
+                .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(*coal, NbtValue::Byte(18));
+
+    let damaged = level.query_all(&"Data.Player.Inventory[].tag.Damage".parse().unwrap());
+    assert_eq!(damaged.len(), 9);
+
+    let unbroken = level.query_all(&"Data.Player.Inventory[{tag:{Damage:0}}]".parse().unwrap());
+    assert_eq!(unbroken.len(), 4);
+
+    assert_eq!(level.get_path(&"Data.Missing".parse().unwrap()), None);
+    assert_eq!(level.get_path(&"Data.LevelName[0]".parse().unwrap()), None);
+}
+
+#[test]
+fn test_set_and_remove_path() {
+    let mut level = java_level();
+
+    let count = level
+        .set_path(
+            &"Data.Player.Inventory[].Count".parse().unwrap(),
+            NbtValue::Byte(64),
+        )
+        .unwrap();
+    assert_eq!(count, 18);
+
+    let path: NbtPath = "Data.Player.Inventory[0].tag.display.Name".parse().unwrap();
+    level.set_path(&path, "\"Digger\"").unwrap();
+    assert_eq!(
+        level.get_path(&path).as_deref(),
+        Some(&NbtValue::from("\"Digger\""))
+    );
+
+    if let Some(NbtValue::Int(level)) = level.get_path_mut(&"Data.Player.XpLevel".parse().unwrap())
+    {
+        *level = 30;
+    }
+    assert_eq!(
+        level
+            .get_path(&"Data.Player.XpLevel".parse().unwrap())
+            .as_deref(),
+        Some(&NbtValue::Int(30))
+    );
+
+    assert!(matches!(
+        level.set_path(&"Data.Player.Inventory[0]".parse().unwrap(), 1),
+        Err(NbtError::InvalidListType(0x03))
+    ));
+
+    let removed =
+        level.remove_path(&r#"Data.Player.Inventory[{id:"minecraft:coal"}]"#.parse().unwrap());
+    assert_eq!(removed.len(), 1);
+    assert_eq!(
+        level
+            .get_path(&"Data.Player.Inventory".parse().unwrap())
+            .map(|inventory| inventory.len()),
+        Some(17)
+    );
+
+    let removed = level.remove_path(&"Data.GameRules".parse().unwrap());
+    assert_eq!(removed.len(), 1);
+    assert_eq!(level.get_path(&"Data.GameRules".parse().unwrap()), None);
+
+    // A filter only creates an element in lists that can hold compounds.
+    let mut value = NbtValue::new();
+    value.insert("ints".to_string(), NbtList::new(0x03));
+    value.insert("untyped".to_string(), NbtList::new(0x00));
+    let path: NbtPath = "ints[{id:1b}].count".parse().unwrap();
+    assert_eq!(value.set_path(&path, 1).unwrap(), 0);
+    assert_eq!(value.get("ints"), Some(&NbtValue::List(NbtList::new(0x03))));
+    let path: NbtPath = "untyped[{id:1b}].count".parse().unwrap();
+    assert_eq!(value.set_path(&path, 1).unwrap(), 1);
+    assert_eq!(
+        value.get("untyped"),
+        Some(&parse_snbt("[{id:1b,count:1}]").unwrap())
+    );
+}
+
+#[test]
+fn test_array_paths() {
+    let mut value = parse_snbt("{UUID:[I;1,2,3,4],Bytes:[B;1b,0b,1b],Longs:[L;7L]}").unwrap();
+
+    let get = |value: &NbtValue, path: &str| {
+        value
+            .get_path(&path.parse().unwrap())
+            .map(|v| v.into_owned())
+    };
+    assert_eq!(get(&value, "UUID[0]"), Some(NbtValue::Int(1)));
+    assert_eq!(get(&value, "UUID[-1]"), Some(NbtValue::Int(4)));
+    assert_eq!(get(&value, "UUID[4]"), None);
+    assert_eq!(get(&value, "UUID[-5]"), None);
+    assert_eq!(get(&value, "UUID[0].x"), None);
+    assert_eq!(get(&value, "Longs[0]"), Some(NbtValue::Long(7)));
+    assert_eq!(value.query_all(&"UUID[]".parse().unwrap()).len(), 4);
+    assert_eq!(
+        value.query_all(&"Bytes[]".parse().unwrap())[1].as_ref(),
+        &NbtValue::Byte(0)
+    );
+    assert_eq!(value.get_path_mut(&"UUID[0]".parse().unwrap()), None);
+
+    assert_eq!(value.set_path(&"UUID[-2]".parse().unwrap(), 30).unwrap(), 1);
+    assert_eq!(get(&value, "UUID[2]"), Some(NbtValue::Int(30)));
+    assert_eq!(
+        value
+            .set_path(&"Bytes[]".parse().unwrap(), NbtValue::Byte(5))
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        value.get("Bytes"),
+        Some(&NbtValue::ByteArray(vec![5, 5, 5]))
+    );
+    assert_eq!(
+        value.set_path(&"Longs[1]".parse().unwrap(), 1i64).unwrap(),
+        0
+    );
+    assert!(matches!(
+        value.set_path(&"UUID[0]".parse().unwrap(), NbtValue::Long(1)),
+        Err(NbtError::TypeMismatch(0x03, 0x04))
+    ));
+
+    assert_eq!(
+        value.remove_path(&"UUID[-1]".parse().unwrap()),
+        vec![NbtValue::Int(4)]
+    );
+    assert_eq!(
+        value.remove_path(&"UUID[0]".parse().unwrap()),
+        vec![NbtValue::Int(1)]
+    );
+    assert_eq!(value.get("UUID"), Some(&NbtValue::IntArray(vec![2, 30])));
+    assert_eq!(value.remove_path(&"Bytes[]".parse().unwrap()).len(), 3);
+    assert_eq!(value.get("Bytes"), Some(&NbtValue::ByteArray(Vec::new())));
+}
@@ -0,0 +1,317 @@
+use std::path::PathBuf;
+
+use commandblock::{
+    nbt::{parse_snbt, read_from_file, Compression, Endian, NbtError, NbtValue},
+    player::{Edition, EffectId, ItemFormat, ItemStack, PlayerData},
+};
+
+#[test]
+fn test_java_player() {
+    let path = PathBuf::from("tests/data/java_level.dat");
+    let (_, root) = read_from_file(path, Compression::Gzip, Endian::Big).unwrap();
+    let value = root.get("Data").unwrap().get("Player").unwrap().clone();
+
+    let mut player = PlayerData::from_nbt(value.clone(), Edition::Java).unwrap();
+    assert_eq!(player.position[1], 63.0);
+    assert_eq!((player.xp_level, player.xp_total), (11, Some(211)));
+    assert_eq!(player.inventory.len(), 18);
+    assert_eq!(player.inventory[0].id, "minecraft:iron_pickaxe");
+    assert_eq!(player.inventory[0].format, ItemFormat::Java);
+    assert_eq!(player.inventory[5].count, 18);
+    assert_eq!(player.inventory[17].slot, Some(103));
+    assert!(player.ender_chest.is_empty());
+    assert!(player.effects.is_empty());
+    assert!(player.other.contains_key("recipeBook"));
+    assert_eq!(player.to_nbt(), value);
+
+    let mut item = ItemStack::new(ItemFormat::Java, "minecraft:diamond", 64);
+    item.slot = Some(7);
+    player.inventory.push(item.clone());
+    player.xp_level = 30;
+
+    let path = std::env::temp_dir().join("commandblock_player.dat");
+    player.write_to_file(path.clone()).unwrap();
+    let read = PlayerData::read_from_file(path).unwrap();
+    assert_eq!(read.inventory.last(), Some(&item));
+    assert_eq!(read, player);
+}
+
+#[test]
+fn test_bedrock_player() {
+    let value = parse_snbt(
+        r#"{
+            Pos: [0.5f, 65.62f, -3.5f],
+            Rotation: [90.0f, 0.0f],
+            PlayerLevel: 3,
+            PlayerLevelProgress: 0.25f,
+            Inventory: [
+                {Name: "minecraft:torch", Count: 12b, Damage: 0s, Slot: 0b, WasPickedUp: 0b},
+                {Name: "", Count: 0b, Damage: 0s, Slot: 1b, WasPickedUp: 0b}
+            ],
+            EnderChestInventory: [
+                {Name: "minecraft:diamond_sword", Count: 1b, Damage: 0s, Slot: 0b, WasPickedUp: 0b, tag: {Damage: 5}}
+            ],
+            ActiveEffects: [{Id: 1b, Amplifier: 1b, Duration: 600, Ambient: 0b, ShowParticles: 1b}],
+            Armor: []
+        }"#,
+    )
+    .unwrap();
+
+    let player = PlayerData::from_nbt(value.clone(), Edition::Bedrock).unwrap();
+    assert_eq!(player.position[0], 0.5);
+    assert_eq!(player.rotation, [90.0, 0.0]);
+    assert_eq!((player.xp_level, player.xp_total), (3, None));
+    assert_eq!(player.inventory[0].format, ItemFormat::Bedrock);
+    assert_eq!(player.inventory[0].id, "minecraft:torch");
+    assert!(player.inventory[1].is_empty());
+    assert!(player.ender_chest[0].tag.is_some());
+    assert_eq!(player.effects[0].id, EffectId::Numeric(1));
+    assert_eq!(player.effects[0].duration, 600);
+    assert!(player.other.contains_key("Armor"));
+    assert_eq!(player.to_nbt(), value);
+
+    let record = player.to_bedrock_record().unwrap();
+    assert_eq!(PlayerData::from_bedrock_record(&record).unwrap(), player);
+}
+
+#[test]
+fn test_invalid_player() {
+    let value =
+        parse_snbt("{Pos: [0.0d, 1.0d], Rotation: [0.0f, 0.0f], XpLevel: 0, XpP: 0.0f}").unwrap();
+    let error = PlayerData::from_nbt(value, Edition::Java).unwrap_err();
+    assert!(matches!(
+        error.without_context(),
+        NbtError::InvalidListLength(2)
+    ));
+    assert_eq!(error.to_string(), "Invalid list length: 2 in Pos");
+
+    let value = parse_snbt("{Count: 1b}").unwrap();
+    assert!(matches!(
+        ItemStack::from_nbt(value).unwrap_err(),
+        NbtError::PathNotFound(key) if key == "id"
+    ));
+}
+
+#[test]
+fn test_item_components() {
+    let value = parse_snbt(
+        r#"{
+            id: "minecraft:diamond_sword",
+            count: 1,
+            Slot: 0b,
+            components: {
+                "minecraft:custom_name": '{"text":"Blade"}',
+                "minecraft:lore": ['"first"', '"second"'],
+                "minecraft:enchantments": {levels: {"minecraft:sharpness": 5}, show_in_tooltip: 0b},
+                "minecraft:damage": 12,
+                "minecraft:unbreakable": {}
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let mut item = ItemStack::from_nbt(value.clone()).unwrap();
+    assert_eq!(item.format, ItemFormat::Components);
+    assert_eq!(item.count, 1);
+    assert_eq!(item.custom_name(), Some(r#"{"text":"Blade"}"#));
+    assert_eq!(item.lore(), [r#""first""#, r#""second""#]);
+    assert_eq!(item.enchantments()["minecraft:sharpness"], 5);
+    assert_eq!(item.damage(), 12);
+    assert_eq!(item.to_nbt(), value);
+
+    item.set_enchantment("minecraft:unbreaking", 3).unwrap();
+    item.set_damage(0);
+    item.set_custom_name(None);
+    let components = item.components.as_ref().unwrap();
+    let enchantments = components.get("minecraft:enchantments").unwrap();
+    assert_eq!(
+        enchantments.get("show_in_tooltip"),
+        Some(&NbtValue::Byte(0))
+    );
+    assert_eq!(item.enchantments().len(), 2);
+    assert!(!components.contains_key("minecraft:damage"));
+    assert!(!components.contains_key("minecraft:custom_name"));
+
+    // Since 1.21.5 the levels are the enchantments component itself.
+    let value = parse_snbt(
+        r#"{id: "minecraft:bow", components: {"minecraft:enchantments": {"minecraft:power": 2}}}"#,
+    )
+    .unwrap();
+    let mut item = ItemStack::from_nbt(value).unwrap();
+    assert_eq!(item.count, 1);
+    assert_eq!(item.enchantments()["minecraft:power"], 2);
+    item.set_enchantment("minecraft:power", 4).unwrap();
+    let components = item.components.as_ref().unwrap();
+    assert_eq!(
+        components
+            .get("minecraft:enchantments")
+            .unwrap()
+            .get("minecraft:power"),
+        Some(&NbtValue::Int(4))
+    );
+}
+
+#[test]
+fn test_convert_legacy_item() {
+    let value = parse_snbt(
+        r#"{
+            id: "minecraft:leather_chestplate",
+            Count: 1b,
+            Slot: 102b,
+            tag: {
+                Damage: 7,
+                display: {Name: '{"text":"Coat"}', Lore: ['"warm"'], color: 16711680},
+                Enchantments: [{id: "minecraft:protection", lvl: 4s}],
+                owner: "support"
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let item = ItemStack::from_nbt(value.clone()).unwrap();
+    assert_eq!(item.format, ItemFormat::Java);
+    assert_eq!(item.custom_name(), Some(r#"{"text":"Coat"}"#));
+    assert_eq!(item.lore(), [r#""warm""#]);
+    assert_eq!(item.enchantments()["minecraft:protection"], 4);
+    assert_eq!(item.damage(), 7);
+
+    let converted = item.clone().into_components().unwrap();
+    assert_eq!(converted.format, ItemFormat::Components);
+    assert!(converted.tag.is_none());
+    assert_eq!(converted.custom_name(), item.custom_name());
+    assert_eq!(converted.lore(), item.lore());
+    assert_eq!(converted.enchantments(), item.enchantments());
+    assert_eq!(converted.damage(), 7);
+    let components = converted.components.as_ref().unwrap();
+    assert_eq!(
+        components.get("minecraft:dyed_color"),
+        Some(&parse_snbt("{rgb: 16711680}").unwrap())
+    );
+    assert_eq!(
+        components.get("minecraft:custom_data"),
+        Some(&parse_snbt(r#"{owner: "support"}"#).unwrap())
+    );
+
+    let legacy = converted.into_legacy().unwrap();
+    assert_eq!(legacy.to_nbt(), value);
+
+    let plain = ItemStack::new(ItemFormat::Java, "minecraft:stone", 3);
+    let converted = plain.clone().into_components().unwrap();
+    assert!(converted.components.is_none());
+    assert_eq!(converted.into_legacy().unwrap(), plain);
+}
+
+#[test]
+fn test_convert_hidden_tooltips() {
+    let value = parse_snbt(
+        r#"{
+            id: "minecraft:enchanted_book",
+            Count: 1b,
+            tag: {
+                RepairCost: 3,
+                CustomModelData: 42,
+                Unbreakable: 1b,
+                HideFlags: 38,
+                StoredEnchantments: [{id: "minecraft:mending", lvl: 1s}],
+                display: {MapColor: 4210752}
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let converted = ItemStack::from_nbt(value.clone())
+        .unwrap()
+        .into_components()
+        .unwrap();
+    let components = converted.components.as_ref().unwrap();
+    let expected = parse_snbt(
+        r#"{
+            "minecraft:repair_cost": 3,
+            "minecraft:custom_model_data": 42,
+            "minecraft:unbreakable": {show_in_tooltip: 0b},
+            "minecraft:stored_enchantments": {levels: {"minecraft:mending": 1}, show_in_tooltip: 0b},
+            "minecraft:hide_additional_tooltip": {},
+            "minecraft:attribute_modifiers": {modifiers: [], show_in_tooltip: 0b},
+            "minecraft:map_color": 4210752
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(NbtValue::from(components.clone()), expected);
+    assert_eq!(converted.into_legacy().unwrap().to_nbt(), value);
+}
+
+#[test]
+fn test_convert_unsupported_item() {
+    let value = parse_snbt(
+        r#"{id: "minecraft:stone_pickaxe", Count: 1b, tag: {CanDestroy: ["minecraft:stone"]}}"#,
+    )
+    .unwrap();
+    let error = ItemStack::from_nbt(value)
+        .unwrap()
+        .into_components()
+        .unwrap_err();
+    assert!(matches!(error, NbtError::UnsupportedConversion(_)));
+    assert_eq!(
+        error.to_string(),
+        "Unsupported conversion: the CanDestroy tag has no component this conversion builds"
+    );
+
+    for tag in [
+        r#"{BlockStateTag: {facing: "north"}}"#,
+        r#"{instrument: "minecraft:ponder_goat_horn"}"#,
+        r#"{DebugProperty: {"minecraft:oak_stairs": "half"}}"#,
+    ] {
+        let mut value = parse_snbt(r#"{id: "minecraft:stone", Count: 1b}"#).unwrap();
+        value.insert("tag".to_string(), parse_snbt(tag).unwrap());
+        assert!(matches!(
+            ItemStack::from_nbt(value).unwrap().into_components(),
+            Err(NbtError::UnsupportedConversion(_))
+        ));
+    }
+
+    let value = parse_snbt(r#"{id: "minecraft:stone", Count: 1b, tag: {HideFlags: 8}}"#).unwrap();
+    assert!(matches!(
+        ItemStack::from_nbt(value).unwrap().into_components(),
+        Err(NbtError::UnsupportedConversion(_))
+    ));
+
+    let value = parse_snbt(
+        r#"{id: "minecraft:stone", count: 1, components: {"minecraft:max_stack_size": 16}}"#,
+    )
+    .unwrap();
+    let error = ItemStack::from_nbt(value)
+        .unwrap()
+        .into_legacy()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unsupported conversion: the minecraft:max_stack_size component has no tag this conversion builds"
+    );
+}
+
+#[test]
+fn test_bedrock_item() {
+    let value = parse_snbt(
+        r#"{Name: "minecraft:diamond_sword", Count: 1b, Damage: 0s, WasPickedUp: 0b,
+            tag: {Damage: 3, display: {Name: "Sword"}, ench: [{id: 9s, lvl: 2s}]}}"#,
+    )
+    .unwrap();
+
+    let mut item = ItemStack::from_nbt(value.clone()).unwrap();
+    assert_eq!(item.format, ItemFormat::Bedrock);
+    assert_eq!(item.custom_name(), Some("Sword"));
+    assert_eq!(item.enchantments()["9"], 2);
+    assert_eq!(item.damage(), 3);
+    assert_eq!(item.to_nbt(), value);
+
+    assert!(matches!(
+        item.set_enchantment("minecraft:sharpness", 1),
+        Err(NbtError::InvalidBedrockData(_))
+    ));
+    item.set_enchantment("9", 0).unwrap();
+    assert!(item.enchantments().is_empty());
+    assert!(matches!(
+        item.clone().into_components(),
+        Err(NbtError::UnsupportedConversion(_))
+    ));
+}
@@ -1,6 +1,11 @@
+#![allow(clippy::assertions_on_constants)]
+
 use std::path::PathBuf;
 
-use commandblock::nbt::{read_from_file, Compression, NbtReader, NbtValue};
+use commandblock::nbt::{
+    read_auto, read_auto_from_reader, read_from_file, read_from_reader, read_from_reader_lenient,
+    write_to_writer, Compression, Endian, NbtError, NbtReader, NbtValue, NbtWriter,
+};
 
 #[test]
 fn test_parse_nbt_value_end() {
@@ -166,11 +171,11 @@ fn test_parse_nbt_value_list() {
 
     assert_eq!(
         java_result,
-        NbtValue::List(vec![NbtValue::Byte(127), NbtValue::Byte(127)])
+        NbtValue::from(vec![NbtValue::Byte(127), NbtValue::Byte(127)])
     );
     assert_eq!(
         bedrock_result,
-        NbtValue::List(vec![NbtValue::Byte(127), NbtValue::Byte(127)])
+        NbtValue::from(vec![NbtValue::Byte(127), NbtValue::Byte(127)])
     );
 }
 
@@ -302,3 +307,211 @@ fn test_read_from_dat_file() {
         }
     }
 }
+
+#[test]
+fn test_read_auto_detects_format() {
+    let (_, java, compression, endian, header) =
+        read_auto(PathBuf::from("tests/data/java_level.dat")).unwrap();
+    assert_eq!(
+        (compression, endian, header),
+        (Compression::Gzip, Endian::Big, false)
+    );
+
+    let (_, bedrock, compression, endian, header) =
+        read_auto(PathBuf::from("tests/data/bedrock_level.dat")).unwrap();
+    assert_eq!(
+        (compression, endian, header),
+        (Compression::Uncompressed, Endian::Little, true)
+    );
+
+    let (_, expected) = read_from_file(
+        PathBuf::from("tests/data/bedrock_level.dat"),
+        Compression::Uncompressed,
+        Endian::Little,
+    )
+    .unwrap();
+    assert_eq!(bedrock, expected);
+
+    for (value, endian) in [(&java, Endian::Big), (&bedrock, Endian::Little)] {
+        for compression in [
+            Compression::Uncompressed,
+            Compression::Gzip,
+            Compression::Zlib,
+        ] {
+            let mut data = Vec::new();
+            write_to_writer(Some(""), value, &mut data, compression, endian).unwrap();
+
+            let (_, read_value, read_compression, read_endian, _) =
+                read_auto_from_reader(&data[..]).unwrap();
+            assert_eq!(&read_value, value);
+            assert_eq!((read_compression, read_endian), (compression, endian));
+        }
+    }
+}
+
+#[test]
+fn test_read_auto_headerless() {
+    // The level.dat saved by the game, without its first 8 bytes.
+    let (name, value, compression, endian, header) =
+        read_auto(PathBuf::from("tests/data/bedrock_level_headerless.dat")).unwrap();
+    assert_eq!(
+        (compression, endian, header),
+        (Compression::Uncompressed, Endian::Little, false)
+    );
+
+    let (expected_name, expected) = read_from_file(
+        PathBuf::from("tests/data/bedrock_level.dat"),
+        Compression::Uncompressed,
+        Endian::Little,
+    )
+    .unwrap();
+    assert_eq!((name.as_str(), &value), (expected_name.as_str(), &expected));
+
+    let mut data = Vec::new();
+    NbtWriter::new(&mut data, Endian::Little)
+        .write_root(&name, &value)
+        .unwrap();
+    let (_, read_value, _, _, header) = read_auto_from_reader(&data[..]).unwrap();
+    assert_eq!(read_value, value);
+    assert!(!header);
+}
+
+#[test]
+fn test_read_auto_invalid_data() {
+    assert!(read_auto_from_reader(&[0x63, 0x61, 0x74][..]).is_err());
+    assert!(read_auto_from_reader(&[][..]).is_err());
+}
+
+#[test]
+fn test_error_context() {
+    let data = [
+        0x0A, 0x00, 0x00, // root compound
+        0x0A, 0x00, 0x05, b'L', b'e', b'v', b'e', b'l', // compound "Level"
+        0x09, 0x00, 0x08, b'S', b'e', b'c', b't', b'i', b'o', b'n', b's', // list "Sections"
+        0x0A, 0x00, 0x00, 0x00, 0x02, // of 2 compounds
+        0x00, // first section, empty
+        0x0D, // second section, invalid tag type
+    ];
+
+    let error = NbtReader::new(&data[..], Endian::Big)
+        .parse_data()
+        .unwrap_err();
+    assert!(matches!(
+        error.without_context(),
+        NbtError::InvalidTagType(0x0D)
+    ));
+    assert_eq!(error.offset(), Some(data.len() as u64));
+    assert_eq!(error.path().unwrap().to_string(), "Level.Sections[1]");
+    assert_eq!(
+        error.to_string(),
+        "Invalid tag type: 13 at byte 29 in Level.Sections[1]"
+    );
+
+    let error = NbtReader::new(&data[..data.len() - 1], Endian::Big)
+        .parse_data()
+        .unwrap_err();
+    assert!(matches!(error.without_context(), NbtError::UnexpectedEof));
+    assert_eq!(error.offset(), Some(data.len() as u64 - 1));
+
+    let error = read_from_reader(&data[..3], Compression::Uncompressed, Endian::Big).unwrap_err();
+    assert!(matches!(error.without_context(), NbtError::UnexpectedEof));
+    assert_eq!(error.path().unwrap().nodes().len(), 0);
+}
+
+#[test]
+fn test_parse_data_lenient() {
+    let data = std::fs::read("tests/data/java_level.dat").unwrap();
+    let (_, complete, problem) =
+        read_from_reader_lenient(&data[..], Compression::Gzip, Endian::Big).unwrap();
+    assert!(problem.is_none());
+
+    // A gzip stream cut off part way through still gives the values before the cut.
+    let (name, partial, problem) =
+        read_from_reader_lenient(&data[..data.len() * 3 / 4], Compression::Gzip, Endian::Big)
+            .unwrap();
+    assert_eq!(name, "");
+    let problem = problem.unwrap();
+    assert!(matches!(problem.without_context(), NbtError::UnexpectedEof));
+    assert!(problem.offset().is_some());
+
+    let data = partial.get("Data").unwrap();
+    assert!(!data.is_empty());
+    assert!(data.len() < complete.get("Data").unwrap().len());
+    for (key, value) in data.iter() {
+        // Every recovered value is either complete or a container that was still being read.
+        let original = complete.get("Data").unwrap().get(key).unwrap();
+        if !matches!(value, NbtValue::Compound(_) | NbtValue::List(_)) {
+            assert_eq!(value, original);
+        }
+    }
+
+    let corrupted = [
+        0x0A, 0x00, 0x00, // root compound
+        0x03, 0x00, 0x01, b'a', 0x00, 0x00, 0x00, 0x01, // int "a"
+        0x09, 0x00, 0x01, b'b', 0x03, 0x00, 0x00, 0x00, 0x03, // list "b" of 3 ints
+        0x00, 0x00, 0x00, 0x01, // first element
+        0x00, 0x00, // second element, truncated
+    ];
+    let (_, value, problem) = NbtReader::new(&corrupted[..], Endian::Big).parse_data_lenient();
+    assert_eq!(value.get("a"), Some(&NbtValue::Int(1)));
+    assert_eq!(
+        value.get("b"),
+        Some(&NbtValue::from(vec![NbtValue::Int(1)]))
+    );
+    assert_eq!(problem.unwrap().path().unwrap().to_string(), "b[1]");
+
+    let (_, value, problem) = NbtReader::new(&[0x0D][..], Endian::Big).parse_data_lenient();
+    assert_eq!(value, NbtValue::End);
+    assert!(matches!(
+        problem.unwrap().without_context(),
+        NbtError::InvalidTagType(0x0D)
+    ));
+}
+
+#[test]
+fn test_parse_huge_list_length() {
+    // A list of lists claiming i32::MAX compound elements, with no elements following.
+    let data = [
+        0x0A, 0x00, 0x00, // root compound
+        0x09, 0x00, 0x00, 0x0A, 0x7F, 0xFF, 0xFF, 0xFF, // list "" of i32::MAX compounds
+    ];
+
+    let error = NbtReader::new(&data[..], Endian::Big)
+        .parse_data()
+        .unwrap_err();
+    assert!(matches!(error.without_context(), NbtError::UnexpectedEof));
+
+    let (_, value, problem) = NbtReader::new(&data[..], Endian::Big).parse_data_lenient();
+    assert!(matches!(value.get(""), Some(NbtValue::List(_))));
+    assert!(problem.is_some());
+}
+
+#[test]
+fn test_read_concatenated_roots() {
+    let data = [
+        0x0A, 0x00, 0x01, b'a', // compound "a"
+        0x01, 0x00, 0x01, b'x', 0x05, // byte "x" = 5
+        0x00, // end of "a"
+        0x08, 0x00, 0x01, b'b', 0x00, 0x02, b'h', b'i', // string "b" = "hi"
+    ];
+
+    let mut reader = NbtReader::new(&data[..], Endian::Big);
+    let roots = reader.roots().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(roots.len(), 2);
+    assert_eq!(roots[0].0, "a");
+    assert_eq!(roots[0].1.get("x"), Some(&NbtValue::Byte(5)));
+    assert_eq!(roots[1], ("b".to_string(), NbtValue::from("hi")));
+    assert!(reader.next_root().unwrap().is_none());
+
+    // Data that ends between roots is a clean end, but data that ends inside one is an error.
+    for length in [0, 10] {
+        let mut reader = NbtReader::new(&data[..length], Endian::Big);
+        assert!(reader.roots().all(|root| root.is_ok()));
+    }
+    let mut reader = NbtReader::new(&data[..data.len() - 1], Endian::Big);
+    let roots: Vec<_> = reader.roots().collect();
+    assert_eq!(roots.len(), 2);
+    let error = roots[1].as_ref().unwrap_err();
+    assert!(matches!(error.without_context(), NbtError::UnexpectedEof));
+    assert_eq!(error.offset(), Some(17));
+}
@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use commandblock::nbt::{
+    parse_snbt, read_from_file, Compression, Endian, NbtError, NbtList, NbtValue,
+};
+
+#[test]
+fn test_parse_snbt_primitives() {
+    assert_eq!(parse_snbt("1b").unwrap(), NbtValue::Byte(1));
+    assert_eq!(parse_snbt("-3s").unwrap(), NbtValue::Short(-3));
+    assert_eq!(parse_snbt("42").unwrap(), NbtValue::Int(42));
+    assert_eq!(parse_snbt("42L").unwrap(), NbtValue::Long(42));
+    assert_eq!(parse_snbt("1.5f").unwrap(), NbtValue::Float(1.5));
+    assert_eq!(parse_snbt("1.5").unwrap(), NbtValue::Double(1.5));
+    assert_eq!(parse_snbt("2d").unwrap(), NbtValue::Double(2.0));
+    assert_eq!(parse_snbt("true").unwrap(), NbtValue::Byte(1));
+    assert_eq!(
+        parse_snbt("minecraft.stone").unwrap(),
+        NbtValue::String("minecraft.stone".to_string())
+    );
+    assert_eq!(
+        parse_snbt(r#""say \"hi\"""#).unwrap(),
+        NbtValue::String("say \"hi\"".to_string())
+    );
+    assert_eq!(
+        parse_snbt("'single'").unwrap(),
+        NbtValue::String("single".to_string())
+    );
+}
+
+#[test]
+fn test_parse_snbt_collections() {
+    let value = parse_snbt(r#"{ id: "minecraft:stone", Count: 1b, "odd key": [1, 2] }"#).unwrap();
+    assert_eq!(value.get("Count"), Some(&NbtValue::Byte(1)));
+    assert_eq!(
+        value.get("odd key"),
+        Some(&NbtValue::from(vec![NbtValue::Int(1), NbtValue::Int(2)]))
+    );
+
+    assert_eq!(
+        parse_snbt("[B;1b,2b]").unwrap(),
+        NbtValue::ByteArray(vec![1, 2])
+    );
+    assert_eq!(
+        parse_snbt("[I;1,2]").unwrap(),
+        NbtValue::IntArray(vec![1, 2])
+    );
+    assert_eq!(parse_snbt("[L;1L]").unwrap(), NbtValue::LongArray(vec![1]));
+    assert_eq!(
+        parse_snbt("[]").unwrap(),
+        NbtValue::List(NbtList::default())
+    );
+}
+
+#[test]
+fn test_parse_snbt_errors() {
+    assert!(matches!(parse_snbt("{a:1"), Err(NbtError::InvalidSnbt(_))));
+    assert!(matches!(
+        parse_snbt("{a:1} x"),
+        Err(NbtError::InvalidSnbt(_))
+    ));
+    assert!(matches!(
+        parse_snbt("[1,2b]"),
+        Err(NbtError::InvalidListType(0x01))
+    ));
+    assert!(matches!(
+        parse_snbt("[I;1,2b]"),
+        Err(NbtError::TypeMismatch(0x03, 0x01))
+    ));
+}
+
+#[test]
+fn test_snbt_round_trip() {
+    let (_, java) = read_from_file(
+        PathBuf::from("tests/data/java_level.dat"),
+        Compression::Gzip,
+        Endian::Big,
+    )
+    .unwrap();
+    let (_, bedrock) = read_from_file(
+        PathBuf::from("tests/data/bedrock_level.dat"),
+        Compression::Uncompressed,
+        Endian::Little,
+    )
+    .unwrap();
+
+    assert_eq!(parse_snbt(&java.to_snbt()).unwrap(), java);
+    assert_eq!(parse_snbt(&bedrock.to_snbt()).unwrap(), bedrock);
+}
+
+#[test]
+fn test_snbt_non_finite() {
+    let values = [
+        (NbtValue::Float(f32::INFINITY), "inff"),
+        (NbtValue::Float(f32::NEG_INFINITY), "-inff"),
+        (NbtValue::Double(f64::INFINITY), "infd"),
+        (NbtValue::Double(f64::NEG_INFINITY), "-infd"),
+    ];
+    for (value, snbt) in values {
+        assert_eq!(value.to_snbt(), snbt);
+        assert_eq!(parse_snbt(snbt).unwrap(), value);
+    }
+
+    // NaN is not equal to itself, so check the type and value separately.
+    assert_eq!(NbtValue::Float(f32::NAN).to_snbt(), "NaNf");
+    assert!(matches!(parse_snbt("NaNf").unwrap(), NbtValue::Float(v) if v.is_nan()));
+    assert_eq!(NbtValue::Double(f64::NAN).to_snbt(), "NaNd");
+    assert!(matches!(parse_snbt("NaNd").unwrap(), NbtValue::Double(v) if v.is_nan()));
+
+    // Other words ending in a type suffix stay strings.
+    assert_eq!(
+        parse_snbt("NaNb").unwrap(),
+        NbtValue::String("NaNb".to_string())
+    );
+    assert_eq!(
+        parse_snbt("infinityd").unwrap(),
+        NbtValue::String("infinityd".to_string())
+    );
+}
@@ -0,0 +1,156 @@
+use std::{fs::File, io::Read, path::PathBuf};
+
+use commandblock::nbt::{
+    read_from_file, Compression, Endian, NbtError, NbtReader, NbtValue, NbtVisitor, Visit,
+};
+use flate2::read::GzDecoder;
+
+fn java_level_bytes() -> Vec<u8> {
+    let file = File::open(PathBuf::from("tests/data/java_level.dat")).unwrap();
+    let mut bytes = Vec::new();
+    GzDecoder::new(file).read_to_end(&mut bytes).unwrap();
+    bytes
+}
+
+/// Counts the values it is given, optionally skipping one key and stopping at another.
+#[derive(Default)]
+struct Counter {
+    skip: Option<&'static str>,
+    stop: Option<&'static str>,
+    root: Option<String>,
+    keys: Vec<String>,
+    compounds: usize,
+    lists: usize,
+    ints: usize,
+    strings: usize,
+}
+
+impl NbtVisitor for Counter {
+    fn visit_root(&mut self, name: String, _tag_type: u8) -> Result<Visit, NbtError> {
+        self.root = Some(name);
+        Ok(Visit::Continue)
+    }
+
+    fn visit_compound_start(&mut self) -> Result<Visit, NbtError> {
+        self.compounds += 1;
+        Ok(Visit::Continue)
+    }
+
+    fn visit_key(&mut self, key: &str, _tag_type: u8) -> Result<Visit, NbtError> {
+        let action = if Some(key) == self.stop {
+            Visit::Stop
+        } else if Some(key) == self.skip {
+            Visit::Skip
+        } else {
+            Visit::Continue
+        };
+        self.keys.push(key.to_string());
+        Ok(action)
+    }
+
+    fn visit_list_start(&mut self, _tag_type: u8, _length: usize) -> Result<Visit, NbtError> {
+        self.lists += 1;
+        Ok(Visit::Continue)
+    }
+
+    fn visit_int(&mut self, _value: i32) -> Result<(), NbtError> {
+        self.ints += 1;
+        Ok(())
+    }
+
+    fn visit_string(&mut self, _value: String) -> Result<(), NbtError> {
+        self.strings += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_visit_data() {
+    let bytes = java_level_bytes();
+    let mut counter = Counter::default();
+    NbtReader::new(&bytes[..], Endian::Big)
+        .visit_data(&mut counter)
+        .unwrap();
+
+    assert_eq!(counter.root.as_deref(), Some(""));
+    assert!(counter.keys.contains(&"LevelName".to_string()));
+    assert!(counter.keys.contains(&"Inventory".to_string()));
+    assert!(counter.compounds > 1);
+    assert!(counter.lists > 1);
+    assert!(counter.ints > 0);
+    assert!(counter.strings > 0);
+}
+
+#[test]
+fn test_visit_skip_and_stop() {
+    let bytes = java_level_bytes();
+
+    let mut full = Counter::default();
+    NbtReader::new(&bytes[..], Endian::Big)
+        .visit_data(&mut full)
+        .unwrap();
+
+    let mut skipping = Counter {
+        skip: Some("Player"),
+        ..Counter::default()
+    };
+    NbtReader::new(&bytes[..], Endian::Big)
+        .visit_data(&mut skipping)
+        .unwrap();
+    assert!(skipping.keys.contains(&"Player".to_string()));
+    assert!(!skipping.keys.contains(&"Inventory".to_string()));
+    assert!(skipping.keys.len() < full.keys.len());
+
+    let mut stopping = Counter {
+        stop: Some("Data"),
+        ..Counter::default()
+    };
+    NbtReader::new(&bytes[..], Endian::Big)
+        .visit_data(&mut stopping)
+        .unwrap();
+    assert_eq!(stopping.keys, vec!["Data".to_string()]);
+    assert_eq!(stopping.strings, 0);
+}
+
+#[test]
+fn test_parse_matches_visitor_tree() {
+    let bytes = java_level_bytes();
+    let (_, parsed) = NbtReader::new(&bytes[..], Endian::Big)
+        .parse_data()
+        .unwrap();
+    let (_, expected) = read_from_file(
+        PathBuf::from("tests/data/java_level.dat"),
+        Compression::Gzip,
+        Endian::Big,
+    )
+    .unwrap();
+    assert_eq!(parsed, expected);
+}
+
+#[test]
+fn test_skip_nbt_value() {
+    let data = [
+        0x01, // tag type
+        0x00, 0x02, // key length
+        0x41, 0x42, // key value
+        0x7F, // value
+        0x09, // tag type
+        0x00, 0x01, // key length
+        0x43, // key value
+        0x03, // list tag type
+        0x00, 0x00, 0x00, 0x02, // list length
+        0x00, 0x00, 0x00, 0x01, // list values
+        0x00, 0x00, 0x00, 0x02, // list values
+        0x00, // end tag
+        0x2A, // trailing byte
+    ];
+    let mut reader = NbtReader::new(&data[..], Endian::Big);
+    reader.skip_nbt_value(0x0A).unwrap();
+    assert_eq!(reader.parse_nbt_value(0x01).unwrap(), NbtValue::Byte(42));
+
+    let truncated = [0x00, 0x00, 0x00, 0x04, 0x01];
+    let error = NbtReader::new(&truncated[..], Endian::Big)
+        .skip_nbt_value(0x07)
+        .unwrap_err();
+    assert!(matches!(error.without_context(), NbtError::UnexpectedEof));
+}
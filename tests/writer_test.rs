@@ -1,6 +1,9 @@
 use std::path::PathBuf;
 
-use commandblock::nbt::{read_from_file, write_to_file, Compression, Endian};
+use commandblock::nbt::{
+    read_from_file, read_from_reader, write_to_file, write_to_writer, Compression, Endian,
+    NbtError, NbtList, NbtReader, NbtRef, NbtValue, NbtWriter,
+};
 
 #[test]
 fn test_writing_new_uncompressed_data() {
@@ -15,7 +18,7 @@ fn test_writing_new_uncompressed_data() {
 
     write_to_file(
         None,
-        value.clone(),
+        &value,
         file_path.clone(),
         Compression::Gzip,
         Endian::Big,
@@ -29,3 +32,138 @@ fn test_writing_new_uncompressed_data() {
 
     std::fs::remove_file(file_path).unwrap();
 }
+
+#[test]
+fn test_writing_large_arrays() {
+    let values = [
+        NbtValue::ByteArray((0..100_000).map(|i| i as i8).collect()),
+        NbtValue::IntArray((0..100_000).map(|i| i * 7 - 3).collect()),
+        NbtValue::LongArray((0..100_000).map(|i| i * -123_456_789).collect()),
+    ];
+
+    for endian in [Endian::Big, Endian::Little] {
+        for value in &values {
+            let mut bytes = Vec::new();
+            NbtWriter::new(&mut bytes, endian)
+                .write_nbt_value(value)
+                .unwrap();
+
+            let read_value = NbtReader::new(&bytes[..], endian)
+                .parse_nbt_value(value.to_binary())
+                .unwrap();
+            assert_eq!(&read_value, value);
+        }
+    }
+}
+
+#[test]
+fn test_writing_borrowed_value() {
+    let (_, value) = read_from_file(
+        PathBuf::from("tests/data/java_level.dat"),
+        Compression::Gzip,
+        Endian::Big,
+    )
+    .unwrap();
+
+    for compression in [Compression::Uncompressed, Compression::Zlib] {
+        let mut borrowed = Vec::new();
+        write_to_writer(Some(""), &value, &mut borrowed, compression, Endian::Big).unwrap();
+
+        let mut owned = Vec::new();
+        write_to_writer(
+            Some(""),
+            value.clone(),
+            &mut owned,
+            compression,
+            Endian::Big,
+        )
+        .unwrap();
+
+        let (_, borrowed_value) =
+            read_from_reader(&borrowed[..], compression, Endian::Big).unwrap();
+        let (_, owned_value) = read_from_reader(&owned[..], compression, Endian::Big).unwrap();
+        assert_eq!(borrowed_value, value);
+        assert_eq!(owned_value, value);
+    }
+}
+
+#[test]
+fn test_empty_list_element_type_round_trip() {
+    let data = [
+        0x0A, 0x00, 0x00, // root compound
+        0x09, 0x00, 0x05, b'I', b't', b'e', b'm', b's', // list "Items"
+        0x0A, 0x00, 0x00, 0x00, 0x00, // of 0 compounds
+        0x00, // end of root
+    ];
+
+    let (name, value) = NbtReader::new(&data[..], Endian::Big).parse_data().unwrap();
+    let items = value.get("Items").unwrap().as_list().unwrap();
+    assert!(items.is_empty());
+    assert_eq!(items.element_type(), 0x0A);
+
+    let mut bytes = Vec::new();
+    NbtWriter::new(&mut bytes, Endian::Big)
+        .write_data(Some(&name), &value)
+        .unwrap();
+    assert_eq!(bytes, data);
+
+    let (_, borrowed) = NbtRef::parse_data(&data, Endian::Big).unwrap();
+    assert_eq!(borrowed.to_nbt_value(), value);
+}
+
+#[test]
+fn test_validate_before_writing() {
+    let mut items = NbtList::new(0x0A);
+    items.push(NbtValue::new());
+    items.push(NbtValue::Int(1));
+
+    let mut inventory = NbtValue::new();
+    inventory.insert("Items".to_string(), items);
+    let mut value = NbtValue::new();
+    value.insert("Inventory".to_string(), inventory);
+
+    let error = value.validate().unwrap_err();
+    assert!(matches!(
+        error.without_context(),
+        NbtError::InvalidListType(0x03)
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Invalid list type: 3 in Inventory.Items[1]"
+    );
+
+    let mut writer = Vec::new();
+    assert!(write_to_writer(None, &value, &mut writer, Compression::Gzip, Endian::Big).is_err());
+    assert!(writer.is_empty());
+
+    let mut value = NbtValue::new();
+    value.insert("name".to_string(), "a".repeat(65536));
+    let error = value.validate().unwrap_err();
+    assert!(matches!(
+        error.without_context(),
+        NbtError::StringTooLong(65536)
+    ));
+    assert_eq!(error.path().unwrap().to_string(), "name");
+
+    let mut value = NbtValue::new();
+    value.insert("b".repeat(70000), 1);
+    assert!(matches!(
+        value.validate().unwrap_err().without_context(),
+        NbtError::StringTooLong(70000)
+    ));
+
+    let mut value = NbtValue::new();
+    value.insert("name".to_string(), "a".repeat(65535));
+    assert!(value.validate().is_ok());
+    let mut writer = Vec::new();
+    write_to_writer(
+        None,
+        &value,
+        &mut writer,
+        Compression::Uncompressed,
+        Endian::Big,
+    )
+    .unwrap();
+    let (_, read) = read_from_reader(&writer[..], Compression::Uncompressed, Endian::Big).unwrap();
+    assert_eq!(read, value);
+}